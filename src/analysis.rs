@@ -1,14 +1,48 @@
+mod dangling_pointer;
+mod double_drop;
+mod ffi_unwind;
+mod interior_mutability;
+mod lifetime_aliasing;
+mod pin_soundness;
+mod reentrancy;
 mod send_sync_variance;
+mod static_mut;
+mod transmute_validity;
+mod unaligned_access;
+mod unchecked_precondition;
+mod uninit_exposure;
 mod unsafe_dataflow;
 mod unsafe_destructor;
+mod zeroed_validity;
 
 use snafu::{Error, ErrorCompat};
 
 use crate::report::ReportLevel;
 
+pub use dangling_pointer::{BehaviorFlag as DanglingPointerBehaviorFlag, DanglingPointerChecker};
+pub use double_drop::{BehaviorFlag as DoubleDropBehaviorFlag, DoubleDropChecker};
+pub use ffi_unwind::{BehaviorFlag as FfiUnwindBehaviorFlag, FfiUnwindChecker};
+pub use interior_mutability::{
+    BehaviorFlag as InteriorMutabilityBehaviorFlag, InteriorMutabilityChecker,
+};
+pub use lifetime_aliasing::{
+    BehaviorFlag as LifetimeAliasingBehaviorFlag, LifetimeAliasingChecker,
+};
+pub use pin_soundness::{BehaviorFlag as PinSoundnessBehaviorFlag, PinSoundnessChecker};
+pub use reentrancy::{BehaviorFlag as ReentrancyBehaviorFlag, ReentrancyChecker};
 pub use send_sync_variance::{BehaviorFlag as SendSyncBehaviorFlag, SendSyncVarianceChecker};
+pub use static_mut::{BehaviorFlag as StaticMutBehaviorFlag, StaticMutChecker};
+pub use transmute_validity::{
+    BehaviorFlag as TransmuteValidityBehaviorFlag, TransmuteValidityChecker,
+};
+pub use unaligned_access::{BehaviorFlag as UnalignedAccessBehaviorFlag, UnalignedAccessChecker};
+pub use unchecked_precondition::{
+    BehaviorFlag as UncheckedPreconditionBehaviorFlag, UncheckedPreconditionChecker,
+};
+pub use uninit_exposure::{BehaviorFlag as UninitExposureBehaviorFlag, UninitExposureChecker};
 pub use unsafe_dataflow::{BehaviorFlag as UnsafeDataflowBehaviorFlag, UnsafeDataflowChecker};
 pub use unsafe_destructor::UnsafeDestructorChecker;
+pub use zeroed_validity::{BehaviorFlag as ZeroedValidityBehaviorFlag, ZeroedValidityChecker};
 
 pub type AnalysisResult<'tcx, T> = Result<T, Box<dyn AnalysisError + 'tcx>>;
 
@@ -65,6 +99,19 @@ pub enum AnalysisKind {
     UnsafeDestructor,
     SendSyncVariance(SendSyncBehaviorFlag),
     UnsafeDataflow(UnsafeDataflowBehaviorFlag),
+    UninitExposure(UninitExposureBehaviorFlag),
+    TransmuteValidity(TransmuteValidityBehaviorFlag),
+    LifetimeAliasing(LifetimeAliasingBehaviorFlag),
+    UncheckedPrecondition(UncheckedPreconditionBehaviorFlag),
+    DanglingPointer(DanglingPointerBehaviorFlag),
+    DoubleDrop(DoubleDropBehaviorFlag),
+    ZeroedValidity(ZeroedValidityBehaviorFlag),
+    UnalignedAccess(UnalignedAccessBehaviorFlag),
+    StaticMut(StaticMutBehaviorFlag),
+    FfiUnwind(FfiUnwindBehaviorFlag),
+    Reentrancy(ReentrancyBehaviorFlag),
+    PinSoundness(PinSoundnessBehaviorFlag),
+    InteriorMutability(InteriorMutabilityBehaviorFlag),
 }
 
 trait IntoReportLevel {
@@ -98,6 +145,21 @@ impl Into<Cow<'static, str>> for AnalysisKind {
                 if sv_analyses.contains(SendSyncBehaviorFlag::RELAX_SYNC) {
                     v.push("RelaxSync")
                 }
+                if sv_analyses.contains(SendSyncBehaviorFlag::PHANTOM_UNSAFE_TRAIT) {
+                    v.push("PhantomUnsafeTrait")
+                }
+                if sv_analyses.contains(SendSyncBehaviorFlag::NAIVE_UNSAFE_TRAIT) {
+                    v.push("NaiveUnsafeTrait")
+                }
+                if sv_analyses.contains(SendSyncBehaviorFlag::PTR_LIKE_SEND) {
+                    v.push("PtrLikeSend")
+                }
+                if sv_analyses.contains(SendSyncBehaviorFlag::GUARD_SEND) {
+                    v.push("GuardSend")
+                }
+                if sv_analyses.contains(SendSyncBehaviorFlag::COVARIANT_MUT) {
+                    v.push("CovariantMut")
+                }
                 v.join("/").into()
             }
             AnalysisKind::UnsafeDataflow(bypass_kinds) => {
@@ -129,6 +191,210 @@ impl Into<Cow<'static, str>> for AnalysisKind {
                 if bypass_kinds.contains(UnsafeDataflowBehaviorFlag::VEC_SET_LEN) {
                     v.push("VecSetLen")
                 }
+                if bypass_kinds.contains(UnsafeDataflowBehaviorFlag::AWAIT_FLOW) {
+                    v.push("AwaitFlow")
+                }
+                v.join("/").into()
+            }
+            AnalysisKind::UninitExposure(uninit_kinds) => {
+                let mut v = vec!["UninitExposure:"];
+                if uninit_kinds.contains(UninitExposureBehaviorFlag::VEC_SET_LEN) {
+                    v.push("VecSetLen")
+                }
+                if uninit_kinds.contains(UninitExposureBehaviorFlag::ASSUME_INIT) {
+                    v.push("AssumeInit")
+                }
+                if uninit_kinds.contains(UninitExposureBehaviorFlag::MEM_UNINITIALIZED) {
+                    v.push("MemUninitialized")
+                }
+                if uninit_kinds.contains(UninitExposureBehaviorFlag::ALLOC) {
+                    v.push("Alloc")
+                }
+                v.join("/").into()
+            }
+            AnalysisKind::TransmuteValidity(transmute_kinds) => {
+                let mut v = vec!["TransmuteValidity:"];
+                if transmute_kinds.contains(TransmuteValidityBehaviorFlag::SIZE_MISMATCH) {
+                    v.push("SizeMismatch")
+                }
+                if transmute_kinds.contains(TransmuteValidityBehaviorFlag::ALIGN_MISMATCH) {
+                    v.push("AlignMismatch")
+                }
+                if transmute_kinds.contains(TransmuteValidityBehaviorFlag::INVALID_VALUE) {
+                    v.push("InvalidValue")
+                }
+                if transmute_kinds.contains(TransmuteValidityBehaviorFlag::LIFETIME_EXTENSION) {
+                    v.push("LifetimeExtension")
+                }
+                if transmute_kinds.contains(TransmuteValidityBehaviorFlag::REF_TO_MUT) {
+                    v.push("RefToMut")
+                }
+                v.join("/").into()
+            }
+            AnalysisKind::LifetimeAliasing(aliasing_kinds) => {
+                let mut v = vec!["LifetimeAliasing:"];
+                if aliasing_kinds.contains(LifetimeAliasingBehaviorFlag::UNBOUNDED_LIFETIME) {
+                    v.push("UnboundedLifetime")
+                }
+                if aliasing_kinds.contains(LifetimeAliasingBehaviorFlag::SHARED_TO_MUT) {
+                    v.push("SharedToMut")
+                }
+                v.join("/").into()
+            }
+            AnalysisKind::UncheckedPrecondition(precondition_kinds) => {
+                let mut v = vec!["UncheckedPrecondition:"];
+                if precondition_kinds.contains(UncheckedPreconditionBehaviorFlag::GET_UNCHECKED) {
+                    v.push("GetUnchecked")
+                }
+                if precondition_kinds.contains(UncheckedPreconditionBehaviorFlag::FROM_RAW_PARTS) {
+                    v.push("FromRawParts")
+                }
+                if precondition_kinds.contains(UncheckedPreconditionBehaviorFlag::PTR_OFFSET) {
+                    v.push("PtrOffset")
+                }
+                if precondition_kinds.contains(UncheckedPreconditionBehaviorFlag::SET_LEN) {
+                    v.push("SetLen")
+                }
+                if precondition_kinds
+                    .contains(UncheckedPreconditionBehaviorFlag::FROM_UTF8_UNCHECKED)
+                {
+                    v.push("FromUtf8Unchecked")
+                }
+                v.join("/").into()
+            }
+            AnalysisKind::DanglingPointer(dangling_kinds) => {
+                let mut v = vec!["DanglingPointer:"];
+                if dangling_kinds.contains(DanglingPointerBehaviorFlag::USE_AFTER_DROP) {
+                    v.push("UseAfterDrop")
+                }
+                if dangling_kinds.contains(DanglingPointerBehaviorFlag::USE_AFTER_SCOPE) {
+                    v.push("UseAfterScope")
+                }
+                if dangling_kinds.contains(DanglingPointerBehaviorFlag::RETURN_LOCAL) {
+                    v.push("ReturnLocal")
+                }
+                if dangling_kinds.contains(DanglingPointerBehaviorFlag::STORE_LOCAL) {
+                    v.push("StoreLocal")
+                }
+                v.join("/").into()
+            }
+            AnalysisKind::DoubleDrop(duplication_kinds) => {
+                let mut v = vec!["DoubleDrop:"];
+                if duplication_kinds.contains(DoubleDropBehaviorFlag::PTR_READ) {
+                    v.push("PtrRead")
+                }
+                if duplication_kinds.contains(DoubleDropBehaviorFlag::BOX_FROM_RAW) {
+                    v.push("BoxFromRaw")
+                }
+                if duplication_kinds.contains(DoubleDropBehaviorFlag::VEC_FROM_RAW_PARTS) {
+                    v.push("VecFromRawParts")
+                }
+                if duplication_kinds.contains(DoubleDropBehaviorFlag::TRANSMUTE_COPY) {
+                    v.push("TransmuteCopy")
+                }
+                v.join("/").into()
+            }
+            AnalysisKind::ZeroedValidity(zeroed_kinds) => {
+                let mut v = vec!["ZeroedValidity:"];
+                if zeroed_kinds.contains(ZeroedValidityBehaviorFlag::INVALID_ZERO) {
+                    v.push("InvalidZero")
+                }
+                if zeroed_kinds.contains(ZeroedValidityBehaviorFlag::GENERIC_ZERO) {
+                    v.push("GenericZero")
+                }
+                if zeroed_kinds.contains(ZeroedValidityBehaviorFlag::MEM_ZEROED) {
+                    v.push("MemZeroed")
+                }
+                if zeroed_kinds.contains(ZeroedValidityBehaviorFlag::ASSUME_INIT) {
+                    v.push("AssumeInit")
+                }
+                if zeroed_kinds.contains(ZeroedValidityBehaviorFlag::ALLOC_ZEROED) {
+                    v.push("AllocZeroed")
+                }
+                v.join("/").into()
+            }
+            AnalysisKind::UnalignedAccess(unaligned_kinds) => {
+                let mut v = vec!["UnalignedAccess:"];
+                if unaligned_kinds.contains(UnalignedAccessBehaviorFlag::GENERIC) {
+                    v.push("Generic")
+                }
+                if unaligned_kinds.contains(UnalignedAccessBehaviorFlag::DEREF) {
+                    v.push("Deref")
+                }
+                if unaligned_kinds.contains(UnalignedAccessBehaviorFlag::PTR_READ) {
+                    v.push("PtrRead")
+                }
+                if unaligned_kinds.contains(UnalignedAccessBehaviorFlag::FROM_RAW_PARTS) {
+                    v.push("FromRawParts")
+                }
+                v.join("/").into()
+            }
+            AnalysisKind::StaticMut(static_mut_kinds) => {
+                let mut v = vec!["StaticMut:"];
+                if static_mut_kinds.contains(StaticMutBehaviorFlag::READ) {
+                    v.push("Read")
+                }
+                if static_mut_kinds.contains(StaticMutBehaviorFlag::WRITE) {
+                    v.push("Write")
+                }
+                if static_mut_kinds.contains(StaticMutBehaviorFlag::ESCAPE_REF) {
+                    v.push("EscapeRef")
+                }
+                v.join("/").into()
+            }
+            AnalysisKind::FfiUnwind(unwind_kinds) => {
+                let mut v = vec!["FfiUnwind:"];
+                if unwind_kinds.contains(FfiUnwindBehaviorFlag::UNRESOLVABLE_GENERIC) {
+                    v.push("UnresolvableGeneric")
+                }
+                if unwind_kinds.contains(FfiUnwindBehaviorFlag::TRAIT_OBJECT) {
+                    v.push("TraitObject")
+                }
+                if unwind_kinds.contains(FfiUnwindBehaviorFlag::FN_PTR) {
+                    v.push("FnPtr")
+                }
+                v.join("/").into()
+            }
+            AnalysisKind::Reentrancy(reentrancy_kinds) => {
+                let mut v = vec!["Reentrancy:"];
+                if reentrancy_kinds.contains(ReentrancyBehaviorFlag::MUT_REF) {
+                    v.push("MutRef")
+                }
+                if reentrancy_kinds.contains(ReentrancyBehaviorFlag::RAW_MUT) {
+                    v.push("RawMut")
+                }
+                if reentrancy_kinds.contains(ReentrancyBehaviorFlag::CLOSURE) {
+                    v.push("Closure")
+                }
+                if reentrancy_kinds.contains(ReentrancyBehaviorFlag::SHARED_OWNER) {
+                    v.push("SharedOwner")
+                }
+                v.join("/").into()
+            }
+            AnalysisKind::PinSoundness(pin_kinds) => {
+                let mut v = vec!["PinSoundness:"];
+                if pin_kinds.contains(PinSoundnessBehaviorFlag::MOVE_AFTER_PIN) {
+                    v.push("MoveAfterPin")
+                }
+                if pin_kinds.contains(PinSoundnessBehaviorFlag::UNPIN_IMPL) {
+                    v.push("UnpinImpl")
+                }
+                if pin_kinds.contains(PinSoundnessBehaviorFlag::DROP_MOVE) {
+                    v.push("DropMove")
+                }
+                v.join("/").into()
+            }
+            AnalysisKind::InteriorMutability(write_kinds) => {
+                let mut v = vec!["InteriorMutability:"];
+                if write_kinds.contains(InteriorMutabilityBehaviorFlag::UNSAFE_CELL_WRITE) {
+                    v.push("UnsafeCellWrite")
+                }
+                if write_kinds.contains(InteriorMutabilityBehaviorFlag::CELL_WRITE) {
+                    v.push("CellWrite")
+                }
+                if write_kinds.contains(InteriorMutabilityBehaviorFlag::RAW_PTR_WRITE) {
+                    v.push("RawPtrWrite")
+                }
                 v.join("/").into()
             }
         }
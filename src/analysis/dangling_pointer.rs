@@ -0,0 +1,406 @@
+//! Dangling pointer (use-after-scope / use-after-drop) detector
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::BodyId;
+use rustc_middle::mir::visit::{PlaceContext, Visitor};
+use rustc_middle::mir::{
+    self, Location, Operand, Rvalue, StatementKind, TerminatorKind, RETURN_PLACE,
+};
+use rustc_middle::ty::TypingEnv;
+use rustc_span::Span;
+
+use snafu::{Backtrace, Snafu};
+use termcolor::Color;
+
+use crate::prelude::*;
+use crate::{
+    analysis::{AnalysisKind, IntoReportLevel},
+    ir,
+    paths::*,
+    report::{Report, ReportLevel},
+    utils,
+    visitor::ContainsUnsafe,
+};
+
+#[derive(Debug, Snafu)]
+pub enum DanglingPointerError {
+    InvalidSpan { backtrace: Backtrace },
+}
+
+impl AnalysisError for DanglingPointerError {
+    fn kind(&self) -> AnalysisErrorKind {
+        use DanglingPointerError::*;
+        match self {
+            InvalidSpan { .. } => AnalysisErrorKind::Unreachable,
+        }
+    }
+}
+
+pub struct DanglingPointerChecker<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+}
+
+impl<'tcx> DanglingPointerChecker<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+        DanglingPointerChecker { rcx }
+    }
+
+    pub fn analyze(self) {
+        let tcx = self.rcx.tcx();
+        let hir_map = tcx.hir();
+
+        // Iterates all (type, related function) pairs
+        for (_ty_hir_id, (body_id, related_item_span)) in self.rcx.types_with_related_items() {
+            if let Some(status) =
+                inner::DanglingPointerBodyAnalyzer::analyze_body(self.rcx, body_id)
+            {
+                let behavior_flag = status.behavior_flag();
+                if !behavior_flag.is_empty()
+                    && behavior_flag.report_level() >= self.rcx.report_level()
+                {
+                    let mut color_span = unwrap_or!(
+                        utils::ColorSpan::new(tcx, related_item_span).context(InvalidSpan) => continue
+                    );
+
+                    for &span in status.invalidation_spans() {
+                        color_span.add_sub_span(Color::Yellow, span);
+                    }
+
+                    for &span in status.dangling_use_spans() {
+                        color_span.add_sub_span(Color::Red, span);
+                    }
+
+                    rudra_report(Report::with_color_span(
+                        tcx,
+                        behavior_flag.report_level(),
+                        AnalysisKind::DanglingPointer(behavior_flag),
+                        format!(
+                            "Potential dangling pointer in `{}`",
+                            tcx.def_path_str(hir_map.body_owner_def_id(body_id).to_def_id())
+                        ),
+                        &color_span,
+                    ))
+                }
+            }
+        }
+    }
+}
+
+mod inner {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct DanglingPointerStatus {
+        invalidations: Vec<Span>,
+        dangling_uses: Vec<Span>,
+        behavior_flag: BehaviorFlag,
+    }
+
+    impl DanglingPointerStatus {
+        pub fn behavior_flag(&self) -> BehaviorFlag {
+            self.behavior_flag
+        }
+
+        pub fn invalidation_spans(&self) -> &Vec<Span> {
+            &self.invalidations
+        }
+
+        pub fn dangling_use_spans(&self) -> &Vec<Span> {
+            &self.dangling_uses
+        }
+    }
+
+    pub struct DanglingPointerBodyAnalyzer<'a, 'tcx> {
+        rcx: RudraCtxt<'tcx>,
+        body: &'a ir::Body<'tcx>,
+        typing_env: TypingEnv<'tcx>,
+        status: DanglingPointerStatus,
+    }
+
+    impl<'a, 'tcx> DanglingPointerBodyAnalyzer<'a, 'tcx> {
+        fn new(rcx: RudraCtxt<'tcx>, body: &'a ir::Body<'tcx>) -> Self {
+            DanglingPointerBodyAnalyzer {
+                rcx,
+                body,
+                typing_env: body.original.typing_env(rcx.tcx()),
+                status: Default::default(),
+            }
+        }
+
+        pub fn analyze_body(
+            rcx: RudraCtxt<'tcx>,
+            body_id: BodyId,
+        ) -> Option<DanglingPointerStatus> {
+            let hir_map = rcx.tcx().hir();
+            let body_did = hir_map.body_owner_def_id(body_id).to_def_id();
+
+            if ContainsUnsafe::contains_unsafe(rcx.tcx(), body_id) {
+                match rcx.translate_body(body_did).as_ref() {
+                    Err(e) => {
+                        // MIR is not available for def - log it and continue
+                        e.log();
+                        None
+                    }
+                    Ok(body) => {
+                        let body_analyzer = DanglingPointerBodyAnalyzer::new(rcx, body);
+                        Some(body_analyzer.analyze())
+                    }
+                }
+            } else {
+                // Raw pointers can only be used in unsafe code
+                Some(Default::default())
+            }
+        }
+
+        fn analyze(mut self) -> DanglingPointerStatus {
+            let tcx = self.rcx.tcx();
+            let borrowed_locals = self.borrowed_locals();
+
+            for local in borrowed_locals {
+                let local_ty = self.body.local_decls[local.index()].ty;
+                if local_ty.is_ref() || local_ty.is_raw_ptr() {
+                    // Pointers derived from a reference point to its referent
+                    continue;
+                }
+
+                // Raw pointers into `local` or into memory owned by `local`
+                let owned_ptrs = self
+                    .body
+                    .derived_locals(local)
+                    .into_iter()
+                    .filter(|&ptr| self.body.local_decls[ptr.index()].ty.is_raw_ptr())
+                    .collect::<FxHashSet<_>>();
+                if owned_ptrs.is_empty() {
+                    continue;
+                }
+
+                // Raw pointers into the stack slot of `local`.
+                // Pointers into an array are as short-lived as the array itself.
+                let stack_ptrs = if local_ty.is_array() {
+                    owned_ptrs.clone()
+                } else {
+                    owned_ptrs
+                        .iter()
+                        .copied()
+                        .filter(|&ptr| self.body.borrowed_root(ptr) == local)
+                        .collect::<FxHashSet<_>>()
+                };
+
+                // Pointers that outlive the function
+                self.check_escapes(&stack_ptrs);
+
+                for (block_idx, block) in self.body.basic_blocks.iter().enumerate() {
+                    if block.is_cleanup {
+                        continue;
+                    }
+
+                    for (statement_idx, statement) in block.statements.iter().enumerate() {
+                        if let StatementKind::StorageDead(dead_local) = statement.kind {
+                            if dead_local == local && !stack_ptrs.is_empty() {
+                                self.check_uses_after(
+                                    local,
+                                    &stack_ptrs,
+                                    (block_idx, statement_idx + 1),
+                                    statement.source_info.span,
+                                    BehaviorFlag::USE_AFTER_SCOPE,
+                                );
+                            }
+                        }
+                    }
+
+                    let terminator = &block.terminator.original;
+                    let dropped = match &terminator.kind {
+                        TerminatorKind::Drop { place, .. } => place.as_local() == Some(local),
+                        TerminatorKind::Call { args, .. } => {
+                            // `mem::drop(local)`
+                            matches!(
+                                block.terminator.kind,
+                                ir::TerminatorKind::StaticCall { callee_did, .. }
+                                    if tcx.ext().match_def_path(callee_did, &MEM_DROP)
+                            ) && matches!(
+                                args.first().map(|arg| &arg.node),
+                                Some(Operand::Move(place)) if place.as_local() == Some(local)
+                            )
+                        }
+                        _ => false,
+                    };
+                    if dropped && local_ty.needs_drop(tcx, self.typing_env) {
+                        for successor in terminator.successors() {
+                            self.check_uses_after(
+                                local,
+                                &owned_ptrs,
+                                (successor.index(), 0),
+                                terminator.source_info.span,
+                                BehaviorFlag::USE_AFTER_DROP,
+                            );
+                        }
+                    }
+                }
+            }
+
+            self.status
+        }
+
+        /// Locals that are borrowed or whose address is taken.
+        fn borrowed_locals(&self) -> FxHashSet<mir::Local> {
+            self.body
+                .statements()
+                .filter_map(|statement| match &statement.kind {
+                    StatementKind::Assign(box (
+                        _,
+                        Rvalue::Ref(_, _, place) | Rvalue::RawPtr(_, place),
+                    )) if !place.is_indirect_first_projection() && place.local != RETURN_PLACE => {
+                        Some(place.local)
+                    }
+                    _ => None,
+                })
+                .collect()
+        }
+
+        /// Reports pointers into the stack frame that are returned
+        /// or stored behind a reference or a pointer parameter.
+        fn check_escapes(&mut self, stack_ptrs: &FxHashSet<mir::Local>) {
+            let arg_count = self.body.original.arg_count;
+            let is_ptr = |operand: &Operand<'tcx>| {
+                operand.place().is_some_and(|place| {
+                    place.projection.is_empty() && stack_ptrs.contains(&place.local)
+                })
+            };
+
+            for statement in self.body.statements() {
+                if let StatementKind::Assign(box (place, rvalue)) = &statement.kind {
+                    let stores_ptr = match rvalue {
+                        Rvalue::Use(operand) | Rvalue::Cast(_, operand, _) => is_ptr(operand),
+                        Rvalue::Aggregate(_, operands) => operands.iter().any(is_ptr),
+                        _ => false,
+                    };
+                    if !stores_ptr {
+                        continue;
+                    }
+
+                    let flag = if place.local == RETURN_PLACE {
+                        BehaviorFlag::RETURN_LOCAL
+                    } else if place.is_indirect_first_projection()
+                        && (1..=arg_count).contains(&self.body.borrowed_root(place.local).index())
+                    {
+                        BehaviorFlag::STORE_LOCAL
+                    } else {
+                        continue;
+                    };
+
+                    self.status.behavior_flag |= flag;
+                    self.status.dangling_uses.push(statement.source_info.span);
+                }
+            }
+        }
+
+        /// Searches the paths starting at `start` for uses of `ptrs`
+        /// before `local` is brought back to life.
+        fn check_uses_after(
+            &mut self,
+            local: mir::Local,
+            ptrs: &FxHashSet<mir::Local>,
+            start: (usize, usize),
+            invalidation_span: Span,
+            flag: BehaviorFlag,
+        ) {
+            let mut uses = Vec::new();
+            let mut visited = FxHashSet::default();
+            let mut worklist = vec![start];
+
+            'paths: while let Some((block_idx, first_statement)) = worklist.pop() {
+                let block = &self.body.basic_blocks[block_idx];
+                if block.is_cleanup || (first_statement == 0 && !visited.insert(block_idx)) {
+                    continue;
+                }
+
+                for statement in block.statements.iter().skip(first_statement) {
+                    match &statement.kind {
+                        StatementKind::StorageLive(live_local) if *live_local == local => {
+                            continue 'paths
+                        }
+                        StatementKind::Assign(box (place, _))
+                            if place.as_local() == Some(local) =>
+                        {
+                            continue 'paths
+                        }
+                        _ => (),
+                    }
+
+                    let mut deref_finder = DerefFinder { ptrs, found: false };
+                    deref_finder.visit_statement(statement, Location::START);
+                    if deref_finder.found {
+                        uses.push(statement.source_info.span);
+                    }
+                }
+
+                let terminator = &block.terminator.original;
+                let used = match &terminator.kind {
+                    TerminatorKind::Call { args, .. } => args.iter().any(|arg| {
+                        arg.node
+                            .place()
+                            .is_some_and(|place| ptrs.contains(&place.local))
+                    }),
+                    TerminatorKind::Return => ptrs.contains(&RETURN_PLACE),
+                    _ => false,
+                };
+                if used {
+                    uses.push(terminator.source_info.span);
+                }
+
+                for successor in terminator.successors() {
+                    worklist.push((successor.index(), 0));
+                }
+            }
+
+            if !uses.is_empty() {
+                self.status.behavior_flag |= flag;
+                self.status.invalidations.push(invalidation_span);
+                self.status.dangling_uses.extend(uses);
+            }
+        }
+    }
+
+    /// Checks whether a statement dereferences one of `ptrs`.
+    struct DerefFinder<'p> {
+        ptrs: &'p FxHashSet<mir::Local>,
+        found: bool,
+    }
+
+    impl<'tcx> Visitor<'tcx> for DerefFinder<'_> {
+        fn visit_place(
+            &mut self,
+            place: &mir::Place<'tcx>,
+            _context: PlaceContext,
+            _location: Location,
+        ) {
+            if place.is_indirect_first_projection() && self.ptrs.contains(&place.local) {
+                self.found = true;
+            }
+        }
+    }
+}
+
+bitflags! {
+    #[derive(Default)]
+    pub struct BehaviorFlag: u8 {
+        // Pointer into a value is used after the value is dropped
+        const USE_AFTER_DROP = 0b00000001;
+        // Pointer to a local is used after its `StorageDead`
+        const USE_AFTER_SCOPE = 0b00000010;
+        // Pointer to a local is returned
+        const RETURN_LOCAL = 0b00000100;
+        // Pointer to a local is stored behind a parameter
+        const STORE_LOCAL = 0b00001000;
+    }
+}
+
+impl IntoReportLevel for BehaviorFlag {
+    fn report_level(&self) -> ReportLevel {
+        if *self == BehaviorFlag::STORE_LOCAL {
+            // The stored pointer may be overwritten before returning
+            ReportLevel::Warning
+        } else {
+            ReportLevel::Error
+        }
+    }
+}
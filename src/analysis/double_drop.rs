@@ -0,0 +1,453 @@
+//! Double drop detector for duplicated ownership on normal (non-panic) paths
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir::BodyId;
+use rustc_middle::mir::{self, Operand, Rvalue, StatementKind, TerminatorKind};
+use rustc_middle::ty::{Ty, TypingEnv};
+use rustc_span::Span;
+
+use snafu::{Backtrace, Snafu};
+use termcolor::Color;
+
+use crate::prelude::*;
+use crate::{
+    analysis::{AnalysisKind, IntoReportLevel},
+    ir,
+    paths::{self, *},
+    report::{Report, ReportLevel},
+    utils,
+    visitor::ContainsUnsafe,
+};
+
+#[derive(Debug, Snafu)]
+pub enum DoubleDropError {
+    InvalidSpan { backtrace: Backtrace },
+}
+
+impl AnalysisError for DoubleDropError {
+    fn kind(&self) -> AnalysisErrorKind {
+        use DoubleDropError::*;
+        match self {
+            InvalidSpan { .. } => AnalysisErrorKind::Unreachable,
+        }
+    }
+}
+
+pub struct DoubleDropChecker<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+}
+
+impl<'tcx> DoubleDropChecker<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+        DoubleDropChecker { rcx }
+    }
+
+    pub fn analyze(self) {
+        let tcx = self.rcx.tcx();
+        let hir_map = tcx.hir();
+
+        // Iterates all (type, related function) pairs
+        for (_ty_hir_id, (body_id, related_item_span)) in self.rcx.types_with_related_items() {
+            if let Some(status) = inner::DoubleDropBodyAnalyzer::analyze_body(self.rcx, body_id) {
+                let behavior_flag = status.behavior_flag();
+                if !behavior_flag.is_empty()
+                    && behavior_flag.report_level() >= self.rcx.report_level()
+                {
+                    let mut color_span = unwrap_or!(
+                        utils::ColorSpan::new(tcx, related_item_span).context(InvalidSpan) => continue
+                    );
+
+                    for &span in status.duplication_spans() {
+                        color_span.add_sub_span(Color::Yellow, span);
+                    }
+
+                    for &span in status.drop_spans() {
+                        color_span.add_sub_span(Color::Red, span);
+                    }
+
+                    rudra_report(Report::with_color_span(
+                        tcx,
+                        behavior_flag.report_level(),
+                        AnalysisKind::DoubleDrop(behavior_flag),
+                        format!(
+                            "Potential double drop in `{}`",
+                            tcx.def_path_str(hir_map.body_owner_def_id(body_id).to_def_id())
+                        ),
+                        &color_span,
+                    ))
+                }
+            }
+        }
+    }
+}
+
+mod inner {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct DoubleDropStatus {
+        duplications: Vec<Span>,
+        drops: Vec<Span>,
+        behavior_flag: BehaviorFlag,
+    }
+
+    impl DoubleDropStatus {
+        pub fn behavior_flag(&self) -> BehaviorFlag {
+            self.behavior_flag
+        }
+
+        pub fn duplication_spans(&self) -> &Vec<Span> {
+            &self.duplications
+        }
+
+        pub fn drop_spans(&self) -> &Vec<Span> {
+            &self.drops
+        }
+    }
+
+    /// A bitwise copy of an owned value made at the terminator of `block`.
+    struct Duplication {
+        block: usize,
+        /// The new owner
+        copy: mir::Local,
+        /// The pointer the value was copied from
+        pointer_root: mir::Local,
+        /// Owned locals that the source pointer points into
+        owners: Vec<mir::Local>,
+        flag: BehaviorFlag,
+    }
+
+    pub struct DoubleDropBodyAnalyzer<'a, 'tcx> {
+        rcx: RudraCtxt<'tcx>,
+        body: &'a ir::Body<'tcx>,
+        typing_env: TypingEnv<'tcx>,
+        status: DoubleDropStatus,
+    }
+
+    impl<'a, 'tcx> DoubleDropBodyAnalyzer<'a, 'tcx> {
+        fn new(rcx: RudraCtxt<'tcx>, body: &'a ir::Body<'tcx>) -> Self {
+            DoubleDropBodyAnalyzer {
+                rcx,
+                body,
+                typing_env: body.original.typing_env(rcx.tcx()),
+                status: Default::default(),
+            }
+        }
+
+        pub fn analyze_body(rcx: RudraCtxt<'tcx>, body_id: BodyId) -> Option<DoubleDropStatus> {
+            let hir_map = rcx.tcx().hir();
+            let body_did = hir_map.body_owner_def_id(body_id).to_def_id();
+
+            if ContainsUnsafe::contains_unsafe(rcx.tcx(), body_id) {
+                match rcx.translate_body(body_did).as_ref() {
+                    Err(e) => {
+                        // MIR is not available for def - log it and continue
+                        e.log();
+                        None
+                    }
+                    Ok(body) => {
+                        let body_analyzer = DoubleDropBodyAnalyzer::new(rcx, body);
+                        Some(body_analyzer.analyze())
+                    }
+                }
+            } else {
+                // Ownership can only be duplicated in unsafe code
+                Some(Default::default())
+            }
+        }
+
+        fn analyze(mut self) -> DoubleDropStatus {
+            let owned_aliases = self.owned_aliases();
+            let duplications = self.collect_duplications(&owned_aliases);
+
+            for (idx, duplication) in duplications.iter().enumerate() {
+                let mut barriers = FxHashSet::default();
+                for owner in duplication.owners.iter() {
+                    barriers.extend(self.reinitializations(&owned_aliases[owner]));
+                }
+                let reachable = self.body.reachable_blocks(duplication.block, &barriers);
+
+                // Returning the copy hands it to the caller, which drops it
+                let mut copy_drops = self.drops_of(duplication.copy);
+                copy_drops.extend(self.escapes_of(duplication.copy));
+                copy_drops.retain(|block| reachable.contains(block));
+                if copy_drops.is_empty() {
+                    continue;
+                }
+
+                // Drops of the other owners of the same value
+                let mut other_drops = Vec::new();
+                for &owner in duplication.owners.iter() {
+                    other_drops.extend(self.drops_of(owner));
+                }
+                other_drops.extend(self.drop_in_places(duplication.pointer_root));
+                for (other_idx, other) in duplications.iter().enumerate() {
+                    if other_idx != idx
+                        && (other.pointer_root == duplication.pointer_root
+                            || other.owners.iter().any(|o| duplication.owners.contains(o)))
+                    {
+                        other_drops.extend(self.drops_of(other.copy));
+                    }
+                }
+                other_drops.retain(|block| reachable.contains(block));
+
+                // Both drops must be on a single path
+                let mut double_drops = Vec::new();
+                for &copy_drop in copy_drops.iter() {
+                    let after_copy_drop = self.body.reachable_blocks(copy_drop, &barriers);
+                    for &other_drop in other_drops.iter() {
+                        if copy_drop == other_drop
+                            || after_copy_drop.contains(&other_drop)
+                            || self
+                                .body
+                                .reachable_blocks(other_drop, &barriers)
+                                .contains(&copy_drop)
+                        {
+                            double_drops.push(copy_drop);
+                            double_drops.push(other_drop);
+                        }
+                    }
+                }
+
+                if !double_drops.is_empty() {
+                    double_drops.sort_unstable();
+                    double_drops.dedup();
+                    self.status.behavior_flag |= duplication.flag;
+                    self.status
+                        .duplications
+                        .push(self.terminator_span(duplication.block));
+                    for block in double_drops {
+                        self.status.drops.push(self.terminator_span(block));
+                    }
+                }
+            }
+
+            self.status
+        }
+
+        /// Maps each local that owns a value with drop glue to the locals that may point into it.
+        fn owned_aliases(&self) -> FxHashMap<mir::Local, FxHashSet<mir::Local>> {
+            (0..self.body.local_decls.len())
+                .map(mir::Local::from_usize)
+                .filter(|&local| {
+                    let ty = self.body.local_decls[local.index()].ty;
+                    !ty.is_ref() && !ty.is_raw_ptr() && self.needs_drop(ty)
+                })
+                .map(|local| (local, self.body.derived_locals(local)))
+                .collect()
+        }
+
+        fn collect_duplications(
+            &self,
+            owned_aliases: &FxHashMap<mir::Local, FxHashSet<mir::Local>>,
+        ) -> Vec<Duplication> {
+            let ext = self.rcx.tcx().ext();
+            let mut duplications = Vec::new();
+
+            for (block, terminator) in self.body.terminators().enumerate() {
+                if_chain! {
+                    if let ir::TerminatorKind::StaticCall {
+                        callee_did,
+                        ref args,
+                        destination: Some((place, _)),
+                        ..
+                    } = terminator.kind;
+                    if let Some(&flag) = DUPLICATION_MAP.get(&ext.get_def_path(callee_did));
+                    if let Some(src) = args.first().and_then(Operand::place);
+                    let copy = place.local;
+                    if self.needs_drop(self.body.local_decls[copy.index()].ty);
+                    then {
+                        let mut owners = owned_aliases
+                            .iter()
+                            .filter(|(&owner, aliases)| owner != copy && aliases.contains(&src.local))
+                            .map(|(&owner, _)| owner)
+                            .collect::<Vec<_>>();
+                        owners.sort_unstable();
+                        duplications.push(Duplication {
+                            block,
+                            copy,
+                            pointer_root: self.body.borrowed_root(src.local),
+                            owners,
+                            flag,
+                        });
+                    }
+                }
+            }
+
+            duplications
+        }
+
+        fn needs_drop(&self, ty: Ty<'tcx>) -> bool {
+            ty.needs_drop(self.rcx.tcx(), self.typing_env)
+        }
+
+        /// Blocks that drop `local` (`Drop` terminators and `mem::drop(local)`),
+        /// following moves of `local` into other locals.
+        fn drops_of(&self, local: mir::Local) -> Vec<usize> {
+            let ext = self.rcx.tcx().ext();
+            let owners = self.moved_into(local);
+            self.body
+                .basic_blocks
+                .iter()
+                .enumerate()
+                .filter(|(_, block)| match &block.terminator.original.kind {
+                    TerminatorKind::Drop { place, .. } => owners.contains(&place.local),
+                    _ => matches!(
+                        &block.terminator.kind,
+                        ir::TerminatorKind::StaticCall { callee_did, args, .. }
+                            if ext.match_def_path(*callee_did, &MEM_DROP)
+                                && matches!(
+                                    args.first(),
+                                    Some(Operand::Move(place)) if owners.contains(&place.local)
+                                )
+                    ),
+                })
+                .map(|(block, _)| block)
+                .collect()
+        }
+
+        /// Blocks that move `local` out of the body, into the return place or a place behind
+        /// an argument (`*out = copy`, `ptr::write(out, copy)`), following moves of `local`.
+        fn escapes_of(&self, local: mir::Local) -> Vec<usize> {
+            let ext = self.rcx.tcx().ext();
+            let owners = self.moved_into(local);
+            let is_moved = |operand: &Operand<'tcx>| matches!(operand, Operand::Move(place) if owners.contains(&place.local));
+            let is_argument = |local: mir::Local| {
+                (1..=self.body.original.arg_count).contains(&self.body.borrowed_root(local).index())
+            };
+            let is_outside = |place: &mir::Place<'tcx>| {
+                place.local == mir::RETURN_PLACE
+                    || place.is_indirect_first_projection() && is_argument(place.local)
+            };
+
+            self.body
+                .basic_blocks
+                .iter()
+                .enumerate()
+                .filter(|(_, block)| {
+                    let stores = block
+                        .statements
+                        .iter()
+                        .any(|statement| match &statement.kind {
+                            StatementKind::Assign(box (place, Rvalue::Use(operand))) => {
+                                is_outside(place) && is_moved(operand)
+                            }
+                            StatementKind::Assign(box (place, Rvalue::Aggregate(_, operands))) => {
+                                is_outside(place) && operands.iter().any(is_moved)
+                            }
+                            _ => false,
+                        });
+                    let writes = matches!(
+                        &block.terminator.kind,
+                        ir::TerminatorKind::StaticCall { callee_did, args, .. }
+                            if (ext.match_def_path(*callee_did, &PTR_WRITE)
+                                || ext.match_def_path(*callee_did, &PTR_DIRECT_WRITE))
+                                && args
+                                    .first()
+                                    .and_then(Operand::place)
+                                    .is_some_and(|place| is_argument(place.local))
+                                && args.get(1).is_some_and(is_moved)
+                    );
+                    stores || writes
+                })
+                .map(|(block, _)| block)
+                .collect()
+        }
+
+        /// Returns `local` and the locals it is moved into (`_a = move _b`).
+        fn moved_into(&self, local: mir::Local) -> FxHashSet<mir::Local> {
+            let mut owners = FxHashSet::default();
+            owners.insert(local);
+
+            loop {
+                let mut changed = false;
+                for statement in self.body.statements() {
+                    if_chain! {
+                        if let StatementKind::Assign(box (place, Rvalue::Use(Operand::Move(source)))) =
+                            &statement.kind;
+                        if owners.contains(&source.local);
+                        if let Some(target) = place.as_local();
+                        then {
+                            changed |= owners.insert(target);
+                        }
+                    }
+                }
+                if !changed {
+                    break;
+                }
+            }
+
+            owners
+        }
+
+        /// Blocks that call `drop_in_place` on a pointer derived from `pointer_root`.
+        fn drop_in_places(&self, pointer_root: mir::Local) -> Vec<usize> {
+            let ext = self.rcx.tcx().ext();
+            self.body
+                .terminators()
+                .enumerate()
+                .filter(|(_, terminator)| match &terminator.kind {
+                    ir::TerminatorKind::StaticCall {
+                        callee_did, args, ..
+                    } => {
+                        (ext.match_def_path(*callee_did, &PTR_DROP_IN_PLACE)
+                            || ext.match_def_path(*callee_did, &PTR_DIRECT_DROP_IN_PLACE))
+                            && args.first().and_then(Operand::place).is_some_and(|place| {
+                                self.body.borrowed_root(place.local) == pointer_root
+                            })
+                    }
+                    _ => false,
+                })
+                .map(|(block, _)| block)
+                .collect()
+        }
+
+        /// Blocks that overwrite or give up the value behind `aliases`
+        /// (`ptr::write`, `ptr::copy`, `Vec::set_len`), after which dropping the owner is fine.
+        fn reinitializations(&self, aliases: &FxHashSet<mir::Local>) -> Vec<usize> {
+            let ext = self.rcx.tcx().ext();
+            self.body
+                .terminators()
+                .enumerate()
+                .filter(|(_, terminator)| match &terminator.kind {
+                    ir::TerminatorKind::StaticCall {
+                        callee_did, args, ..
+                    } => {
+                        paths::REINITIALIZE_LIST.contains(&ext.get_def_path(*callee_did))
+                            && args.iter().any(|arg| {
+                                arg.place()
+                                    .is_some_and(|place| aliases.contains(&place.local))
+                            })
+                    }
+                    _ => false,
+                })
+                .map(|(block, _)| block)
+                .collect()
+        }
+
+        fn terminator_span(&self, block: usize) -> Span {
+            self.body.basic_blocks[block]
+                .terminator
+                .original
+                .source_info
+                .span
+        }
+    }
+}
+
+// Double Drop BehaviorFlag.
+// Used to associate each report with the way ownership was duplicated.
+bitflags! {
+    #[derive(Default)]
+    pub struct BehaviorFlag: u8 {
+        const PTR_READ = 0b00000001;
+        const BOX_FROM_RAW = 0b00000010;
+        const VEC_FROM_RAW_PARTS = 0b00000100;
+        const TRANSMUTE_COPY = 0b00001000;
+    }
+}
+
+impl IntoReportLevel for BehaviorFlag {
+    fn report_level(&self) -> ReportLevel {
+        ReportLevel::Error
+    }
+}
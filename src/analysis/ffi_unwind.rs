@@ -0,0 +1,279 @@
+//! Detector for panics unwinding out of `extern "C"` functions
+use rustc_abi::ExternAbi;
+use rustc_hir::{def::DefKind, def_id::DefId, BodyId};
+use rustc_middle::mir::{self, AggregateKind, Operand, Rvalue, StatementKind};
+use rustc_middle::ty::layout::fn_can_unwind;
+use rustc_middle::ty::{GenericArgsRef, Instance, InstanceKind, TyCtxt, TyKind, TypingEnv};
+use rustc_span::Span;
+
+use snafu::{Backtrace, Snafu};
+use termcolor::Color;
+
+use crate::prelude::*;
+use crate::{
+    analysis::{unsafe_dataflow::calls_user_provided_code, AnalysisKind, IntoReportLevel},
+    ir,
+    paths::*,
+    report::{Report, ReportLevel},
+    utils,
+};
+
+#[derive(Debug, Snafu)]
+pub enum FfiUnwindError {
+    InvalidSpan { backtrace: Backtrace },
+}
+
+impl AnalysisError for FfiUnwindError {
+    fn kind(&self) -> AnalysisErrorKind {
+        use FfiUnwindError::*;
+        match self {
+            InvalidSpan { .. } => AnalysisErrorKind::Unreachable,
+        }
+    }
+}
+
+pub struct FfiUnwindChecker<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+}
+
+impl<'tcx> FfiUnwindChecker<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+        FfiUnwindChecker { rcx }
+    }
+
+    pub fn analyze(self) {
+        let tcx = self.rcx.tcx();
+        let hir_map = tcx.hir();
+
+        // Iterates all (type, related function) pairs
+        for (_ty_hir_id, (body_id, related_item_span)) in self.rcx.types_with_related_items() {
+            if let Some(status) = inner::FfiUnwindBodyAnalyzer::analyze_body(self.rcx, body_id) {
+                let behavior_flag = status.behavior_flag();
+                if !behavior_flag.is_empty()
+                    && behavior_flag.report_level() >= self.rcx.report_level()
+                {
+                    let mut color_span = unwrap_or!(
+                        utils::ColorSpan::new(tcx, related_item_span).context(InvalidSpan) => continue
+                    );
+
+                    for &span in status.panicking_call_spans() {
+                        color_span.add_sub_span(Color::Red, span);
+                    }
+
+                    rudra_report(Report::with_color_span(
+                        tcx,
+                        behavior_flag.report_level(),
+                        AnalysisKind::FfiUnwind(behavior_flag),
+                        format!(
+                            "Panic may unwind out of the non-unwinding ABI function `{}`",
+                            tcx.def_path_str(hir_map.body_owner_def_id(body_id).to_def_id())
+                        ),
+                        &color_span,
+                    ))
+                }
+            }
+        }
+    }
+}
+
+mod inner {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct FfiUnwindStatus {
+        panicking_calls: Vec<Span>,
+        behavior_flag: BehaviorFlag,
+    }
+
+    impl FfiUnwindStatus {
+        pub fn behavior_flag(&self) -> BehaviorFlag {
+            self.behavior_flag
+        }
+
+        pub fn panicking_call_spans(&self) -> &Vec<Span> {
+            &self.panicking_calls
+        }
+    }
+
+    pub struct FfiUnwindBodyAnalyzer<'tcx> {
+        rcx: RudraCtxt<'tcx>,
+        status: FfiUnwindStatus,
+    }
+
+    impl<'tcx> FfiUnwindBodyAnalyzer<'tcx> {
+        pub fn analyze_body(rcx: RudraCtxt<'tcx>, body_id: BodyId) -> Option<FfiUnwindStatus> {
+            let tcx = rcx.tcx();
+            let body_did = tcx.hir().body_owner_def_id(body_id).to_def_id();
+
+            if !is_non_unwinding_foreign_fn(tcx, body_did) {
+                return Some(Default::default());
+            }
+
+            let mut body_analyzer = FfiUnwindBodyAnalyzer {
+                rcx,
+                status: Default::default(),
+            };
+            match rcx.translate_body(body_did).as_ref() {
+                Err(e) => {
+                    // MIR is not available for def - log it and continue
+                    e.log();
+                    None
+                }
+                Ok(body) => {
+                    body_analyzer.analyze(body);
+
+                    // Closures called from the function, unless they run inside `catch_unwind`
+                    for closure_did in unprotected_closures(rcx, body) {
+                        if let Ok(closure_body) = rcx.translate_body(closure_did).as_ref() {
+                            body_analyzer.analyze(closure_body);
+                        }
+                    }
+
+                    Some(body_analyzer.status)
+                }
+            }
+        }
+
+        /// Records calls that may run user-provided code, which potentially panics.
+        fn analyze(&mut self, body: &ir::Body<'tcx>) {
+            let tcx = self.rcx.tcx();
+            let typing_env = body.original.typing_env(tcx);
+
+            for terminator in body.terminators() {
+                let flag = match terminator.kind {
+                    ir::TerminatorKind::StaticCall {
+                        callee_did,
+                        callee_substs,
+                        ..
+                    } => {
+                        if calls_user_provided_code(self.rcx, typing_env, callee_did, callee_substs)
+                        {
+                            BehaviorFlag::UNRESOLVABLE_GENERIC
+                        } else if is_trait_object_call(tcx, typing_env, callee_did, callee_substs) {
+                            BehaviorFlag::TRAIT_OBJECT
+                        } else {
+                            continue;
+                        }
+                    }
+                    _ => match &terminator.original.kind {
+                        mir::TerminatorKind::Call { func, .. }
+                            if matches!(func.ty(body, tcx).kind(), TyKind::FnPtr(..)) =>
+                        {
+                            BehaviorFlag::FN_PTR
+                        }
+                        _ => continue,
+                    },
+                };
+
+                self.status.behavior_flag |= flag;
+                self.status
+                    .panicking_calls
+                    .push(terminator.original.source_info.span);
+            }
+        }
+    }
+
+    /// Returns true if the call dispatches through a trait object,
+    /// either directly or through a reference or a `Box` (`Box<dyn FnMut()>`).
+    fn is_trait_object_call<'tcx>(
+        tcx: TyCtxt<'tcx>,
+        typing_env: TypingEnv<'tcx>,
+        callee_did: DefId,
+        callee_substs: GenericArgsRef<'tcx>,
+    ) -> bool {
+        if let Ok(Some(Instance {
+            def: InstanceKind::Virtual(..),
+            ..
+        })) = Instance::try_resolve(tcx, typing_env, callee_did, callee_substs)
+        {
+            return true;
+        }
+
+        if tcx.trait_of_item(callee_did).is_none() {
+            return false;
+        }
+        let mut self_ty = match callee_substs.types().next() {
+            Some(ty) => ty,
+            None => return false,
+        };
+        loop {
+            self_ty = self_ty.peel_refs();
+            match self_ty.boxed_ty() {
+                Some(boxed_ty) => self_ty = boxed_ty,
+                None => return matches!(self_ty.kind(), TyKind::Dynamic(..)),
+            }
+        }
+    }
+
+    /// Returns true for functions with an ABI such as `extern "C"`,
+    /// where unwinding out of the function aborts the process or is undefined behavior.
+    fn is_non_unwinding_foreign_fn(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
+        if !matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn) {
+            return false;
+        }
+
+        let abi = tcx.fn_sig(def_id).skip_binder().abi();
+        !matches!(
+            abi,
+            ExternAbi::Rust | ExternAbi::RustCall | ExternAbi::RustCold | ExternAbi::RustIntrinsic
+        ) && !fn_can_unwind(tcx, None, abi)
+    }
+
+    /// Returns closures created in `body` that are not passed to `catch_unwind`.
+    fn unprotected_closures<'tcx>(rcx: RudraCtxt<'tcx>, body: &ir::Body<'tcx>) -> Vec<DefId> {
+        let ext = rcx.tcx().ext();
+
+        // Arguments of `catch_unwind(AssertUnwindSafe(|| ...))`
+        let protected: Vec<mir::Local> = body
+            .terminators()
+            .filter_map(|terminator| match &terminator.kind {
+                ir::TerminatorKind::StaticCall {
+                    callee_did, args, ..
+                } if ext.match_def_path(*callee_did, &PANIC_CATCH_UNWIND) => args
+                    .first()
+                    .and_then(Operand::place)
+                    .map(|place| place.local),
+                _ => None,
+            })
+            .collect();
+
+        body.statements()
+            .filter_map(|statement| match &statement.kind {
+                StatementKind::Assign(box (
+                    place,
+                    Rvalue::Aggregate(box AggregateKind::Closure(closure_did, _), _),
+                )) if !body
+                    .derived_locals(place.local)
+                    .iter()
+                    .any(|local| protected.contains(local)) =>
+                {
+                    Some(*closure_did)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+bitflags! {
+    #[derive(Default)]
+    pub struct BehaviorFlag: u8 {
+        // Calls an unresolvable generic function, such as a closure parameter
+        const UNRESOLVABLE_GENERIC = 0b00000001;
+        // Calls a method of a trait object
+        const TRAIT_OBJECT = 0b00000010;
+        // Calls a function pointer, which may be a Rust function
+        const FN_PTR = 0b00000100;
+    }
+}
+
+impl IntoReportLevel for BehaviorFlag {
+    fn report_level(&self) -> ReportLevel {
+        if self.intersects(BehaviorFlag::UNRESOLVABLE_GENERIC | BehaviorFlag::TRAIT_OBJECT) {
+            ReportLevel::Error
+        } else {
+            // Function pointers handed over from C are usually C functions
+            ReportLevel::Warning
+        }
+    }
+}
@@ -0,0 +1,413 @@
+//! Interior mutability detector for `Sync` types written through `&self`
+use rustc_abi::VariantIdx;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::{def_id::DefId, Mutability, Safety};
+use rustc_middle::mir::visit::{MutatingUseContext, PlaceContext, Visitor};
+use rustc_middle::mir::{self, AggregateKind, Location, Operand, Rvalue, StatementKind};
+use rustc_middle::ty::{self, AdtDef, AssocKind, GenericArgKind, Ty, TyCtxt, TyKind};
+use rustc_span::{Span, Symbol};
+
+use snafu::{Backtrace, Snafu};
+use termcolor::Color;
+
+use crate::prelude::*;
+use crate::{
+    analysis::{AnalysisKind, IntoReportLevel},
+    ir,
+    paths::*,
+    report::{Report, ReportLevel},
+    utils,
+};
+
+#[derive(Debug, Snafu)]
+pub enum InteriorMutabilityError {
+    InvalidSpan { backtrace: Backtrace },
+}
+
+impl AnalysisError for InteriorMutabilityError {
+    fn kind(&self) -> AnalysisErrorKind {
+        use InteriorMutabilityError::*;
+        match self {
+            InvalidSpan { .. } => AnalysisErrorKind::Unreachable,
+        }
+    }
+}
+
+pub struct InteriorMutabilityChecker<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+}
+
+impl<'tcx> InteriorMutabilityChecker<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+        InteriorMutabilityChecker { rcx }
+    }
+
+    pub fn analyze(self) {
+        let tcx = self.rcx.tcx();
+        let sync_trait_did = match tcx.lang_items().sync_trait() {
+            Some(did) => did,
+            None => return,
+        };
+
+        // `unsafe impl Sync` for ADTs with `UnsafeCell`, `Cell` or raw pointer fields
+        let mut checked = FxHashSet::default();
+        for &impl_id in tcx.hir().trait_impls(sync_trait_did) {
+            if_chain! {
+                if tcx.impl_polarity(impl_id) == ty::ImplPolarity::Positive;
+                if let Some(trait_ref) = tcx.impl_trait_ref(impl_id);
+                if let TyKind::Adt(adt_def, _) = trait_ref.skip_binder().self_ty().kind();
+                if inner::has_interior_mutability(tcx, *adt_def);
+                if checked.insert(adt_def.did());
+                then {
+                    self.analyze_adt(adt_def.did());
+                }
+            }
+        }
+    }
+
+    /// Report `&self` methods of the `Sync` ADT that write through its fields
+    /// without synchronization
+    fn analyze_adt(&self, adt_did: DefId) {
+        let tcx = self.rcx.tcx();
+        let relevant_impls = match self.rcx.index_adt_cache(&adt_did) {
+            Some(relevant_impls) => relevant_impls,
+            None => return,
+        };
+
+        for &(impl_did, _) in relevant_impls.iter() {
+            for assoc_item in tcx
+                .associated_items(impl_did.to_def_id())
+                .in_definition_order()
+            {
+                if assoc_item.kind != AssocKind::Fn || !assoc_item.fn_has_self_parameter {
+                    continue;
+                }
+
+                // Unsafe methods may require their callers to synchronize
+                let fn_did = assoc_item.def_id;
+                let fn_sig = tcx.fn_sig(fn_did).skip_binder();
+                if fn_sig.safety() == Safety::Unsafe
+                    || !matches!(
+                        fn_sig.inputs().skip_binder()[0].kind(),
+                        TyKind::Ref(_, _, Mutability::Not)
+                    )
+                {
+                    continue;
+                }
+
+                let (behavior_flag, write_spans) = inner::unsynchronized_writes(self.rcx, fn_did);
+                if behavior_flag.is_empty()
+                    || behavior_flag.report_level() < self.rcx.report_level()
+                {
+                    continue;
+                }
+
+                let fn_span = tcx
+                    .hir()
+                    .span_with_body(tcx.local_def_id_to_hir_id(fn_did.expect_local()));
+                let mut color_span = unwrap_or!(
+                    utils::ColorSpan::new(tcx, fn_span).context(InvalidSpan) => continue
+                );
+
+                for span in write_spans {
+                    color_span.add_sub_span(Color::Red, span);
+                }
+
+                rudra_report(Report::with_color_span(
+                    tcx,
+                    behavior_flag.report_level(),
+                    AnalysisKind::InteriorMutability(behavior_flag),
+                    format!(
+                        "`{}` writes to `Sync` type `{}` through `&self` without synchronization",
+                        tcx.def_path_str(fn_did),
+                        tcx.def_path_str(adt_did)
+                    ),
+                    &color_span,
+                ))
+            }
+        }
+    }
+}
+
+mod inner {
+    use super::*;
+
+    /// Returns true if a field of the ADT contains `UnsafeCell`, `Cell` or a raw pointer.
+    pub fn has_interior_mutability<'tcx>(tcx: TyCtxt<'tcx>, adt_def: AdtDef<'tcx>) -> bool {
+        let ext = tcx.ext();
+        adt_def.all_fields().any(|field| {
+            tcx.type_of(field.did)
+                .instantiate_identity()
+                .walk()
+                .any(|arg| match arg.unpack() {
+                    GenericArgKind::Type(ty) => match ty.kind() {
+                        TyKind::RawPtr(..) => true,
+                        TyKind::Adt(adt_def, _) => {
+                            adt_def.is_unsafe_cell()
+                                || ext.match_def_path(adt_def.did(), &CELL)
+                                || ext.match_def_path(adt_def.did(), &NON_NULL)
+                        }
+                        _ => false,
+                    },
+                    _ => false,
+                })
+        })
+    }
+
+    /// Returns the writes through `self` in `fn_did` that are not preceded on every path
+    /// by an atomic operation or a lock.
+    pub fn unsynchronized_writes(rcx: RudraCtxt<'_>, fn_did: DefId) -> (BehaviorFlag, Vec<Span>) {
+        let tcx = rcx.tcx();
+        let ext = tcx.ext();
+        let translation = rcx.translate_body(fn_did);
+        let body = unwrap_or!(translation.as_ref() => return Default::default());
+        let sync_points = synchronization_points(tcx, body);
+        let dominators = body.original.basic_blocks.dominators();
+        let is_synchronized = |location: Location| {
+            sync_points
+                .iter()
+                .any(|sync_point| sync_point.dominates(location, dominators))
+        };
+
+        // `&self`, and the pointers and references derived from it
+        let self_derived = body.derived_locals(mir::Local::from_usize(1));
+        let is_self_derived = |operand: Option<&Operand<'_>>| {
+            operand
+                .and_then(Operand::place)
+                .is_some_and(|place| self_derived.contains(&place.local))
+        };
+
+        // Pointers returned by `UnsafeCell::get`
+        let mut cell_ptrs = FxHashSet::default();
+        for terminator in body.terminators() {
+            if let ir::TerminatorKind::StaticCall {
+                callee_did,
+                ref args,
+                destination: Some((place, _)),
+                ..
+            } = terminator.kind
+            {
+                if UNSAFE_CELL_GET_LIST.contains(&ext.get_def_path(callee_did))
+                    && is_self_derived(args.first())
+                {
+                    cell_ptrs.extend(body.derived_locals(place.local));
+                }
+            }
+        }
+        let ptr_flag = |local: mir::Local| {
+            if cell_ptrs.contains(&local) {
+                BehaviorFlag::UNSAFE_CELL_WRITE
+            } else {
+                BehaviorFlag::RAW_PTR_WRITE
+            }
+        };
+
+        let mut behavior_flag = BehaviorFlag::empty();
+        let mut write_spans = Vec::new();
+
+        // `cell.set(value)` and `ptr::write(ptr, value)`
+        for (block, basic_block) in body.basic_blocks.iter().enumerate() {
+            let terminator = &basic_block.terminator;
+            if let ir::TerminatorKind::StaticCall {
+                callee_did,
+                ref args,
+                ..
+            } = terminator.kind
+            {
+                let receiver = match args.first().and_then(Operand::place) {
+                    Some(receiver) => receiver,
+                    None => continue,
+                };
+                if !self_derived.contains(&receiver.local) {
+                    continue;
+                }
+                let path = ext.get_def_path(callee_did);
+                let flag = if CELL_WRITE_LIST.contains(&path) {
+                    BehaviorFlag::CELL_WRITE
+                } else if PTR_WRITE_LIST.contains(&path) {
+                    ptr_flag(receiver.local)
+                } else {
+                    continue;
+                };
+                if is_synchronized(body.terminator_location(block)) {
+                    continue;
+                }
+                behavior_flag |= flag;
+                write_spans.push(terminator.original.source_info.span);
+            }
+        }
+
+        // `*ptr = value` and `&mut *ptr`
+        let mut write_visitor = WriteVisitor {
+            body,
+            self_derived: &self_derived,
+            writes: Vec::new(),
+            current_span: None,
+        };
+        write_visitor.visit_body(&body.original);
+        for (local, span, location) in write_visitor.writes {
+            if is_synchronized(location) {
+                continue;
+            }
+            behavior_flag |= ptr_flag(local);
+            write_spans.push(span);
+        }
+
+        write_spans.sort();
+        write_spans.dedup();
+        (behavior_flag, write_spans)
+    }
+
+    /// Returns the locations of lock acquisitions, `Mutex`/`RwLock` accesses and
+    /// atomic operations that are not `Relaxed`.
+    fn synchronization_points<'tcx>(tcx: TyCtxt<'tcx>, body: &ir::Body<'tcx>) -> Vec<Location> {
+        let ext = tcx.ext();
+        let atomic_module: Vec<Symbol> = ATOMIC_MODULE.iter().map(|p| Symbol::intern(p)).collect();
+        let is_lock = |ty: Ty<'tcx>| match ty.kind() {
+            TyKind::Adt(adt_def, _) => {
+                ext.match_def_path(adt_def.did(), &MUTEX)
+                    || ext.match_def_path(adt_def.did(), &RWLOCK)
+            }
+            _ => false,
+        };
+        // e.g. a `Relaxed` statistics counter does not order other memory accesses
+        let is_relaxed_variant = |adt_def: AdtDef<'_>, variant_idx: VariantIdx| {
+            ext.match_def_path(adt_def.did(), &ATOMIC_ORDERING)
+                && adt_def.variant(variant_idx).name.as_str() == "Relaxed"
+        };
+        let relaxed_locals: FxHashSet<mir::Local> = body
+            .statements()
+            .filter_map(|statement| match &statement.kind {
+                StatementKind::Assign(box (
+                    place,
+                    Rvalue::Aggregate(box AggregateKind::Adt(adt_did, variant_idx, ..), _),
+                )) if is_relaxed_variant(tcx.adt_def(*adt_did), *variant_idx) => place.as_local(),
+                _ => None,
+            })
+            .collect();
+        let is_ordered = |operand: &Operand<'tcx>| match operand.ty(body, tcx).kind() {
+            TyKind::Adt(adt_def, _) if ext.match_def_path(adt_def.did(), &ATOMIC_ORDERING) => {
+                match operand {
+                    Operand::Constant(constant) => {
+                        let relaxed = adt_def
+                            .discriminants(tcx)
+                            .find(|&(variant_idx, _)| is_relaxed_variant(*adt_def, variant_idx));
+                        match (constant.const_.try_to_scalar_int(), relaxed) {
+                            (Some(scalar), Some((_, discr))) => {
+                                scalar.to_bits_unchecked() != discr.val
+                            }
+                            _ => true,
+                        }
+                    }
+                    Operand::Copy(place) | Operand::Move(place) => place
+                        .as_local()
+                        .is_none_or(|local| !relaxed_locals.contains(&local)),
+                }
+            }
+            _ => false,
+        };
+
+        let mut sync_points = Vec::new();
+        for (block, basic_block) in body.basic_blocks.iter().enumerate() {
+            for (statement_index, statement) in basic_block.statements.iter().enumerate() {
+                if let StatementKind::Assign(box (_, Rvalue::Ref(_, _, place))) = &statement.kind {
+                    if is_lock(place.ty(body, tcx).ty) {
+                        sync_points.push(Location {
+                            block: block.into(),
+                            statement_index,
+                        });
+                    }
+                }
+            }
+
+            if let ir::TerminatorKind::StaticCall {
+                callee_did,
+                ref args,
+                ..
+            } = basic_block.terminator.kind
+            {
+                let path = ext.get_def_path(callee_did);
+                if SYNCHRONIZATION_LIST.contains(&path)
+                    || path.starts_with(&atomic_module) && args.iter().any(is_ordered)
+                {
+                    sync_points.push(body.terminator_location(block));
+                }
+            }
+        }
+        sync_points
+    }
+
+    /// Collects stores and mutable borrows through raw pointers derived from `self`.
+    struct WriteVisitor<'a, 'tcx> {
+        body: &'a ir::Body<'tcx>,
+        self_derived: &'a FxHashSet<mir::Local>,
+        writes: Vec<(mir::Local, Span, Location)>,
+        current_span: Option<Span>,
+    }
+
+    impl<'tcx> Visitor<'tcx> for WriteVisitor<'_, 'tcx> {
+        fn visit_statement(&mut self, statement: &mir::Statement<'tcx>, location: Location) {
+            self.current_span = Some(statement.source_info.span);
+            self.super_statement(statement, location);
+        }
+
+        fn visit_terminator(&mut self, terminator: &mir::Terminator<'tcx>, location: Location) {
+            self.current_span = Some(terminator.source_info.span);
+            self.super_terminator(terminator, location);
+        }
+
+        fn visit_place(
+            &mut self,
+            place: &mir::Place<'tcx>,
+            context: PlaceContext,
+            location: Location,
+        ) {
+            // Taking a raw pointer or dropping does not write to the pointee
+            if !matches!(
+                context,
+                PlaceContext::MutatingUse(
+                    MutatingUseContext::Store
+                        | MutatingUseContext::Call
+                        | MutatingUseContext::AsmOutput
+                        | MutatingUseContext::SetDiscriminant
+                        | MutatingUseContext::Deinit
+                        | MutatingUseContext::Borrow
+                )
+            ) {
+                return;
+            }
+            if_chain! {
+                if place.is_indirect_first_projection();
+                if self.self_derived.contains(&place.local);
+                if self.body.local_decls[place.local.index()].ty.is_raw_ptr();
+                if let Some(span) = self.current_span;
+                then {
+                    self.writes.push((place.local, span, location));
+                }
+            }
+        }
+    }
+}
+
+bitflags! {
+    #[derive(Default)]
+    pub struct BehaviorFlag: u8 {
+        // Writes through the pointer returned by `UnsafeCell::get`
+        const UNSAFE_CELL_WRITE = 0b00000001;
+        // Calls `Cell::set` or a similar method
+        const CELL_WRITE = 0b00000010;
+        // Writes through a raw pointer field
+        const RAW_PTR_WRITE = 0b00000100;
+    }
+}
+
+impl IntoReportLevel for BehaviorFlag {
+    fn report_level(&self) -> ReportLevel {
+        if self.intersects(BehaviorFlag::UNSAFE_CELL_WRITE | BehaviorFlag::CELL_WRITE) {
+            ReportLevel::Error
+        } else {
+            // The pointee may be owned by a single thread or synchronized elsewhere
+            ReportLevel::Warning
+        }
+    }
+}
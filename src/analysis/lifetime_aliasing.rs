@@ -0,0 +1,375 @@
+//! Unbounded lifetime and shared-to-mutable aliasing detector
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::{def_id::LocalDefId, BodyId};
+use rustc_middle::mir::visit::{PlaceContext, Visitor};
+use rustc_middle::mir::{self, CastKind, Location, Mutability, Operand, Rvalue, StatementKind};
+use rustc_middle::ty::{self, ClauseKind, Region, TyKind, TypingEnv};
+use rustc_span::Span;
+
+use snafu::{Backtrace, Snafu};
+use termcolor::Color;
+
+use crate::prelude::*;
+use crate::{
+    analysis::{AnalysisKind, IntoReportLevel},
+    ir,
+    paths::*,
+    report::{Report, ReportLevel},
+    utils,
+    visitor::ContainsUnsafe,
+};
+
+#[derive(Debug, Snafu)]
+pub enum LifetimeAliasingError {
+    InvalidSpan { backtrace: Backtrace },
+}
+
+impl AnalysisError for LifetimeAliasingError {
+    fn kind(&self) -> AnalysisErrorKind {
+        use LifetimeAliasingError::*;
+        match self {
+            InvalidSpan { .. } => AnalysisErrorKind::Unreachable,
+        }
+    }
+}
+
+pub struct LifetimeAliasingChecker<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+}
+
+impl<'tcx> LifetimeAliasingChecker<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+        LifetimeAliasingChecker { rcx }
+    }
+
+    pub fn analyze(self) {
+        let tcx = self.rcx.tcx();
+        let hir_map = tcx.hir();
+
+        // Iterates all (type, related function) pairs
+        for (_ty_hir_id, (body_id, related_item_span)) in self.rcx.types_with_related_items() {
+            if let Some(status) =
+                inner::LifetimeAliasingBodyAnalyzer::analyze_body(self.rcx, body_id)
+            {
+                let behavior_flag = status.behavior_flag();
+                if !behavior_flag.is_empty()
+                    && behavior_flag.report_level() >= self.rcx.report_level()
+                {
+                    let mut color_span = unwrap_or!(
+                        utils::ColorSpan::new(tcx, related_item_span).context(InvalidSpan) => continue
+                    );
+
+                    for &span in status.unbounded_ref_spans() {
+                        color_span.add_sub_span(Color::Yellow, span);
+                    }
+
+                    for &span in status.shared_to_mut_spans() {
+                        color_span.add_sub_span(Color::Red, span);
+                    }
+
+                    rudra_report(Report::with_color_span(
+                        tcx,
+                        behavior_flag.report_level(),
+                        AnalysisKind::LifetimeAliasing(behavior_flag),
+                        format!(
+                            "Potential unbounded lifetime or aliasing violation in `{}`",
+                            tcx.def_path_str(hir_map.body_owner_def_id(body_id).to_def_id())
+                        ),
+                        &color_span,
+                    ))
+                }
+            }
+        }
+    }
+}
+
+mod inner {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct LifetimeAliasingStatus {
+        unbounded_refs: Vec<Span>,
+        shared_to_muts: Vec<Span>,
+        behavior_flag: BehaviorFlag,
+    }
+
+    impl LifetimeAliasingStatus {
+        pub fn behavior_flag(&self) -> BehaviorFlag {
+            self.behavior_flag
+        }
+
+        pub fn unbounded_ref_spans(&self) -> &Vec<Span> {
+            &self.unbounded_refs
+        }
+
+        pub fn shared_to_mut_spans(&self) -> &Vec<Span> {
+            &self.shared_to_muts
+        }
+    }
+
+    pub struct LifetimeAliasingBodyAnalyzer<'a, 'tcx> {
+        rcx: RudraCtxt<'tcx>,
+        body_did: LocalDefId,
+        body: &'a ir::Body<'tcx>,
+        typing_env: TypingEnv<'tcx>,
+        status: LifetimeAliasingStatus,
+    }
+
+    impl<'a, 'tcx> LifetimeAliasingBodyAnalyzer<'a, 'tcx> {
+        fn new(rcx: RudraCtxt<'tcx>, body_did: LocalDefId, body: &'a ir::Body<'tcx>) -> Self {
+            LifetimeAliasingBodyAnalyzer {
+                rcx,
+                body_did,
+                body,
+                typing_env: body.original.typing_env(rcx.tcx()),
+                status: Default::default(),
+            }
+        }
+
+        pub fn analyze_body(
+            rcx: RudraCtxt<'tcx>,
+            body_id: BodyId,
+        ) -> Option<LifetimeAliasingStatus> {
+            let hir_map = rcx.tcx().hir();
+            let body_did = hir_map.body_owner_def_id(body_id);
+
+            if ContainsUnsafe::contains_unsafe(rcx.tcx(), body_id) {
+                match rcx.translate_body(body_did.to_def_id()).as_ref() {
+                    Err(e) => {
+                        // MIR is not available for def - log it and continue
+                        e.log();
+                        None
+                    }
+                    Ok(body) => {
+                        let body_analyzer = LifetimeAliasingBodyAnalyzer::new(rcx, body_did, body);
+                        Some(body_analyzer.analyze())
+                    }
+                }
+            } else {
+                // Raw pointers can only be dereferenced in unsafe code
+                Some(Default::default())
+            }
+        }
+
+        fn analyze(mut self) -> LifetimeAliasingStatus {
+            let tcx = self.rcx.tcx();
+            if tcx.ext().is_exported_safe_fn(self.body_did) && self.returns_unbounded_ref() {
+                let raw_to_ref_spans = self.raw_to_ref_spans();
+                if !raw_to_ref_spans.is_empty() {
+                    self.status.behavior_flag |= BehaviorFlag::UNBOUNDED_LIFETIME;
+                    self.status.unbounded_refs.extend(raw_to_ref_spans);
+                }
+            }
+
+            self.check_shared_to_mut();
+            self.status
+        }
+
+        /// Returns true if the return type contains a reference whose lifetime
+        /// does not appear in any of the inputs (and is not `'static`).
+        fn returns_unbounded_ref(&self) -> bool {
+            let tcx = self.rcx.tcx();
+            let fn_sig = tcx.fn_sig(self.body_did).skip_binder().skip_binder();
+
+            let mut bounded_regions: FxHashSet<Region<'tcx>> = FxHashSet::default();
+            for input_ty in fn_sig.inputs() {
+                bounded_regions.extend(input_ty.walk().filter_map(|arg| arg.as_region()));
+            }
+
+            // `'b: 'a` ties `'a` to the inputs if `'b` is already tied.
+            let outlives = tcx
+                .param_env(self.body_did)
+                .caller_bounds()
+                .iter()
+                .filter_map(|clause| match clause.kind().skip_binder() {
+                    ClauseKind::RegionOutlives(ty::OutlivesPredicate(longer, shorter)) => {
+                        Some((longer, shorter))
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            loop {
+                let mut changed = false;
+                for &(longer, shorter) in outlives.iter() {
+                    if bounded_regions.contains(&longer) {
+                        changed |= bounded_regions.insert(shorter);
+                    }
+                }
+                if !changed {
+                    break;
+                }
+            }
+
+            fn_sig
+                .output()
+                .walk()
+                .any(|arg| match arg.as_type().map(|ty| *ty.kind()) {
+                    Some(TyKind::Ref(region, _, _)) => {
+                        !region.is_static() && !bounded_regions.contains(&region)
+                    }
+                    _ => false,
+                })
+        }
+
+        /// Spans where a raw pointer is turned into a reference (`&*ptr`, `ptr.as_ref()`).
+        fn raw_to_ref_spans(&self) -> Vec<Span> {
+            let tcx = self.rcx.tcx();
+            let ext = tcx.ext();
+            let mut spans = Vec::new();
+
+            for statement in self.body.statements() {
+                if_chain! {
+                    if let StatementKind::Assign(box (_, Rvalue::Ref(_, _, place))) = &statement.kind;
+                    if place.is_indirect_first_projection();
+                    if self.body.local_decls[place.local.index()].ty.is_raw_ptr();
+                    then {
+                        spans.push(statement.source_info.span);
+                    }
+                }
+            }
+
+            for terminator in self.body.terminators() {
+                if let ir::TerminatorKind::StaticCall { callee_did, .. } = terminator.kind {
+                    if [&PTR_AS_REF, &PTR_AS_MUT, &NON_NULL_AS_REF, &NON_NULL_AS_MUT]
+                        .iter()
+                        .any(|path| ext.match_def_path(callee_did, *path))
+                    {
+                        spans.push(terminator.original.source_info.span);
+                    }
+                }
+            }
+
+            spans
+        }
+
+        /// Detects `&T -> *const T -> *mut T` followed by a write or `&mut T`,
+        /// where `T` does not contain an `UnsafeCell`.
+        fn check_shared_to_mut(&mut self) {
+            let tcx = self.rcx.tcx();
+            let ext = tcx.ext();
+
+            let mut mutation_collector = MutationCollector::default();
+            mutation_collector.visit_body(&self.body.original);
+
+            // Raw pointers passed to functions that write through them
+            for terminator in self.body.terminators() {
+                if let ir::TerminatorKind::StaticCall {
+                    callee_did,
+                    ref args,
+                    ..
+                } = terminator.kind
+                {
+                    if [
+                        &PTR_WRITE[..],
+                        &PTR_DIRECT_WRITE,
+                        &PTR_AS_MUT,
+                        &NON_NULL_AS_MUT,
+                    ]
+                    .iter()
+                    .any(|path| ext.match_def_path(callee_did, path))
+                    {
+                        if let Some(place) = args.first().and_then(Operand::place) {
+                            mutation_collector
+                                .mutations
+                                .push((place.local, terminator.original.source_info.span));
+                        }
+                    }
+                }
+            }
+
+            for statement in self.body.statements() {
+                if_chain! {
+                    if let StatementKind::Assign(box (
+                        place,
+                        Rvalue::Cast(CastKind::PtrToPtr, operand, dst_ty),
+                    )) = &statement.kind;
+                    if let TyKind::RawPtr(_, Mutability::Mut) = dst_ty.kind();
+                    if let Some(src_place) = operand.place();
+                    if let TyKind::RawPtr(pointee_ty, Mutability::Not) =
+                        self.body.local_decls[src_place.local.index()].ty.kind();
+                    if pointee_ty.is_freeze(tcx, self.typing_env);
+                    if self.is_derived_from_shared_ref(src_place.local);
+                    then {
+                        let derived = self.body.derived_locals(place.local);
+                        let mutation_spans = mutation_collector
+                            .mutations
+                            .iter()
+                            .filter(|(local, _)| derived.contains(local))
+                            .map(|&(_, span)| span)
+                            .collect::<Vec<_>>();
+                        if !mutation_spans.is_empty() {
+                            self.status.behavior_flag |= BehaviorFlag::SHARED_TO_MUT;
+                            self.status.shared_to_muts.push(statement.source_info.span);
+                            self.status.shared_to_muts.extend(mutation_spans);
+                        }
+                    }
+                }
+            }
+        }
+
+        /// Returns true if `local` was (transitively) derived from a shared reference.
+        fn is_derived_from_shared_ref(&self, local: mir::Local) -> bool {
+            self.body.borrow_chain(local).iter().any(|&local| {
+                matches!(
+                    self.body.local_decls[local.index()].ty.kind(),
+                    TyKind::Ref(_, _, Mutability::Not)
+                )
+            })
+        }
+    }
+
+    /// Collects locals that are written through or mutably reborrowed (`*_1 = ..`, `&mut *_1`).
+    #[derive(Default)]
+    struct MutationCollector {
+        mutations: Vec<(mir::Local, Span)>,
+        current_span: Option<Span>,
+    }
+
+    impl<'tcx> Visitor<'tcx> for MutationCollector {
+        fn visit_statement(&mut self, statement: &mir::Statement<'tcx>, location: Location) {
+            self.current_span = Some(statement.source_info.span);
+            self.super_statement(statement, location);
+        }
+
+        fn visit_terminator(&mut self, terminator: &mir::Terminator<'tcx>, location: Location) {
+            self.current_span = Some(terminator.source_info.span);
+            self.super_terminator(terminator, location);
+        }
+
+        fn visit_place(
+            &mut self,
+            place: &mir::Place<'tcx>,
+            context: PlaceContext,
+            _location: Location,
+        ) {
+            if_chain! {
+                if let PlaceContext::MutatingUse(_) = context;
+                if place.is_indirect_first_projection();
+                if let Some(span) = self.current_span;
+                then {
+                    self.mutations.push((place.local, span));
+                }
+            }
+        }
+    }
+}
+
+bitflags! {
+    #[derive(Default)]
+    pub struct BehaviorFlag: u8 {
+        // Safe public function returns a reference with a lifetime unrelated to its inputs
+        const UNBOUNDED_LIFETIME = 0b00000001;
+        // `&T -> *const T -> *mut T -> &mut T` without `UnsafeCell`
+        const SHARED_TO_MUT = 0b00000010;
+    }
+}
+
+impl IntoReportLevel for BehaviorFlag {
+    fn report_level(&self) -> ReportLevel {
+        if self.contains(BehaviorFlag::SHARED_TO_MUT) {
+            ReportLevel::Error
+        } else {
+            ReportLevel::Warning
+        }
+    }
+}
@@ -0,0 +1,615 @@
+//! Pin soundness detector for unchecked pins, pin projections and `Unpin` impls
+use rustc_abi::FieldIdx;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir::{
+    def::DefKind,
+    def_id::{DefId, LocalDefId},
+    BodyId, Safety,
+};
+use rustc_middle::mir::{self, Operand, ProjectionElem, Rvalue, StatementKind};
+use rustc_middle::ty::{self, AdtDef, ClauseKind, GenericArgsRef, TyCtxt, TyKind};
+use rustc_span::{Span, Symbol};
+
+use snafu::{Backtrace, Snafu};
+use termcolor::Color;
+
+use crate::prelude::*;
+use crate::{
+    analysis::{
+        send_sync_variance::{
+            generic_param_idx_mapper, owned_generic_params_in_ty, phantom_indices, PostMapIdx,
+            PreMapIdx,
+        },
+        AnalysisKind, IntoReportLevel,
+    },
+    ir,
+    paths::*,
+    report::{Report, ReportLevel},
+    utils,
+    visitor::ContainsUnsafe,
+};
+
+#[derive(Debug, Snafu)]
+pub enum PinSoundnessError {
+    InvalidSpan { backtrace: Backtrace },
+}
+
+impl AnalysisError for PinSoundnessError {
+    fn kind(&self) -> AnalysisErrorKind {
+        use PinSoundnessError::*;
+        match self {
+            InvalidSpan { .. } => AnalysisErrorKind::Unreachable,
+        }
+    }
+}
+
+pub struct PinSoundnessChecker<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+}
+
+impl<'tcx> PinSoundnessChecker<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+        PinSoundnessChecker { rcx }
+    }
+
+    pub fn analyze(self) {
+        self.analyze_unchecked_pins();
+
+        // Fields accessed through `Pin<&mut Field>` projections, per ADT
+        let pinned_fields = inner::pinned_fields(self.rcx);
+        self.analyze_unpin_impls(&pinned_fields);
+        self.analyze_drop_impls(&pinned_fields);
+    }
+
+    /// Detect safe functions that move pinned data after pinning it without `Unpin` guarantees
+    fn analyze_unchecked_pins(&self) {
+        let tcx = self.rcx.tcx();
+        let hir_map = tcx.hir();
+
+        // Iterates all (type, related function) pairs
+        for (_ty_hir_id, (body_id, related_item_span)) in self.rcx.types_with_related_items() {
+            if let Some(status) = inner::UncheckedPinBodyAnalyzer::analyze_body(self.rcx, body_id) {
+                let behavior_flag = status.behavior_flag();
+                if !behavior_flag.is_empty()
+                    && behavior_flag.report_level() >= self.rcx.report_level()
+                {
+                    let mut color_span = unwrap_or!(
+                        utils::ColorSpan::new(tcx, related_item_span).context(InvalidSpan) => continue
+                    );
+
+                    for &span in status.pin_spans() {
+                        color_span.add_sub_span(Color::Yellow, span);
+                    }
+
+                    for &span in status.move_spans() {
+                        color_span.add_sub_span(Color::Red, span);
+                    }
+
+                    rudra_report(Report::with_color_span(
+                        tcx,
+                        behavior_flag.report_level(),
+                        AnalysisKind::PinSoundness(behavior_flag),
+                        format!(
+                            "Pinned data may be moved in `{}`",
+                            tcx.def_path_str(hir_map.body_owner_def_id(body_id).to_def_id())
+                        ),
+                        &color_span,
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Detect `impl<T> Unpin for X<T>` without `T: Unpin` when `T` is structurally pinned
+    fn analyze_unpin_impls(&self, pinned_fields: &FxHashMap<DefId, FxHashSet<FieldIdx>>) {
+        let tcx = self.rcx.tcx();
+        let unpin_trait_did = match tcx.lang_items().unpin_trait() {
+            Some(did) => did,
+            None => return,
+        };
+
+        for &impl_id in tcx.hir().trait_impls(unpin_trait_did) {
+            if_chain! {
+                if tcx.impl_polarity(impl_id) == ty::ImplPolarity::Positive;
+                if let Some(trait_ref) = tcx.impl_trait_ref(impl_id);
+                if let TyKind::Adt(adt_def, impl_trait_substs) =
+                    trait_ref.skip_binder().self_ty().kind();
+                if let Some(fields) = pinned_fields.get(&adt_def.did());
+                then {
+                    let unbounded_params = inner::unbounded_pinned_params(
+                        tcx,
+                        impl_id,
+                        unpin_trait_did,
+                        *adt_def,
+                        impl_trait_substs,
+                        fields,
+                    );
+                    let behavior_flag = BehaviorFlag::UNPIN_IMPL;
+                    if !unbounded_params.is_empty()
+                        && behavior_flag.report_level() >= self.rcx.report_level()
+                    {
+                        let bounds = unbounded_params
+                            .iter()
+                            .map(|name| format!("`{}: Unpin`", name))
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        rudra_report(Report::with_hir_id(
+                            tcx,
+                            behavior_flag.report_level(),
+                            AnalysisKind::PinSoundness(behavior_flag),
+                            format!(
+                                "`Unpin` impl of structurally pinned `{}` is missing {}",
+                                tcx.def_path_str(adt_def.did()),
+                                bounds
+                            ),
+                            impl_id,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Detect `Drop` impls that move structurally pinned fields out of `self`
+    fn analyze_drop_impls(&self, pinned_fields: &FxHashMap<DefId, FxHashSet<FieldIdx>>) {
+        let tcx = self.rcx.tcx();
+        let drop_trait_did = match tcx.lang_items().drop_trait() {
+            Some(did) => did,
+            None => return,
+        };
+
+        for &impl_id in tcx.hir().trait_impls(drop_trait_did) {
+            if_chain! {
+                if let Some(trait_ref) = tcx.impl_trait_ref(impl_id);
+                if let TyKind::Adt(adt_def, _) = trait_ref.skip_binder().self_ty().kind();
+                if let Some(fields) = pinned_fields.get(&adt_def.did());
+                if let Some(&drop_did) = tcx.associated_item_def_ids(impl_id).first();
+                let move_spans = inner::pinned_field_moves(self.rcx, drop_did, fields);
+                if !move_spans.is_empty();
+                then {
+                    let behavior_flag = BehaviorFlag::DROP_MOVE;
+                    if behavior_flag.report_level() < self.rcx.report_level() {
+                        continue;
+                    }
+
+                    let impl_span = tcx.hir().span_with_body(tcx.local_def_id_to_hir_id(impl_id));
+                    let mut color_span = unwrap_or!(
+                        utils::ColorSpan::new(tcx, impl_span).context(InvalidSpan) => continue
+                    );
+
+                    for span in move_spans {
+                        color_span.add_sub_span(Color::Red, span);
+                    }
+
+                    rudra_report(Report::with_color_span(
+                        tcx,
+                        behavior_flag.report_level(),
+                        AnalysisKind::PinSoundness(behavior_flag),
+                        format!(
+                            "`Drop` impl moves a structurally pinned field of `{}`",
+                            tcx.def_path_str(adt_def.did())
+                        ),
+                        &color_span,
+                    ))
+                }
+            }
+        }
+    }
+}
+
+mod inner {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct UncheckedPinStatus {
+        pins: Vec<Span>,
+        moves: Vec<Span>,
+        behavior_flag: BehaviorFlag,
+    }
+
+    impl UncheckedPinStatus {
+        pub fn behavior_flag(&self) -> BehaviorFlag {
+            self.behavior_flag
+        }
+
+        pub fn pin_spans(&self) -> &Vec<Span> {
+            &self.pins
+        }
+
+        pub fn move_spans(&self) -> &Vec<Span> {
+            &self.moves
+        }
+    }
+
+    /// Data pinned without `Unpin` guarantees
+    enum PinnedData<'tcx> {
+        /// The pointee of `Pin::new_unchecked(&mut place)`
+        Place(mir::Place<'tcx>),
+        /// The `&mut T` returned by `Pin::get_unchecked_mut`
+        Reference(mir::Local),
+    }
+
+    pub struct UncheckedPinBodyAnalyzer<'a, 'tcx> {
+        rcx: RudraCtxt<'tcx>,
+        body: &'a ir::Body<'tcx>,
+        status: UncheckedPinStatus,
+    }
+
+    impl<'tcx> UncheckedPinBodyAnalyzer<'_, 'tcx> {
+        pub fn analyze_body(rcx: RudraCtxt<'tcx>, body_id: BodyId) -> Option<UncheckedPinStatus> {
+            let tcx = rcx.tcx();
+            let body_did = tcx.hir().body_owner_def_id(body_id).to_def_id();
+
+            // Unsafe functions may require their callers not to move the data
+            if !matches!(tcx.def_kind(body_did), DefKind::Fn | DefKind::AssocFn)
+                || tcx.fn_sig(body_did).skip_binder().safety() != Safety::Safe
+                || !ContainsUnsafe::contains_unsafe(tcx, body_id)
+            {
+                return Some(Default::default());
+            }
+
+            match rcx.translate_body(body_did).as_ref() {
+                Err(e) => {
+                    // MIR is not available for def - log it and continue
+                    e.log();
+                    None
+                }
+                Ok(body) => {
+                    let mut body_analyzer = UncheckedPinBodyAnalyzer {
+                        rcx,
+                        body,
+                        status: Default::default(),
+                    };
+                    body_analyzer.analyze();
+                    Some(body_analyzer.status)
+                }
+            }
+        }
+
+        fn analyze(&mut self) {
+            let tcx = self.rcx.tcx();
+            let ext = tcx.ext();
+
+            for (id, terminator) in self.body.terminators().enumerate() {
+                let pinned = match terminator.kind {
+                    ir::TerminatorKind::StaticCall {
+                        callee_did,
+                        ref args,
+                        destination,
+                        ..
+                    } => {
+                        if ext.match_def_path(callee_did, &PIN_NEW_UNCHECKED) {
+                            args.first()
+                                .and_then(|arg| borrowed_place(self.body, arg))
+                                .map(PinnedData::Place)
+                        } else if ext.match_def_path(callee_did, &PIN_GET_UNCHECKED_MUT) {
+                            destination.map(|(place, _)| PinnedData::Reference(place.local))
+                        } else {
+                            None
+                        }
+                    }
+                    _ => None,
+                };
+                let pinned = match pinned {
+                    Some(pinned) => pinned,
+                    None => continue,
+                };
+
+                let move_spans = self.moves_after(id, &pinned);
+                if !move_spans.is_empty() {
+                    self.status.behavior_flag |= BehaviorFlag::MOVE_AFTER_PIN;
+                    self.status.pins.push(terminator.original.source_info.span);
+                    self.status.moves.extend(move_spans);
+                }
+            }
+        }
+
+        /// Returns the spans of moves of `!Unpin` data that overlaps with `pinned`,
+        /// in blocks reachable from `block`.
+        fn moves_after(&self, block: usize, pinned: &PinnedData<'tcx>) -> Vec<Span> {
+            let tcx = self.rcx.tcx();
+            let typing_env = self.body.original.typing_env(tcx);
+            let derived = match pinned {
+                PinnedData::Reference(local) => self.body.derived_locals(*local),
+                PinnedData::Place(_) => FxHashSet::default(),
+            };
+
+            let is_pinned = |moved_place: mir::Place<'tcx>| {
+                !moved_place.ty(self.body, tcx).ty.is_unpin(tcx, typing_env)
+            };
+            // `moved_place` is moved by value
+            let moves_pinned = |moved_place: mir::Place<'tcx>| match pinned {
+                PinnedData::Place(pinned_place) => {
+                    !moved_place.is_indirect()
+                        && pinned_place.local == moved_place.local
+                        && overlaps(*pinned_place, moved_place)
+                        && is_pinned(moved_place)
+                }
+                PinnedData::Reference(_) => false,
+            };
+            // `moved_place` is moved out through the pointer in `operand`
+            let moves_pinned_out = |operand: &Operand<'tcx>, moved_place: mir::Place<'tcx>| {
+                let from_pinned = match pinned {
+                    PinnedData::Place(pinned_place) => {
+                        self.body.borrowed_root(pinned_place.local)
+                            == self.body.borrowed_root(moved_place.local)
+                            && overlaps(*pinned_place, moved_place)
+                    }
+                    PinnedData::Reference(_) => operand
+                        .place()
+                        .is_some_and(|place| derived.contains(&place.local)),
+                };
+                from_pinned && is_pinned(moved_place)
+            };
+
+            let mut move_spans = Vec::new();
+            for id in self.body.reachable_blocks(block, &FxHashSet::default()) {
+                let basic_block = &self.body.basic_blocks[id];
+
+                // Moving the pinned local itself, e.g. `let moved = value;`
+                for statement in basic_block.statements.iter() {
+                    if let StatementKind::Assign(box (_, Rvalue::Use(Operand::Move(place)))) =
+                        &statement.kind
+                    {
+                        if moves_pinned(*place) {
+                            move_spans.push(statement.source_info.span);
+                        }
+                    }
+                }
+
+                // Moving out through a reference, e.g. `mem::replace(&mut this.field, ..)`
+                if let ir::TerminatorKind::StaticCall {
+                    callee_did,
+                    ref args,
+                    ..
+                } = basic_block.terminator.kind
+                {
+                    let is_move_out = MOVE_OUT_LIST.contains(&tcx.ext().get_def_path(callee_did));
+                    let moves = args.iter().any(|arg| match arg {
+                        Operand::Move(place) if moves_pinned(*place) => true,
+                        _ if is_move_out => borrowed_place(self.body, arg)
+                            .is_some_and(|place| moves_pinned_out(arg, place)),
+                        _ => false,
+                    });
+                    if moves {
+                        move_spans.push(basic_block.terminator.original.source_info.span);
+                    }
+                }
+            }
+
+            move_spans
+        }
+    }
+
+    /// Returns the fields that are pinned through `Pin::new_unchecked(&mut this.field)`
+    /// or `pinned.map_unchecked_mut(|this| &mut this.field)`, per ADT.
+    pub fn pinned_fields(rcx: RudraCtxt<'_>) -> FxHashMap<DefId, FxHashSet<FieldIdx>> {
+        let tcx = rcx.tcx();
+        let ext = tcx.ext();
+        let mut pinned_fields: FxHashMap<DefId, FxHashSet<FieldIdx>> = FxHashMap::default();
+
+        for (_ty_hir_id, (body_id, _related_item_span)) in rcx.types_with_related_items() {
+            let body_did = tcx.hir().body_owner_def_id(body_id).to_def_id();
+            let translation = rcx.translate_body(body_did);
+            let body = unwrap_or!(translation.as_ref() => continue);
+
+            for terminator in body.terminators() {
+                if let ir::TerminatorKind::StaticCall {
+                    callee_did,
+                    ref args,
+                    ..
+                } = terminator.kind
+                {
+                    if ext.match_def_path(callee_did, &PIN_NEW_UNCHECKED) {
+                        let place = args.first().and_then(|arg| borrowed_place(body, arg));
+                        if let Some((adt_did, field)) =
+                            place.and_then(|place| projected_field(tcx, body, place))
+                        {
+                            pinned_fields.entry(adt_did).or_default().insert(field);
+                        }
+                    } else if ext.match_def_path(callee_did, &PIN_MAP_UNCHECKED)
+                        || ext.match_def_path(callee_did, &PIN_MAP_UNCHECKED_MUT)
+                    {
+                        let closure_did = match args.get(1).map(|arg| arg.ty(body, tcx).kind()) {
+                            Some(&TyKind::Closure(closure_did, _)) => closure_did,
+                            _ => continue,
+                        };
+                        let closure_translation = rcx.translate_body(closure_did);
+                        let closure_body = unwrap_or!(closure_translation.as_ref() => continue);
+                        for statement in closure_body.statements() {
+                            if let StatementKind::Assign(box (_, Rvalue::Ref(_, _, place))) =
+                                &statement.kind
+                            {
+                                if let Some((adt_did, field)) =
+                                    projected_field(tcx, closure_body, *place)
+                                {
+                                    pinned_fields.entry(adt_did).or_default().insert(field);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        pinned_fields
+    }
+
+    /// Returns the names of generic parameters that appear owned in pinned fields,
+    /// but are not bounded by `Unpin` in the `Unpin` impl.
+    pub fn unbounded_pinned_params<'tcx>(
+        tcx: TyCtxt<'tcx>,
+        impl_id: LocalDefId,
+        unpin_trait_did: DefId,
+        adt_def: AdtDef<'tcx>,
+        impl_trait_substs: GenericArgsRef<'tcx>,
+        fields: &FxHashSet<FieldIdx>,
+    ) -> Vec<Symbol> {
+        let adt_did = adt_def.did();
+        let adt_ty = tcx.type_of(adt_did).instantiate_identity();
+
+        // Generic params that only occur within `PhantomData<_>` cannot be pinned
+        let phantom_params = phantom_indices(tcx, adt_ty);
+
+        // Field types are written in terms of the ADT's own generic parameters
+        let mut pinned_params = FxHashSet::default();
+        for (field_idx, field) in adt_def.non_enum_variant().fields.iter_enumerated() {
+            if fields.contains(&field_idx) {
+                let field_ty = tcx.type_of(field.did).instantiate_identity();
+                for PreMapIdx(idx) in owned_generic_params_in_ty(tcx, field_ty) {
+                    if !phantom_params.contains(&idx) {
+                        pinned_params.insert(PostMapIdx(idx));
+                    }
+                }
+            }
+        }
+
+        let generic_param_idx_map =
+            generic_param_idx_mapper(&tcx.generics_of(adt_did).own_params, impl_trait_substs);
+
+        // Parameters replaced with concrete types are checked by the compiler
+        pinned_params.retain(|idx| generic_param_idx_map.values().any(|mapped| mapped == idx));
+
+        for atom in tcx
+            .param_env(impl_id)
+            .caller_bounds()
+            .iter()
+            .map(|x| x.kind().skip_binder())
+        {
+            if let ClauseKind::Trait(trait_predicate) = atom {
+                if let TyKind::Param(param_ty) = trait_predicate.self_ty().kind() {
+                    if trait_predicate.def_id() == unpin_trait_did {
+                        if let Some(mapped_idx) =
+                            generic_param_idx_map.get(&PreMapIdx(param_ty.index))
+                        {
+                            pinned_params.remove(mapped_idx);
+                        }
+                    }
+                }
+            }
+        }
+
+        let generics = tcx.generics_of(adt_did);
+        let mut names = pinned_params
+            .into_iter()
+            .map(|PostMapIdx(idx)| generics.param_at(idx as usize, tcx).name)
+            .collect::<Vec<_>>();
+        names.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        names
+    }
+
+    /// Returns the spans of calls in `drop_did` that move `!Unpin` pinned fields out of `self`.
+    pub fn pinned_field_moves(
+        rcx: RudraCtxt<'_>,
+        drop_did: DefId,
+        fields: &FxHashSet<FieldIdx>,
+    ) -> Vec<Span> {
+        let tcx = rcx.tcx();
+        let translation = rcx.translate_body(drop_did);
+        let body = unwrap_or!(translation.as_ref() => return Vec::new());
+        let typing_env = body.original.typing_env(tcx);
+        // `self` of `fn drop(&mut self)`
+        let self_local = mir::Local::from_usize(1);
+
+        let mut move_spans = Vec::new();
+        for terminator in body.terminators() {
+            if let ir::TerminatorKind::StaticCall {
+                callee_did,
+                ref args,
+                ..
+            } = terminator.kind
+            {
+                if !MOVE_OUT_LIST.contains(&tcx.ext().get_def_path(callee_did)) {
+                    continue;
+                }
+
+                let moves_pinned_field = args.iter().any(|arg| {
+                    borrowed_place(body, arg).is_some_and(|place| {
+                        body.borrowed_root(place.local) == self_local
+                            && !place.ty(body, tcx).ty.is_unpin(tcx, typing_env)
+                            && projected_field(tcx, body, place)
+                                .is_some_and(|(_, field)| fields.contains(&field))
+                    })
+                });
+                if moves_pinned_field {
+                    move_spans.push(terminator.original.source_info.span);
+                }
+            }
+        }
+
+        move_spans
+    }
+
+    /// Returns the place that the pointer in `operand` was created from,
+    /// looking through reborrows such as `&raw const (*_3)`.
+    fn borrowed_place<'tcx>(
+        body: &ir::Body<'tcx>,
+        operand: &Operand<'tcx>,
+    ) -> Option<mir::Place<'tcx>> {
+        let mut local = operand.place()?.as_local()?;
+        let mut visited = FxHashSet::default();
+        while visited.insert(local) {
+            let source = body
+                .statements()
+                .find_map(|statement| match &statement.kind {
+                    StatementKind::Assign(box (
+                        place,
+                        Rvalue::Ref(_, _, source) | Rvalue::RawPtr(_, source),
+                    )) if place.as_local() == Some(local) => Some(*source),
+                    _ => None,
+                })?;
+
+            match source.projection.as_slice() {
+                [ProjectionElem::Deref] => local = source.local,
+                _ => return Some(source),
+            }
+        }
+        None
+    }
+
+    /// Returns the struct field that `place` ends in, as in `(*this).field`.
+    fn projected_field<'tcx>(
+        tcx: TyCtxt<'tcx>,
+        body: &ir::Body<'tcx>,
+        place: mir::Place<'tcx>,
+    ) -> Option<(DefId, FieldIdx)> {
+        match place.as_ref().last_projection()? {
+            (base, ProjectionElem::Field(field, _)) => match base.ty(body, tcx).ty.kind() {
+                TyKind::Adt(adt_def, _) if adt_def.is_struct() => Some((adt_def.did(), field)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns true if the projections of one place are a prefix of the other's.
+    fn overlaps<'tcx>(a: mir::Place<'tcx>, b: mir::Place<'tcx>) -> bool {
+        a.projection
+            .iter()
+            .zip(b.projection.iter())
+            .all(|(a_elem, b_elem)| a_elem == b_elem)
+    }
+}
+
+bitflags! {
+    #[derive(Default)]
+    pub struct BehaviorFlag: u8 {
+        // Moves data pinned with `Pin::new_unchecked` or `Pin::get_unchecked_mut`
+        const MOVE_AFTER_PIN = 0b00000001;
+        // Implements `Unpin` without bounds on structurally pinned generic parameters
+        const UNPIN_IMPL = 0b00000010;
+        // Moves a structurally pinned field in `Drop::drop`
+        const DROP_MOVE = 0b00000100;
+    }
+}
+
+impl IntoReportLevel for BehaviorFlag {
+    fn report_level(&self) -> ReportLevel {
+        if self.intersects(BehaviorFlag::MOVE_AFTER_PIN | BehaviorFlag::DROP_MOVE) {
+            ReportLevel::Error
+        } else {
+            // Structural pinning is inferred from projections in the crate
+            ReportLevel::Warning
+        }
+    }
+}
@@ -0,0 +1,473 @@
+//! Reentrancy detector for unsafe code that calls user code while holding a mutable alias
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::BodyId;
+use rustc_middle::mir::visit::{MutatingUseContext, PlaceContext, Visitor};
+use rustc_middle::mir::{
+    self, BorrowKind, Location, Mutability, Operand, ProjectionElem, Rvalue, StatementKind,
+};
+use rustc_middle::ty::{GenericArgKind, Ty, TyKind, TypingEnv};
+use rustc_span::{sym, Span};
+
+use snafu::{Backtrace, Snafu};
+use termcolor::Color;
+
+use crate::prelude::*;
+use crate::{
+    analysis::{unsafe_dataflow::calls_user_provided_code, AnalysisKind, IntoReportLevel},
+    ir,
+    report::{Report, ReportLevel},
+    utils,
+    visitor::{ContainsUnsafe, UnsafeBlockSpans},
+};
+
+#[derive(Debug, Snafu)]
+pub enum ReentrancyError {
+    InvalidSpan { backtrace: Backtrace },
+}
+
+impl AnalysisError for ReentrancyError {
+    fn kind(&self) -> AnalysisErrorKind {
+        use ReentrancyError::*;
+        match self {
+            InvalidSpan { .. } => AnalysisErrorKind::Unreachable,
+        }
+    }
+}
+
+pub struct ReentrancyChecker<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+}
+
+impl<'tcx> ReentrancyChecker<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+        ReentrancyChecker { rcx }
+    }
+
+    pub fn analyze(self) {
+        let tcx = self.rcx.tcx();
+        let hir_map = tcx.hir();
+
+        // Iterates all (type, related function) pairs
+        for (_ty_hir_id, (body_id, related_item_span)) in self.rcx.types_with_related_items() {
+            if let Some(status) = inner::ReentrancyBodyAnalyzer::analyze_body(self.rcx, body_id) {
+                let behavior_flag = status.behavior_flag();
+                if !behavior_flag.is_empty()
+                    && behavior_flag.report_level() >= self.rcx.report_level()
+                {
+                    let mut color_span = unwrap_or!(
+                        utils::ColorSpan::new(tcx, related_item_span).context(InvalidSpan) => continue
+                    );
+
+                    for &span in status.alias_spans() {
+                        color_span.add_sub_span(Color::Yellow, span);
+                    }
+
+                    for &span in status.reentrant_call_spans() {
+                        color_span.add_sub_span(Color::Red, span);
+                    }
+
+                    rudra_report(Report::with_color_span(
+                        tcx,
+                        behavior_flag.report_level(),
+                        AnalysisKind::Reentrancy(behavior_flag),
+                        format!(
+                            "User code may re-enter `self` while a mutable alias is live in `{}`",
+                            tcx.def_path_str(hir_map.body_owner_def_id(body_id).to_def_id())
+                        ),
+                        &color_span,
+                    ))
+                }
+            }
+        }
+    }
+}
+
+mod inner {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct ReentrancyStatus {
+        aliases: Vec<Span>,
+        reentrant_calls: Vec<Span>,
+        behavior_flag: BehaviorFlag,
+    }
+
+    impl ReentrancyStatus {
+        pub fn behavior_flag(&self) -> BehaviorFlag {
+            self.behavior_flag
+        }
+
+        pub fn alias_spans(&self) -> &Vec<Span> {
+            &self.aliases
+        }
+
+        pub fn reentrant_call_spans(&self) -> &Vec<Span> {
+            &self.reentrant_calls
+        }
+    }
+
+    /// A `&mut` or `*mut` to `self` data created in an unsafe block
+    struct MutableAlias {
+        local: mir::Local,
+        block: usize,
+        flag: BehaviorFlag,
+        span: Span,
+    }
+
+    /// An unresolvable generic call that receives something that can reach `self`
+    struct ReentrantCall {
+        block: usize,
+        target: usize,
+        flag: BehaviorFlag,
+        span: Span,
+    }
+
+    pub struct ReentrancyBodyAnalyzer<'a, 'tcx> {
+        rcx: RudraCtxt<'tcx>,
+        body: &'a ir::Body<'tcx>,
+        typing_env: TypingEnv<'tcx>,
+        self_ty: Ty<'tcx>,
+        status: ReentrancyStatus,
+    }
+
+    impl<'tcx> ReentrancyBodyAnalyzer<'_, 'tcx> {
+        pub fn analyze_body(rcx: RudraCtxt<'tcx>, body_id: BodyId) -> Option<ReentrancyStatus> {
+            let tcx = rcx.tcx();
+            let body_did = tcx.hir().body_owner_def_id(body_id).to_def_id();
+
+            // Only methods of an impl block have a known `Self` type
+            let impl_did = match tcx.opt_associated_item(body_did) {
+                Some(assoc_item) if assoc_item.fn_has_self_parameter => {
+                    tcx.impl_of_method(body_did)?
+                }
+                _ => return Some(Default::default()),
+            };
+
+            if ContainsUnsafe::contains_unsafe(tcx, body_id) {
+                match rcx.translate_body(body_did).as_ref() {
+                    Err(e) => {
+                        // MIR is not available for def - log it and continue
+                        e.log();
+                        None
+                    }
+                    Ok(body) => {
+                        let self_ty =
+                            tcx.erase_regions(tcx.type_of(impl_did).instantiate_identity());
+                        let body_analyzer = ReentrancyBodyAnalyzer {
+                            rcx,
+                            body,
+                            typing_env: body.original.typing_env(tcx),
+                            self_ty,
+                            status: Default::default(),
+                        };
+                        Some(body_analyzer.analyze(UnsafeBlockSpans::collect(tcx, body_id)))
+                    }
+                }
+            } else {
+                // Mutable aliases from raw pointers can only be created in unsafe code
+                Some(Default::default())
+            }
+        }
+
+        fn analyze(mut self, unsafe_spans: Vec<Span>) -> ReentrancyStatus {
+            let aliases = self.mutable_aliases(&unsafe_spans);
+            if aliases.is_empty() {
+                return self.status;
+            }
+            let calls = self.reentrant_calls();
+
+            for alias in aliases.iter() {
+                let alias_uses = self.use_blocks(&self.body.derived_locals(alias.local));
+                let reachable_from_alias = self
+                    .body
+                    .reachable_blocks(alias.block, &FxHashSet::default());
+
+                let mut live_calls = Vec::new();
+                for call in calls.iter() {
+                    if call.block != alias.block && !reachable_from_alias.contains(&call.block) {
+                        continue;
+                    }
+
+                    // The alias is used again after the call returns
+                    let mut after_call = self
+                        .body
+                        .reachable_blocks(call.target, &FxHashSet::default());
+                    after_call.insert(call.target);
+                    if alias_uses.iter().any(|block| after_call.contains(block)) {
+                        live_calls.push(call);
+                    }
+                }
+
+                if !live_calls.is_empty() {
+                    self.status.behavior_flag |= alias.flag;
+                    self.status.aliases.push(alias.span);
+                    for call in live_calls {
+                        self.status.behavior_flag |= call.flag;
+                        self.status.reentrant_calls.push(call.span);
+                    }
+                }
+            }
+
+            self.status.reentrant_calls.sort();
+            self.status.reentrant_calls.dedup();
+            self.status
+        }
+
+        /// `&mut *ptr` and `*mut` pointers derived from `self` in an unsafe block
+        fn mutable_aliases(&self, unsafe_spans: &[Span]) -> Vec<MutableAlias> {
+            let self_derived = self.body.derived_locals(mir::Local::from_usize(1));
+            let from_self = |operand: &Operand<'tcx>| {
+                operand
+                    .place()
+                    .is_some_and(|place| self_derived.contains(&place.local))
+            };
+
+            let mut aliases = Vec::new();
+            for (block, data) in self.body.basic_blocks.iter().enumerate() {
+                for statement in data.statements.iter() {
+                    let span = statement.source_info.span;
+                    if !unsafe_spans
+                        .iter()
+                        .any(|unsafe_span| unsafe_span.contains(span))
+                    {
+                        continue;
+                    }
+
+                    if let StatementKind::Assign(box (place, rvalue)) = &statement.kind {
+                        let dest_ty = self.body.local_decls[place.local.index()].ty;
+                        let flag = match rvalue {
+                            Rvalue::Ref(_, BorrowKind::Mut { .. }, source)
+                                if source.is_indirect_first_projection()
+                                    && self.body.local_decls[source.local.index()]
+                                        .ty
+                                        .is_raw_ptr()
+                                    && self_derived.contains(&source.local) =>
+                            {
+                                BehaviorFlag::MUT_REF
+                            }
+                            Rvalue::RawPtr(_, source)
+                                if matches!(dest_ty.kind(), TyKind::RawPtr(_, Mutability::Mut))
+                                    && self_derived.contains(&source.local) =>
+                            {
+                                BehaviorFlag::RAW_MUT
+                            }
+                            Rvalue::Use(operand) | Rvalue::Cast(_, operand, _)
+                                if matches!(dest_ty.kind(), TyKind::RawPtr(_, Mutability::Mut))
+                                    && from_self(operand) =>
+                            {
+                                BehaviorFlag::RAW_MUT
+                            }
+                            _ => continue,
+                        };
+
+                        aliases.push(MutableAlias {
+                            local: place.local,
+                            block,
+                            flag,
+                            span,
+                        });
+                    }
+                }
+            }
+
+            aliases
+        }
+
+        /// Unresolvable generic calls that receive `&Self`, `Rc<Self>`
+        /// or a closure capturing a reference to `self`
+        fn reentrant_calls(&self) -> Vec<ReentrantCall> {
+            let tcx = self.rcx.tcx();
+            let mut calls = Vec::new();
+
+            for (block, data) in self.body.basic_blocks.iter().enumerate() {
+                if let ir::TerminatorKind::StaticCall {
+                    callee_did,
+                    callee_substs,
+                    ref args,
+                    destination: Some((_, target)),
+                } = data.terminator.kind
+                {
+                    if !calls_user_provided_code(
+                        self.rcx,
+                        self.typing_env,
+                        callee_did,
+                        callee_substs,
+                    ) {
+                        continue;
+                    }
+
+                    let reaching_flags: Vec<BehaviorFlag> = args
+                        .iter()
+                        .filter_map(|arg| match arg.place() {
+                            Some(place) if place.projection.is_empty() => {
+                                self.local_reaches_self(place.local, &mut FxHashSet::default())
+                            }
+                            _ => self.reaches_self(arg.ty(self.body, tcx)),
+                        })
+                        .collect();
+                    if reaching_flags.is_empty() {
+                        continue;
+                    }
+
+                    calls.push(ReentrantCall {
+                        block,
+                        target,
+                        flag: reaching_flags
+                            .into_iter()
+                            .fold(BehaviorFlag::empty(), |acc, flag| acc | flag),
+                        span: data.terminator.original.source_info.span,
+                    });
+                }
+            }
+
+            calls
+        }
+
+        /// Like [`Self::reaches_self`], but also checks the values that `local` was built from.
+        /// `&closure as &dyn Fn()` and `(arg,)` hide the types of their parts.
+        fn local_reaches_self(
+            &self,
+            local: mir::Local,
+            visited: &mut FxHashSet<mir::Local>,
+        ) -> Option<BehaviorFlag> {
+            if !visited.insert(local) {
+                return None;
+            }
+            if let Some(flag) = self.reaches_self(self.body.local_decls[local.index()].ty) {
+                return Some(flag);
+            }
+
+            let sources: Vec<mir::Local> = self
+                .body
+                .statements()
+                .filter_map(|statement| match &statement.kind {
+                    StatementKind::Assign(box (place, rvalue))
+                        if place.as_local() == Some(local) =>
+                    {
+                        Some(rvalue)
+                    }
+                    _ => None,
+                })
+                .flat_map(|rvalue| match rvalue {
+                    Rvalue::Ref(_, _, source)
+                    | Rvalue::RawPtr(_, source)
+                    | Rvalue::CopyForDeref(source)
+                    | Rvalue::Use(Operand::Copy(source) | Operand::Move(source))
+                    | Rvalue::Cast(_, Operand::Copy(source) | Operand::Move(source), _) => {
+                        // Fields of `self` do not reach `self`
+                        if source
+                            .projection
+                            .iter()
+                            .all(|elem| elem == ProjectionElem::Deref)
+                        {
+                            vec![source.local]
+                        } else {
+                            vec![]
+                        }
+                    }
+                    Rvalue::Aggregate(_, operands) => operands
+                        .iter()
+                        .filter_map(Operand::place)
+                        .filter(|place| place.projection.is_empty())
+                        .map(|place| place.local)
+                        .collect(),
+                    _ => vec![],
+                })
+                .collect();
+
+            sources
+                .into_iter()
+                .find_map(|source| self.local_reaches_self(source, visited))
+        }
+
+        /// Returns `Some` if a value of type `ty` can reach a value of type `Self`.
+        /// The returned flag describes how `self` is reached.
+        fn reaches_self(&self, ty: Ty<'tcx>) -> Option<BehaviorFlag> {
+            let tcx = self.rcx.tcx();
+            let mut reaches = false;
+            let mut flag = BehaviorFlag::empty();
+
+            for arg in ty.walk() {
+                if let GenericArgKind::Type(inner_ty) = arg.unpack() {
+                    match inner_ty.kind() {
+                        TyKind::Ref(_, pointee, _) | TyKind::RawPtr(pointee, _)
+                            if *pointee == self.self_ty =>
+                        {
+                            reaches = true;
+                        }
+                        TyKind::Adt(adt_def, substs)
+                            if (tcx.is_diagnostic_item(sym::Rc, adt_def.did())
+                                || tcx.is_diagnostic_item(sym::Arc, adt_def.did()))
+                                && substs.type_at(0) == self.self_ty =>
+                        {
+                            reaches = true;
+                            flag |= BehaviorFlag::SHARED_OWNER;
+                        }
+                        _ => (),
+                    }
+                }
+            }
+
+            if reaches {
+                if matches!(ty.peel_refs().kind(), TyKind::Closure(..)) {
+                    flag |= BehaviorFlag::CLOSURE;
+                }
+                Some(flag)
+            } else {
+                None
+            }
+        }
+
+        /// Blocks that use one of `locals`
+        fn use_blocks(&self, locals: &FxHashSet<mir::Local>) -> FxHashSet<usize> {
+            let mut use_collector = UseCollector {
+                locals,
+                blocks: FxHashSet::default(),
+            };
+            use_collector.visit_body(&self.body.original);
+            use_collector.blocks
+        }
+    }
+
+    struct UseCollector<'a> {
+        locals: &'a FxHashSet<mir::Local>,
+        blocks: FxHashSet<usize>,
+    }
+
+    impl Visitor<'_> for UseCollector<'_> {
+        fn visit_local(&mut self, local: mir::Local, context: PlaceContext, location: Location) {
+            // Assigning a new value is not a use of the old one
+            let is_use = !matches!(
+                context,
+                PlaceContext::NonUse(_) | PlaceContext::MutatingUse(MutatingUseContext::Store)
+            );
+            if is_use && self.locals.contains(&local) {
+                self.blocks.insert(location.block.index());
+            }
+        }
+    }
+}
+
+bitflags! {
+    #[derive(Default)]
+    pub struct BehaviorFlag: u8 {
+        // `&mut` created from a raw pointer to `self` data
+        const MUT_REF = 0b00000001;
+        // `*mut` pointer to `self` data
+        const RAW_MUT = 0b00000010;
+        // `self` is reached through a closure capture
+        const CLOSURE = 0b00000100;
+        // `self` is reached through `Rc<Self>` or `Arc<Self>`
+        const SHARED_OWNER = 0b00001000;
+    }
+}
+
+impl IntoReportLevel for BehaviorFlag {
+    fn report_level(&self) -> ReportLevel {
+        if self.contains(BehaviorFlag::MUT_REF) {
+            ReportLevel::Error
+        } else {
+            ReportLevel::Warning
+        }
+    }
+}
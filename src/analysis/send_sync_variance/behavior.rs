@@ -5,18 +5,6 @@ use super::*;
 /*
 #[derive(Debug)]
 pub(crate) enum AdtBehavior {
-    // Acts like a shared pointer (e.g. Arc, Rc, Beef).
-    // For `impl Send`, `T: Send + Sync` is needed. (filtering criteria fortified)
-    // Identify as 'PtrLike' if ADT satisfies both of the conditions below:
-    //
-    // Condition #1:
-    //    At least one API (of ADT) satisfies both of the following conditions.
-    //    (1) `&self` contained within first parameter
-    //    (2) return type contains either `&T` or `&mut T`
-    // Condition #2:
-    //    Cloning the ADT does not clone `T`
-    // PtrLike,
-
     // Solely moves `T` and doesn't return `&T` within API output.
     // For `impl Sync`, `T: Send` is needed.
     // Identify as 'ConcurrentQueue' if all `&self` methods of ADT only
@@ -42,54 +30,58 @@ pub(crate) enum AdtBehavior {
 bitflags! {
     // For each generic parameter of an ADT, ADT shows one or more of the behaviors below.
     pub struct AdtBehavior: u8 {
-        /* TODO: Implement fine-grained handling for ptr-like types.
         // At least one API of ADT takes `&self` within input and `&T` within output.
         const REF_REF = 0b00000001;
-        // `Clone` impl clones on generic param `T.
+        // `Clone` impl clones on generic param `T`, or the ADT doesn't implement `Clone`.
         const CLONED = 0b00000010;
-        */
 
         // `T` only appears in ADT API input/output as owned `T`.
         // (Sender/Receiver side of Queue APIs)
         // e.g. `T`, `Box<T>`, `Option<T>`, `Result<T, !>`
         const PASS_OWNED = 0b00000100;
 
-        // Satisfies both of the following conditions:
+        // Satisfies all of the following conditions:
         // * `&T` is not exposed in any method ret type.
-        // * TODO: There exists a method that takes closure `Fn(&T)` as input.
+        // * `&T` is not handed to any callback (e.g. closure `Fn(&T)`) taken as method input.
+        // * No method returns a local ADT whose APIs expose `&T` (e.g. a guard with `Deref`).
         //
-        // Current limitation:
-        // This category may include cases where `&T` isn't directly exposed as ret type,
-        // but the ret type (ADT) has an API to expose `&T`.
-        // Greater precision can be achieved by implementing the following:
-        // * For more precision: check APIs of the ret types.
-        // * For even more precision: check (APIs of the ret types of (APIs of the ret types)).
-        // * Need more precision?: recurse until reaching your predefined maximum search depth.
-        // Inspection cost expected to be exponential to # of layers (of ADT).
+        // The APIs of returned ADTs are searched recursively, until the chain of methods
+        // reaches `api_search_depth`. `&T` exposed through a longer chain is missed.
         const NO_DEREF = 0b00001000;
 
         // Satisfies either one of the following conditions:
         // * `&T` is exposed in method return type.
-        // * TODO: `&T` can be accessed by method input closure `Fn(&T)`
-        // Current limitation:
-        // May miss out on cases where `&T` isn't directly exposed as ret type,
-        // but the ret type (ADT) has an API to expose `&T`.
+        // * `&T` can be accessed by method input closure `Fn(&T)`
+        // * A method returns a local ADT whose APIs expose `&T`, within `api_search_depth`
+        //   methods (e.g. `Lock::lock` -> `<Guard<'_, T> as Deref>::deref`).
+        // The chain of methods that exposes `&T` is kept for the report.
         const DEREF = 0b00010000;
+
+        // `&mut T` is handed to a callback taken as method input
+        // (e.g. `Mutex::with`-style `FnOnce(&mut T)`).
+        const EXCLUSIVE = 0b00100000;
     }
 }
 
 impl AdtBehavior {
-    /* TODO: Implement fine-grained handling for ptr-like types.
-    fn ptr_like(&self) -> bool {
-        self.intersects(Cond::REF_REF & !Cond::CLONED)
+    /// Acts like a shared pointer (e.g. Arc, Rc, Beef).
+    /// For `impl Send`, `T: Send + Sync` is needed.
+    /// Identified if at least one `&self` method returns `&T`,
+    /// and cloning the ADT does not clone `T`.
+    pub fn is_ptr_like(&self) -> bool {
+        self.contains(AdtBehavior::REF_REF) && !self.contains(AdtBehavior::CLONED)
     }
-    */
     pub fn is_concurrent_queue(&self) -> bool {
         !self.intersects(AdtBehavior::DEREF) && self.intersects(AdtBehavior::PASS_OWNED)
     }
     pub fn is_deref(&self) -> bool {
         self.intersects(AdtBehavior::DEREF)
     }
+    /// Acts like a lock (e.g. Mutex) that grants exclusive access to `T`.
+    /// For `impl Sync`, `T: Send` is needed.
+    pub fn is_mutex_like(&self) -> bool {
+        self.contains(AdtBehavior::EXCLUSIVE) && !self.contains(AdtBehavior::DEREF)
+    }
     pub fn _is_undefined(&self) -> bool {
         !self.intersects(AdtBehavior::DEREF) && !self.intersects(AdtBehavior::PASS_OWNED)
     }
@@ -103,96 +95,210 @@ enum FnType {
     TakeBorrowedSelf(DefId),
 }
 
-/// For each generic parameter (identified by index) of a given ADT,
-/// inspect fn signature & body to identify `AdtBehavior`.
-/// Inspects all `safe` methods of the given ADT, including methods from trait impls.
-pub(crate) fn adt_behavior<'tcx>(
-    rcx: RudraCtxt<'tcx>,
-    adt_did: DefId,
-) -> FxHashMap<PostMapIdx, AdtBehavior> {
-    let tcx = rcx.tcx();
-
-    // Set of `T`s that appear only as owned `T` in either input or output of APIs.
-    let mut owned_generic_params = FxHashSet::default();
-    // Set of `T`s that appear only as `&T` in return type of APIs.
-    let mut deref_generic_params = FxHashSet::default();
-
-    let adt_ty = tcx.type_of(adt_did);
-    // For ADT `Foo<A, B>` => adt_ty_name = `Foo`
-    let adt_ty_name = tcx.item_name(adt_did);
-
-    let adt_generic_params = &tcx.generics_of(adt_did).own_params;
-
-    if let Some(relevant_impls) = rcx.index_adt_cache(&adt_did) {
-        // Inspect `impl`s relevant to the given ADT.
-        for (impl_hir_id, impl_self_ty) in relevant_impls.iter() {
-            if let ty::TyKind::Adt(impl_self_adt_def, impl_substs) = impl_self_ty.kind() {
-                let impl_self_ty_name = tcx.item_name(impl_self_adt_def.did());
-                if adt_ty_name != impl_self_ty_name {
-                    continue;
-                }
+impl SendSyncVarianceChecker<'_> {
+    /// For each generic parameter (identified by index) of a given ADT,
+    /// inspect fn signature & body to identify `AdtBehavior`.
+    /// Results are cached per ADT in `behavior_map` (and `exposure_map`).
+    pub(crate) fn adt_behavior(&mut self, adt_did: DefId) -> FxHashMap<PostMapIdx, AdtBehavior> {
+        if let Some(behavior) = self.behavior_map.get(&adt_did) {
+            return behavior.clone();
+        }
+
+        // Placeholder entries cut cycles between ADTs whose APIs return each other.
+        self.behavior_map.insert(adt_did, FxHashMap::default());
+        self.exposure_map.insert(adt_did, FxHashMap::default());
+
+        let (behavior, exposure) = self.inspect_adt_apis(adt_did);
+        self.behavior_map.insert(adt_did, behavior.clone());
+        self.exposure_map.insert(adt_did, exposure);
+        behavior
+    }
+
+    /// Returns the chain of `&self` methods that exposes `&T` for each generic parameter `T`
+    /// of the given ADT, if any.
+    pub(crate) fn adt_exposure(&mut self, adt_did: DefId) -> FxHashMap<PostMapIdx, Vec<DefId>> {
+        self.adt_behavior(adt_did);
+        self.exposure_map[&adt_did].clone()
+    }
+
+    /// Inspects all `safe` methods of the given ADT, including methods from trait impls.
+    /// If a method returns another local ADT (e.g. a guard), the APIs of that ADT are
+    /// inspected as well, until the chain of methods reaches `api_search_depth`.
+    fn inspect_adt_apis(
+        &mut self,
+        adt_did: DefId,
+    ) -> (
+        FxHashMap<PostMapIdx, AdtBehavior>,
+        FxHashMap<PostMapIdx, Vec<DefId>>,
+    ) {
+        let rcx = self.rcx;
+        let tcx = rcx.tcx();
 
-                // At this point, `adt_ty.name == impl_self_ty_name` . (Foo == Foo)
-
-                // There are three possiblities now..
-                // (1) adt_ty != impl_self_ty . (Foo<A, B> != Foo<i64, B>)
-                // (2) adt_ty != impl_self_ty . (Foo<A, B> != Foo<A, B: Send>)
-                // (3) adt_ty == impl_self_ty . (Foo<A, B> == Foo<A, B>)
-                // TODO: Should we cater to each of the possibilities?
-
-                // DefIds of `safe` functions (within the given impl block)
-                // which either satisfy the following.
-                // * Take `&self` within its first parameter type.
-                // * Construct the Self type, but don't contain `self` within its inputs.
-                let relevant_safe_fns = tcx
-                    .associated_items(impl_hir_id.to_def_id())
-                    .in_definition_order()
-                    .filter_map(|assoc_item| {
-                        if assoc_item.kind == AssocKind::Fn {
-                            let fn_did = assoc_item.def_id;
-                            let fn_sig = tcx.fn_sig(fn_did).skip_binder();
-                            if let rustc_hir::Safety::Unsafe = fn_sig.safety() {
-                                return None;
+        // Set of `T`s that appear only as owned `T` in either input or output of APIs.
+        let mut owned_generic_params = FxHashSet::default();
+        // Set of `T`s that appear only as `&T` in return type of APIs.
+        let mut deref_generic_params = FxHashSet::default();
+        // Set of `T`s that appear as `&T` in APIs of ADTs returned by APIs (e.g. `Guard<'_, T>`).
+        let mut nested_deref_generic_params = FxHashSet::default();
+        // Shortest chain of methods that exposes `&T`, per generic parameter.
+        let mut exposure = FxHashMap::default();
+        // Set of `T`s that are handed to callbacks as `&T` (e.g. `with(&self, f: impl Fn(&T))`).
+        let mut callback_generic_params = FxHashSet::default();
+        // Set of `T`s that are handed to callbacks as `&mut T` (e.g. `with(&self, f: impl FnOnce(&mut T))`).
+        let mut exclusive_generic_params = FxHashSet::default();
+        // Set of `T`s that are cloned when the ADT is cloned.
+        // `None` if the ADT doesn't implement `Clone`.
+        let mut cloned_generic_params: Option<FxHashSet<PostMapIdx>> = None;
+
+        let clone_trait_did = clone_trait_def_id(tcx).ok();
+
+        let adt_ty = tcx.type_of(adt_did);
+        // For ADT `Foo<A, B>` => adt_ty_name = `Foo`
+        let adt_ty_name = tcx.item_name(adt_did);
+
+        let adt_generic_params = &tcx.generics_of(adt_did).own_params;
+
+        if let Some(relevant_impls) = rcx.index_adt_cache(&adt_did) {
+            // Inspect `impl`s relevant to the given ADT.
+            for (impl_hir_id, impl_self_ty) in relevant_impls.iter() {
+                if let ty::TyKind::Adt(impl_self_adt_def, impl_substs) = impl_self_ty.kind() {
+                    let impl_self_ty_name = tcx.item_name(impl_self_adt_def.did());
+                    if adt_ty_name != impl_self_ty_name {
+                        continue;
+                    }
+
+                    // At this point, `adt_ty.name == impl_self_ty_name` . (Foo == Foo)
+
+                    // There are three possiblities now..
+                    // (1) adt_ty != impl_self_ty . (Foo<A, B> != Foo<i64, B>)
+                    // (2) adt_ty != impl_self_ty . (Foo<A, B> != Foo<A, B: Send>)
+                    // (3) adt_ty == impl_self_ty . (Foo<A, B> == Foo<A, B>)
+                    // TODO: Should we cater to each of the possibilities?
+
+                    // Since each `impl` block may assign different indices to equivalent generic parameters,
+                    // We need one translation map per `impl` block.
+                    let generic_param_idx_map =
+                        generic_param_idx_mapper(adt_generic_params, impl_substs);
+
+                    // A `Clone` impl can only clone `T` if it is bounded by `T: Clone`.
+                    // (Derived impls always add the bound)
+                    if let Some(trait_ref) = tcx.impl_trait_ref(impl_hir_id.to_def_id()) {
+                        if Some(trait_ref.skip_binder().def_id) == clone_trait_did {
+                            let cloned =
+                                cloned_generic_params.get_or_insert_with(FxHashSet::default);
+                            for atom in tcx
+                                .param_env(impl_hir_id.to_def_id())
+                                .caller_bounds()
+                                .iter()
+                                .map(|x| x.kind().skip_binder())
+                            {
+                                if let ClauseKind::Trait(trait_predicate) = atom {
+                                    if let ty::TyKind::Param(param_ty) =
+                                        trait_predicate.self_ty().kind()
+                                    {
+                                        if Some(trait_predicate.def_id()) == clone_trait_did {
+                                            if let Some(&mapped_idx) = generic_param_idx_map
+                                                .get(&PreMapIdx(param_ty.index))
+                                            {
+                                                cloned.insert(mapped_idx);
+                                            }
+                                        }
+                                    }
+                                }
                             }
-                            if assoc_item.fn_has_self_parameter {
-                                // Check if the given method takes `&self` within its first parameter's type.
-                                // We already know the method takes `self` within its first parameter,
-                                // so we only check whether the first parameter contains a reference.
-                                // e.g. `&self`, `Box<&self>`, `Pin<&self>`, ..
-                                let mut walker =
-                                    fn_sig.inputs().iter().next().unwrap().skip_binder().walk();
-                                while let Some(node) = walker.next() {
-                                    if let GenericArgKind::Type(ty) = node.unpack() {
-                                        if let ty::TyKind::Ref(_, _, Mutability::Not) = ty.kind() {
-                                            return Some(FnType::TakeBorrowedSelf(fn_did));
+                        }
+                    }
+
+                    // DefIds of `safe` functions (within the given impl block)
+                    // which either satisfy the following.
+                    // * Take `&self` within its first parameter type.
+                    // * Construct the Self type, but don't contain `self` within its inputs.
+                    let relevant_safe_fns = tcx
+                        .associated_items(impl_hir_id.to_def_id())
+                        .in_definition_order()
+                        .filter_map(|assoc_item| {
+                            if assoc_item.kind == AssocKind::Fn {
+                                let fn_did = assoc_item.def_id;
+                                let fn_sig = tcx.fn_sig(fn_did).skip_binder();
+                                if let rustc_hir::Safety::Unsafe = fn_sig.safety() {
+                                    return None;
+                                }
+                                if assoc_item.fn_has_self_parameter {
+                                    // Check if the given method takes `&self` within its first parameter's type.
+                                    // We already know the method takes `self` within its first parameter,
+                                    // so we only check whether the first parameter contains a reference.
+                                    // e.g. `&self`, `Box<&self>`, `Pin<&self>`, ..
+                                    let mut walker =
+                                        fn_sig.inputs().iter().next().unwrap().skip_binder().walk();
+                                    while let Some(node) = walker.next() {
+                                        if let GenericArgKind::Type(ty) = node.unpack() {
+                                            if let ty::TyKind::Ref(_, _, Mutability::Not) =
+                                                ty.kind()
+                                            {
+                                                return Some(FnType::TakeBorrowedSelf(fn_did));
+                                            }
                                         }
                                     }
+                                } else {
+                                    // Check if the function return type equals `Self`.
+                                    if fn_sig.output().skip_binder() == adt_ty.skip_binder() {
+                                        return Some(FnType::ConstructSelf(fn_did));
+                                    }
                                 }
-                            } else {
-                                // Check if the function return type equals `Self`.
-                                if fn_sig.output().skip_binder() == adt_ty.skip_binder() {
-                                    return Some(FnType::ConstructSelf(fn_did));
+                            }
+                            return None;
+                        });
+
+                    // Inspect selected functions' input/output types to determine `AdtBehavior`.
+                    for fn_type in relevant_safe_fns {
+                        match fn_type {
+                            FnType::ConstructSelf(fn_did) => {
+                                let fn_ctxt_pseudo_owned_param_idx_map =
+                                    find_pseudo_owned_in_fn_ctxt(tcx, fn_did);
+                                let fn_sig = tcx.fn_sig(fn_did).skip_binder();
+                                // Check inputs of the constructor
+                                for input_ty in fn_sig.inputs().skip_binder() {
+                                    for owned_idx in owned_generic_params_in_ty(tcx, *input_ty)
+                                        .into_iter()
+                                        .map(|idx| {
+                                            *fn_ctxt_pseudo_owned_param_idx_map
+                                                .get(&idx)
+                                                .unwrap_or(&idx)
+                                        })
+                                    {
+                                        if let Some(&mapped_idx) =
+                                            generic_param_idx_map.get(&owned_idx)
+                                        {
+                                            owned_generic_params.insert(mapped_idx);
+                                        }
+                                    }
                                 }
                             }
-                        }
-                        return None;
-                    });
-
-                // Since each `impl` block may assign different indices to equivalent generic parameters,
-                // We need one translation map per `impl` block.
-                let generic_param_idx_map =
-                    generic_param_idx_mapper(adt_generic_params, impl_substs);
-
-                // Inspect selected functions' input/output types to determine `AdtBehavior`.
-                for fn_type in relevant_safe_fns {
-                    match fn_type {
-                        FnType::ConstructSelf(fn_did) => {
-                            let fn_ctxt_pseudo_owned_param_idx_map =
-                                find_pseudo_owned_in_fn_ctxt(tcx, fn_did);
-                            let fn_sig = tcx.fn_sig(fn_did).skip_binder();
-                            // Check inputs of the constructor
-                            for input_ty in fn_sig.inputs().skip_binder() {
-                                for owned_idx in owned_generic_params_in_ty(tcx, *input_ty)
+                            FnType::TakeBorrowedSelf(method_did) => {
+                                let fn_ctxt_pseudo_owned_param_idx_map =
+                                    find_pseudo_owned_in_fn_ctxt(tcx, method_did);
+                                let fn_sig = tcx.fn_sig(method_did).skip_binder();
+
+                                // Check generic parameters that are passed as owned `T`.
+                                for ty in fn_sig.inputs_and_output().skip_binder() {
+                                    for owned_idx in
+                                        owned_generic_params_in_ty(tcx, ty).into_iter().map(|idx| {
+                                            *fn_ctxt_pseudo_owned_param_idx_map
+                                                .get(&idx)
+                                                .unwrap_or(&idx)
+                                        })
+                                    {
+                                        if let Some(&mapped_idx) =
+                                            generic_param_idx_map.get(&owned_idx)
+                                        {
+                                            owned_generic_params.insert(mapped_idx);
+                                        }
+                                    }
+                                }
+
+                                // Check whether any of the methods return either `&T` or `Option<&T>` or `Result<&T>`.
+                                let output_ty = normalized_output(tcx, method_did);
+                                for peek_idx in borrowed_generic_params_in_ty(tcx, output_ty)
                                     .into_iter()
                                     .map(|idx| {
                                         *fn_ctxt_pseudo_owned_param_idx_map
@@ -200,88 +306,189 @@ pub(crate) fn adt_behavior<'tcx>(
                                             .unwrap_or(&idx)
                                     })
                                 {
-                                    if let Some(&mapped_idx) = generic_param_idx_map.get(&owned_idx)
+                                    if let Some(&mapped_idx) = generic_param_idx_map.get(&peek_idx)
                                     {
-                                        owned_generic_params.insert(mapped_idx);
+                                        deref_generic_params.insert(mapped_idx);
+                                        record_exposure(
+                                            &mut exposure,
+                                            mapped_idx,
+                                            vec![method_did],
+                                        );
                                     }
                                 }
-                            }
-                        }
-                        FnType::TakeBorrowedSelf(method_did) => {
-                            let fn_ctxt_pseudo_owned_param_idx_map =
-                                find_pseudo_owned_in_fn_ctxt(tcx, method_did);
-                            let fn_sig = tcx.fn_sig(method_did).skip_binder();
-
-                            // Check generic parameters that are passed as owned `T`.
-                            for ty in fn_sig.inputs_and_output().skip_binder() {
-                                for owned_idx in
-                                    owned_generic_params_in_ty(tcx, ty).into_iter().map(|idx| {
-                                        *fn_ctxt_pseudo_owned_param_idx_map
-                                            .get(&idx)
-                                            .unwrap_or(&idx)
-                                    })
-                                {
-                                    if let Some(&mapped_idx) = generic_param_idx_map.get(&owned_idx)
-                                    {
-                                        owned_generic_params.insert(mapped_idx);
+
+                                // Check whether the method returns a local ADT that exposes `&T`
+                                // through its own APIs (e.g. a guard that implements `Deref`).
+                                if self.api_search_depth > 1 {
+                                    for node in output_ty.walk() {
+                                        let (nested_did, nested_substs) = match node.unpack() {
+                                            GenericArgKind::Type(ty) => match ty.kind() {
+                                                ty::TyKind::Adt(nested_adt_def, nested_substs) => {
+                                                    (nested_adt_def.did(), nested_substs)
+                                                }
+                                                _ => continue,
+                                            },
+                                            _ => continue,
+                                        };
+                                        if nested_did == adt_did || !nested_did.is_local() {
+                                            continue;
+                                        }
+                                        for (nested_idx, nested_chain) in
+                                            self.adt_exposure(nested_did)
+                                        {
+                                            if nested_chain.len() >= self.api_search_depth {
+                                                continue;
+                                            }
+                                            if let ty::TyKind::Param(param_ty) =
+                                                nested_substs.type_at(nested_idx.0 as usize).kind()
+                                            {
+                                                let peek_idx = PreMapIdx(param_ty.index);
+                                                let peek_idx = *fn_ctxt_pseudo_owned_param_idx_map
+                                                    .get(&peek_idx)
+                                                    .unwrap_or(&peek_idx);
+                                                if let Some(&mapped_idx) =
+                                                    generic_param_idx_map.get(&peek_idx)
+                                                {
+                                                    nested_deref_generic_params.insert(mapped_idx);
+                                                    let mut chain = vec![method_did];
+                                                    chain.extend(nested_chain);
+                                                    record_exposure(
+                                                        &mut exposure,
+                                                        mapped_idx,
+                                                        chain,
+                                                    );
+                                                }
+                                            }
+                                        }
                                     }
                                 }
-                            }
 
-                            // Check whether any of the methods return either `&T` or `Option<&T>` or `Result<&T>`.
-                            for peek_idx in
-                                borrowed_generic_params_in_ty(tcx, fn_sig.output().skip_binder())
+                                // Check whether any of the method inputs are callbacks that take `&T`.
+                                for peek_idx in callback_borrowed_generic_params(tcx, method_did)
                                     .into_iter()
                                     .map(|idx| {
                                         *fn_ctxt_pseudo_owned_param_idx_map
                                             .get(&idx)
                                             .unwrap_or(&idx)
                                     })
-                            {
-                                if let Some(&mapped_idx) = generic_param_idx_map.get(&peek_idx) {
-                                    deref_generic_params.insert(mapped_idx);
+                                {
+                                    if let Some(&mapped_idx) = generic_param_idx_map.get(&peek_idx)
+                                    {
+                                        callback_generic_params.insert(mapped_idx);
+                                        record_exposure(
+                                            &mut exposure,
+                                            mapped_idx,
+                                            vec![method_did],
+                                        );
+                                    }
                                 }
-                            }
 
-                            // TODO: Check whether any of the method inputs are closures of type `Fn(&T) -> !`.
-                            // for _closure_ty in fn_sig.inputs().iter().filter(|ty| ty.is_closure()) {}
+                                // Check whether any of the method inputs are callbacks that take `&mut T`.
+                                for exclusive_idx in
+                                    callback_mut_borrowed_generic_params(tcx, method_did)
+                                        .into_iter()
+                                        .map(|idx| {
+                                            *fn_ctxt_pseudo_owned_param_idx_map
+                                                .get(&idx)
+                                                .unwrap_or(&idx)
+                                        })
+                                {
+                                    if let Some(&mapped_idx) =
+                                        generic_param_idx_map.get(&exclusive_idx)
+                                    {
+                                        exclusive_generic_params.insert(mapped_idx);
+                                    }
+                                }
+                            }
                         }
                     }
                 }
             }
         }
-    }
 
-    let all_generic_params: FxHashSet<PostMapIdx> = adt_generic_params
-        .iter()
-        .filter_map(|x| {
-            if let GenericParamDefKind::Type { .. } = x.kind {
-                Some(PostMapIdx(x.index))
-            } else {
-                None
-            }
-        })
-        .collect();
+        let all_generic_params: FxHashSet<PostMapIdx> = adt_generic_params
+            .iter()
+            .filter_map(|x| {
+                if let GenericParamDefKind::Type { .. } = x.kind {
+                    Some(PostMapIdx(x.index))
+                } else {
+                    None
+                }
+            })
+            .collect();
 
-    // behavior_map: (idx of generic parameter `T`) => (`AdtBehavior`)
-    let mut behavior_map = FxHashMap::default();
+        // behavior_map: (idx of generic parameter `T`) => (`AdtBehavior`)
+        let mut behavior_map = FxHashMap::default();
 
-    for &param_idx in all_generic_params.iter() {
-        behavior_map.insert(param_idx, AdtBehavior::empty());
-    }
+        for &param_idx in all_generic_params.iter() {
+            behavior_map.insert(param_idx, AdtBehavior::empty());
+        }
 
-    for &param_idx in deref_generic_params.iter() {
-        behavior_map
-            .entry(param_idx)
-            .or_insert(AdtBehavior::empty())
-            .insert(AdtBehavior::DEREF);
-    }
-    for &param_idx in owned_generic_params.difference(&deref_generic_params) {
-        behavior_map
-            .entry(param_idx)
-            .or_insert(AdtBehavior::empty())
-            .insert(AdtBehavior::PASS_OWNED);
+        for &param_idx in deref_generic_params.iter() {
+            behavior_map
+                .entry(param_idx)
+                .or_insert(AdtBehavior::empty())
+                .insert(AdtBehavior::DEREF | AdtBehavior::REF_REF);
+        }
+        for &param_idx in all_generic_params.iter() {
+            let cloned = cloned_generic_params
+                .as_ref()
+                .is_none_or(|cloned| cloned.contains(&param_idx));
+            if cloned {
+                behavior_map
+                    .entry(param_idx)
+                    .or_insert(AdtBehavior::empty())
+                    .insert(AdtBehavior::CLONED);
+            }
+        }
+        for &param_idx in callback_generic_params
+            .iter()
+            .chain(nested_deref_generic_params.iter())
+        {
+            behavior_map
+                .entry(param_idx)
+                .or_insert(AdtBehavior::empty())
+                .insert(AdtBehavior::DEREF);
+        }
+        for &param_idx in exclusive_generic_params.iter() {
+            behavior_map
+                .entry(param_idx)
+                .or_insert(AdtBehavior::empty())
+                .insert(AdtBehavior::EXCLUSIVE);
+        }
+        for &param_idx in owned_generic_params
+            .iter()
+            .filter(|idx| !exposure.contains_key(idx))
+        {
+            behavior_map
+                .entry(param_idx)
+                .or_insert(AdtBehavior::empty())
+                .insert(AdtBehavior::PASS_OWNED);
+        }
+
+        return (behavior_map, exposure);
     }
+}
+
+/// Return type of the given fn, with projections normalized
+/// (e.g. `&Self::Target` => `&T` within `Deref` impls).
+fn normalized_output<'tcx>(tcx: TyCtxt<'tcx>, fn_did: DefId) -> Ty<'tcx> {
+    let output = tcx
+        .instantiate_bound_regions_with_erased(tcx.fn_sig(fn_did).instantiate_identity().output());
+    tcx.try_normalize_erasing_regions(ty::TypingEnv::non_body_analysis(tcx, fn_did), output)
+        .unwrap_or(output)
+}
 
-    return behavior_map;
+/// Keep the shortest chain of methods that exposes `&T`.
+fn record_exposure(
+    exposure: &mut FxHashMap<PostMapIdx, Vec<DefId>>,
+    param_idx: PostMapIdx,
+    chain: Vec<DefId>,
+) {
+    match exposure.get(&param_idx) {
+        Some(shortest) if shortest.len() <= chain.len() => (),
+        _ => {
+            exposure.insert(param_idx, chain);
+        }
+    }
 }
@@ -0,0 +1,384 @@
+//! Explain suspicious impls with the fields that contain the offending generic parameters.
+
+use rustc_infer::infer::TyCtxtInferExt;
+use rustc_middle::ty::Upcast;
+use rustc_trait_selection::infer::InferCtxtExt;
+
+use super::*;
+
+/// For each field of the ADT that contains one of the missing bounds, describe the bound
+/// that the compiler would have required to derive `auto_trait_did`
+/// (e.g. "field `inner: Rc<T>` is never `Send`").
+/// Types are printed with the impl's generic parameters.
+pub fn field_explanations(
+    tcx: TyCtxt<'_>,
+    adt_did: DefId,
+    impl_id: LocalDefId,
+    missing_bounds: &MissingBounds,
+    auto_trait_did: DefId,
+    send_trait_did: DefId,
+    sync_trait_did: DefId,
+) -> Vec<String> {
+    let adt_def = tcx.adt_def(adt_did);
+    let in_impl = impl_namer(tcx, adt_did, impl_id);
+    let auto_trait_name = tcx.item_name(auto_trait_did);
+
+    let mut explanations = Vec::new();
+    for field in adt_def.all_fields() {
+        let field_ty = tcx.type_of(field.did).instantiate_identity();
+        let field_ty_str = in_impl(field_ty);
+        let (field_params, field_projections) = generic_parts(field_ty);
+
+        for (_, projection_idx) in field_projections {
+            let explanation = format!(
+                "field `{}: {}` requires `{}: {}`",
+                field.name,
+                field_ty_str,
+                projection_name(tcx, adt_did, &in_impl, projection_idx),
+                auto_trait_name
+            );
+            if missing_bounds.projections.contains(&projection_idx)
+                && !explanations.contains(&explanation)
+            {
+                explanations.push(explanation);
+            }
+        }
+
+        for idx in field_params {
+            if !missing_bounds.params.contains(&PostMapIdx(idx)) {
+                continue;
+            }
+            let required = required_bounds(
+                tcx,
+                adt_did,
+                field_ty,
+                Some(idx),
+                auto_trait_did,
+                send_trait_did,
+                sync_trait_did,
+            );
+            match required {
+                // The field alone doesn't need anything from the parameter
+                Some(bounds) if bounds.is_empty() => (),
+                Some(bounds) => explanations.push(format!(
+                    "field `{}: {}` requires `{}: {}`",
+                    field.name,
+                    field_ty_str,
+                    in_impl(param_ty(tcx, adt_did, idx)),
+                    trait_names(tcx, &bounds)
+                )),
+                None => explanations.push(format!(
+                    "field `{}: {}` is never `{}`",
+                    field.name, field_ty_str, auto_trait_name
+                )),
+            }
+        }
+    }
+
+    explanations
+}
+
+/// Describe when the compiler would have derived `auto_trait_did` for the impl's self type
+/// without the manual impl (e.g. "without this impl, `Wrapper<U>` would be `Send` iff `U: Send`").
+pub fn derived_explanation(
+    tcx: TyCtxt<'_>,
+    adt_did: DefId,
+    impl_id: LocalDefId,
+    auto_trait_did: DefId,
+    send_trait_did: DefId,
+    sync_trait_did: DefId,
+) -> Option<String> {
+    let self_ty = impl_self_ty(tcx, impl_id)?;
+    let self_ty_str = ty::print::with_forced_trimmed_paths!(self_ty.to_string());
+    let in_impl = impl_namer(tcx, adt_did, impl_id);
+    let auto_trait_name = tcx.item_name(auto_trait_did);
+    let required = |field_ty, idx| {
+        required_bounds(
+            tcx,
+            adt_did,
+            field_ty,
+            idx,
+            auto_trait_did,
+            send_trait_did,
+            sync_trait_did,
+        )
+    };
+
+    let mut param_traits: FxHashMap<u32, Vec<DefId>> = FxHashMap::default();
+    let mut projections = Vec::new();
+    for field in tcx.adt_def(adt_did).all_fields() {
+        let field_ty = tcx.type_of(field.did).instantiate_identity();
+        // Even `Send + Sync` generic parameters don't make the field implement the trait
+        if required(field_ty, None).is_none() {
+            return Some(format!(
+                "without this impl, `{}` would never be `{}`",
+                self_ty_str, auto_trait_name
+            ));
+        }
+
+        let (field_params, field_projections) = generic_parts(field_ty);
+        for idx in field_params {
+            let traits = param_traits.entry(idx).or_default();
+            for trait_did in required(field_ty, Some(idx)).unwrap_or_default() {
+                if !traits.contains(&trait_did) {
+                    traits.push(trait_did);
+                }
+            }
+        }
+        for (_, projection_idx) in field_projections {
+            let condition = format!(
+                "`{}: {}`",
+                projection_name(tcx, adt_did, &in_impl, projection_idx),
+                auto_trait_name
+            );
+            if !projections.contains(&condition) {
+                projections.push(condition);
+            }
+        }
+    }
+
+    let mut param_traits = param_traits.into_iter().collect::<Vec<_>>();
+    param_traits.sort_unstable_by_key(|(idx, _)| *idx);
+    let mut conditions = param_traits
+        .into_iter()
+        .filter(|(_, traits)| !traits.is_empty())
+        .map(|(idx, mut traits)| {
+            // `Send` before `Sync`
+            traits.sort_unstable_by_key(|&did| did != send_trait_did);
+            format!(
+                "`{}: {}`",
+                in_impl(param_ty(tcx, adt_did, idx)),
+                trait_names(tcx, &traits)
+            )
+        })
+        .collect::<Vec<_>>();
+    conditions.extend(projections);
+
+    Some(if conditions.is_empty() {
+        format!(
+            "without this impl, `{}` would be `{}` as well",
+            self_ty_str, auto_trait_name
+        )
+    } else {
+        format!(
+            "without this impl, `{}` would be `{}` iff {}",
+            self_ty_str,
+            auto_trait_name,
+            conditions.join(" and ")
+        )
+    })
+}
+
+/// Describe the chain of `&self` methods that exposes `&T` for each of `params`
+/// (e.g. "`&T` is exposed through `Lock::<T>::lock` -> `<Guard<'_, T> as Deref>::deref`").
+pub fn exposure_explanations(
+    tcx: TyCtxt<'_>,
+    adt_did: DefId,
+    impl_id: LocalDefId,
+    params: &[PostMapIdx],
+    exposure: &FxHashMap<PostMapIdx, Vec<DefId>>,
+) -> Vec<String> {
+    let in_impl = impl_namer(tcx, adt_did, impl_id);
+    params
+        .iter()
+        .filter_map(|param_idx| {
+            let chain = exposure.get(param_idx)?;
+            let param_name = in_impl(param_ty(tcx, adt_did, param_idx.0));
+            let methods = chain
+                .iter()
+                .map(|&method_did| {
+                    let path = ty::print::with_forced_trimmed_paths!(tcx.def_path_str(method_did));
+                    format!("`{}`", path)
+                })
+                .collect::<Vec<_>>();
+            Some(format!(
+                "`&{}` is exposed through {}",
+                param_name,
+                methods.join(" -> ")
+            ))
+        })
+        .collect()
+}
+
+/// For each of the missing bounds that the impl can add, returns the bounded type
+/// in terms of the impl's generic parameters and the traits it needs
+/// (e.g. `("U", "Send + Sync")` for `impl<U> Send for Wrapper<U>`).
+/// Parameters that the impl substitutes with a concrete type are skipped.
+pub fn suggested_bounds(
+    tcx: TyCtxt<'_>,
+    impl_id: LocalDefId,
+    missing_bounds: &MissingBounds,
+    impl_trait_did: DefId,
+) -> Vec<(String, String)> {
+    let impl_trait_substs = match impl_self_ty(tcx, impl_id).map(|self_ty| self_ty.kind()) {
+        Some(ty::TyKind::Adt(_, impl_trait_substs)) => *impl_trait_substs,
+        _ => return Vec::new(),
+    };
+    let impl_param = |PostMapIdx(idx): PostMapIdx| match impl_trait_substs
+        .get(idx as usize)
+        .map(|arg| arg.unpack())
+    {
+        Some(GenericArgKind::Type(ty)) if matches!(ty.kind(), ty::TyKind::Param(_)) => {
+            Some(ty.to_string())
+        }
+        _ => None,
+    };
+
+    let mut bounds = Vec::new();
+    for &param_idx in missing_bounds.params.iter() {
+        match (
+            impl_param(param_idx),
+            missing_bounds.param_traits.get(&param_idx),
+        ) {
+            (Some(subject), Some(trait_dids)) if !trait_dids.is_empty() => {
+                bounds.push((subject, trait_names(tcx, trait_dids)))
+            }
+            _ => (),
+        }
+    }
+    for projection_idx in missing_bounds.projections.iter() {
+        if let Some(param) = impl_param(projection_idx.param) {
+            bounds.push((
+                format!("{}::{}", param, tcx.item_name(projection_idx.assoc_did)),
+                trait_names(tcx, &[impl_trait_did]),
+            ));
+        }
+    }
+    bounds
+}
+
+/// Returns a printer of types in the ADT's generic parameters that uses the impl's names
+/// (e.g. `Rc<U>` for the field type `Rc<T>` of `impl<U> Send for Wrapper<U>`)
+fn impl_namer<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    adt_did: DefId,
+    impl_id: LocalDefId,
+) -> impl Fn(Ty<'tcx>) -> String {
+    let impl_trait_substs = match impl_self_ty(tcx, impl_id).map(|self_ty| self_ty.kind()) {
+        Some(ty::TyKind::Adt(adt_def, impl_trait_substs)) if adt_def.did() == adt_did => {
+            Some(*impl_trait_substs)
+        }
+        _ => None,
+    };
+    move |ty| {
+        let ty = match impl_trait_substs {
+            Some(substs) => ty::EarlyBinder::bind(ty).instantiate(tcx, substs),
+            None => ty,
+        };
+        ty::print::with_forced_trimmed_paths!(ty.to_string())
+    }
+}
+
+/// Split the generic parts of `field_ty` into the parameters outside of projections
+/// and the projections on parameters (e.g. `I::Item`)
+fn generic_parts(field_ty: Ty<'_>) -> (Vec<u32>, Vec<(Ty<'_>, ProjectionIdx)>) {
+    let mut params = Vec::new();
+    let mut projections = Vec::new();
+    let mut walker = field_ty.walk();
+    while let Some(node) = walker.next() {
+        if let GenericArgKind::Type(ty) = node.unpack() {
+            if let Some((param_idx, assoc_did)) = projection_on_param(ty) {
+                walker.skip_current_subtree();
+                let projection_idx = ProjectionIdx {
+                    param: PostMapIdx(param_idx),
+                    assoc_did,
+                };
+                if !projections.contains(&(ty, projection_idx)) {
+                    projections.push((ty, projection_idx));
+                }
+            } else if let ty::TyKind::Param(param_ty) = ty.kind() {
+                params.push(param_ty.index);
+            }
+        }
+    }
+    params.sort_unstable();
+    params.dedup();
+    (params, projections)
+}
+
+/// The type of the ADT's generic parameter `index`
+fn param_ty(tcx: TyCtxt<'_>, adt_did: DefId, index: u32) -> Ty<'_> {
+    let name = tcx.generics_of(adt_did).param_at(index as usize, tcx).name;
+    Ty::new_param(tcx, index, name)
+}
+
+/// Name of a projection in the impl's generic parameters (e.g. `J::Item`)
+fn projection_name<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    adt_did: DefId,
+    in_impl: &impl Fn(Ty<'tcx>) -> String,
+    projection_idx: ProjectionIdx,
+) -> String {
+    format!(
+        "{}::{}",
+        in_impl(param_ty(tcx, adt_did, projection_idx.param.0)),
+        tcx.item_name(projection_idx.assoc_did)
+    )
+}
+
+/// Join trait names with ` + ` (e.g. "Send + Sync")
+fn trait_names(tcx: TyCtxt<'_>, trait_dids: &[DefId]) -> String {
+    trait_dids
+        .iter()
+        .map(|&did| tcx.item_name(did).to_string())
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+/// Returns the smallest set of `Send`/`Sync` bounds on the generic parameter `idx`
+/// that makes `field_ty` implement `auto_trait_did`, or `None` if no such set exists.
+/// The other generic parameters and the projections in `field_ty` are assumed to be
+/// `Send + Sync`. Without `idx`, returns whether all of them being `Send + Sync` suffices.
+fn required_bounds<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    adt_did: DefId,
+    field_ty: Ty<'tcx>,
+    idx: Option<u32>,
+    auto_trait_did: DefId,
+    send_trait_did: DefId,
+    sync_trait_did: DefId,
+) -> Option<Vec<DefId>> {
+    let generics = tcx.generics_of(adt_did);
+
+    // Where clauses of the ADT definition, plus `Send + Sync` on the other parameters
+    let mut assumed_tys = Vec::new();
+    for gen_param in generics.own_params.iter() {
+        if let GenericParamDefKind::Type { .. } = gen_param.kind {
+            if Some(gen_param.index) != idx {
+                assumed_tys.push(param_ty(tcx, adt_did, gen_param.index));
+            }
+        }
+    }
+    assumed_tys.extend(generic_parts(field_ty).1.into_iter().map(|(ty, _)| ty));
+    let mut base_clauses: Vec<ty::Clause<'tcx>> =
+        tcx.param_env(adt_did).caller_bounds().iter().collect();
+    for assumed_ty in assumed_tys {
+        for trait_did in [send_trait_did, sync_trait_did] {
+            base_clauses.push(ty::TraitRef::new(tcx, trait_did, [assumed_ty]).upcast(tcx));
+        }
+    }
+
+    let candidates = match idx {
+        Some(_) => vec![
+            vec![],
+            vec![send_trait_did],
+            vec![sync_trait_did],
+            vec![send_trait_did, sync_trait_did],
+        ],
+        None => vec![vec![]],
+    };
+    candidates.into_iter().find(|bounds| {
+        let clauses = base_clauses
+            .iter()
+            .copied()
+            .chain(bounds.iter().filter_map(|&trait_did| {
+                let idx = idx?;
+                Some(ty::TraitRef::new(tcx, trait_did, [param_ty(tcx, adt_did, idx)]).upcast(tcx))
+            }));
+        let param_env = ty::ParamEnv::new(tcx.mk_clauses_from_iter(clauses));
+        let infcx = tcx.infer_ctxt().build(ty::TypingMode::non_body_analysis());
+        infcx
+            .type_implements_trait(auto_trait_did, [field_ty], param_env)
+            .must_apply_modulo_regions()
+    })
+}
@@ -0,0 +1,102 @@
+//! Identify lock guards (e.g. `MutexGuard<'a, T>`), whose `Send`/`Sync` impls need stricter bounds.
+
+use rustc_abi::FieldIdx;
+use rustc_middle::mir;
+
+use super::*;
+use crate::ir;
+
+/// Method names that release a lock, e.g. `RawMutex::unlock`, `unlock_shared`, `release`
+fn is_release_like(name: &str) -> bool {
+    name == "unlock" || name.starts_with("unlock_") || name == "release"
+}
+
+/// For a given ADT, return whether it is a lock guard:
+/// its `Drop` impl calls an unlock- or release-like method on a borrowed field
+/// (e.g. `self.raw.unlock()` with `raw: &'a R`).
+pub fn is_lock_guard(rcx: RudraCtxt<'_>, adt_did: DefId) -> bool {
+    let tcx = rcx.tcx();
+    let adt_def = tcx.adt_def(adt_did);
+    if !adt_def.is_struct() {
+        return false;
+    }
+
+    // The lock is borrowed by the guard (e.g. `lock: &'a Mutex<T>`)
+    let borrowed_fields = adt_def
+        .non_enum_variant()
+        .fields
+        .iter_enumerated()
+        .filter(|(_, field)| {
+            matches!(
+                tcx.type_of(field.did).instantiate_identity().kind(),
+                ty::TyKind::Ref(..)
+            )
+        })
+        .map(|(field_idx, _)| field_idx)
+        .collect::<Vec<_>>();
+    if borrowed_fields.is_empty() {
+        return false;
+    }
+
+    let drop_did = match tcx.adt_destructor(adt_did) {
+        Some(destructor) => destructor.did,
+        None => return false,
+    };
+    let translation = rcx.translate_body(drop_did);
+    let body = unwrap_or!(translation.as_ref() => return false);
+
+    let releases_lock = body.terminators().any(|terminator| match &terminator.kind {
+        ir::TerminatorKind::StaticCall {
+            callee_did, args, ..
+        } => {
+            is_release_like(tcx.item_name(*callee_did).as_str())
+                && args
+                    .first()
+                    .and_then(mir::Operand::place)
+                    .is_some_and(|receiver| {
+                        derives_from_borrowed_field(body, receiver, &borrowed_fields)
+                    })
+        }
+        _ => false,
+    });
+    releases_lock
+}
+
+/// Returns true if `place` is read from one of `borrowed_fields` of `self` in a `Drop` body,
+/// directly (`(*_1).0`) or through a chain of borrows and copies (`&(*(*_1).0).raw`).
+fn derives_from_borrowed_field<'tcx>(
+    body: &ir::Body<'tcx>,
+    place: mir::Place<'tcx>,
+    borrowed_fields: &[FieldIdx],
+) -> bool {
+    // `_1` is `&mut Self`
+    let is_borrowed_field = |source: mir::Place<'tcx>| {
+        source.local == mir::Local::from_usize(1)
+            && matches!(
+                source.projection.as_slice(),
+                [mir::ProjectionElem::Deref, mir::ProjectionElem::Field(field_idx, _), ..]
+                    if borrowed_fields.contains(field_idx)
+            )
+    };
+    if is_borrowed_field(place) {
+        return true;
+    }
+
+    let chain = body.borrow_chain(place.local);
+    body.statements().any(|statement| match &statement.kind {
+        mir::StatementKind::Assign(box (
+            target,
+            mir::Rvalue::Ref(_, _, source)
+            | mir::Rvalue::RawPtr(_, source)
+            | mir::Rvalue::CopyForDeref(source)
+            | mir::Rvalue::Use(mir::Operand::Copy(source) | mir::Operand::Move(source))
+            | mir::Rvalue::Cast(_, mir::Operand::Copy(source) | mir::Operand::Move(source), _),
+        )) => {
+            target
+                .as_local()
+                .is_some_and(|local| chain.contains(&local))
+                && is_borrowed_field(*source)
+        }
+        _ => false,
+    })
+}
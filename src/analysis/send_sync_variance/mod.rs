@@ -1,18 +1,21 @@
 //! Unsafe Send/Sync impl detector
 
 mod behavior;
+mod explain;
+mod guard;
 mod phantom;
-// You need to fix the code to enable `relaxed` mode..
+mod projection;
+// Selected with `--send-sync-mode=relaxed|both`.
 mod relaxed;
 // Default mode is `strict`.
 mod strict;
+mod unsafe_trait;
 mod utils;
+mod variance;
 
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_hir::def_id::{DefId, LocalDefId};
-use rustc_hir::{
-    GenericBound, GenericParam, HirId, Impl, ImplPolarity, ItemId, ItemKind, Node, WherePredicate,
-};
+use rustc_hir::{GenericBound, Impl, ImplPolarity, ItemId, ItemKind, WherePredicate};
 use rustc_middle::mir::Mutability;
 use rustc_middle::ty::{
     self, AssocKind, ClauseKind, GenericArg, GenericArgKind, GenericParamDef, GenericParamDefKind,
@@ -25,9 +28,12 @@ use snafu::{OptionExt, Snafu};
 use crate::analysis::{AnalysisKind, IntoReportLevel};
 use crate::prelude::*;
 use crate::report::{Report, ReportLevel};
+use crate::{SendSyncMode, UnsafeTraitConfig};
 
 use behavior::*;
+use guard::*;
 pub use phantom::*;
+pub use projection::*;
 pub use utils::*;
 
 pub struct SendSyncVarianceChecker<'tcx> {
@@ -36,17 +42,48 @@ pub struct SendSyncVarianceChecker<'tcx> {
     report_map: FxHashMap<DefId, Vec<Report>>,
     /// For each ADT, keep track of `T`s that are only within `PhantomData<T>`.
     phantom_map: FxHashMap<DefId, Vec<u32>>,
+    /// For each ADT, keep track of whether it is a lock guard.
+    guard_map: FxHashMap<DefId, bool>,
     /// For each ADT, keep track of AdtBehavior per generic param.
     behavior_map: FxHashMap<DefId, FxHashMap<PostMapIdx, AdtBehavior>>,
+    /// For each ADT, keep track of the chain of methods that exposes `&T` per generic param.
+    exposure_map: FxHashMap<DefId, FxHashMap<PostMapIdx, Vec<DefId>>>,
+    /// Maximum length of the method chain inspected to find exposed `&T`.
+    api_search_depth: usize,
+    /// Rules applied to `Send`/`Sync` impls.
+    mode: SendSyncMode,
+    /// User-declared `unsafe trait`s, paired with the bound required on generic params.
+    unsafe_traits: Vec<(DefId, DefId)>,
 }
 
 impl<'tcx> SendSyncVarianceChecker<'tcx> {
-    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+    pub fn new(
+        rcx: RudraCtxt<'tcx>,
+        unsafe_traits: &[UnsafeTraitConfig],
+        api_search_depth: usize,
+        mode: SendSyncMode,
+    ) -> Self {
+        let unsafe_traits = unsafe_traits
+            .iter()
+            .filter_map(|config| {
+                let trait_did =
+                    unwrap_or!(trait_def_id(rcx.tcx(), &config.trait_path) => return None);
+                let bound_did =
+                    unwrap_or!(trait_def_id(rcx.tcx(), &config.bound_path) => return None);
+                Some((trait_did, bound_did))
+            })
+            .collect();
+
         SendSyncVarianceChecker {
             rcx,
             report_map: FxHashMap::default(),
             phantom_map: FxHashMap::default(),
+            guard_map: FxHashMap::default(),
             behavior_map: FxHashMap::default(),
+            exposure_map: FxHashMap::default(),
+            api_search_depth,
+            mode,
+            unsafe_traits,
         }
     }
 
@@ -58,6 +95,10 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
         // Main analysis
         self.analyze_send(send_trait_did, sync_trait_did, copy_trait_did);
         self.analyze_sync(send_trait_did, sync_trait_did, copy_trait_did);
+        for (trait_did, bound_did) in self.unsafe_traits.clone() {
+            self.analyze_unsafe_trait(trait_did, bound_did);
+        }
+        self.analyze_variance();
 
         // Report any suspicious `Send`/`Sync` impls on the given struct.
         for (_struct_def_id, reports) in self.report_map.into_iter() {
@@ -83,21 +124,66 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
             if_chain! {
                 if let ItemKind::Impl(impl_item) = &item.kind;
                 if impl_item.polarity == ImplPolarity::Positive;
-                if let Some((adt_def_id, send_sync_analyses)) =
-                    self.suspicious_send(impl_id, send_trait_did, sync_trait_did, copy_trait_did);
+                if let Some((adt_def_id, send_sync_analyses, missing_bounds)) = self
+                    .suspicious_send_in_mode(
+                        impl_id,
+                        send_trait_did,
+                        sync_trait_did,
+                        copy_trait_did,
+                    );
                 if send_sync_analyses.report_level() >= self.rcx.report_level();
                 then {
                     let tcx = self.rcx.tcx();
+                    let mut explanations = explain::field_explanations(
+                        tcx,
+                        adt_def_id,
+                        impl_id,
+                        &missing_bounds,
+                        send_trait_did,
+                        send_trait_did,
+                        sync_trait_did,
+                    );
+                    explanations.extend(explain::derived_explanation(
+                        tcx,
+                        adt_def_id,
+                        impl_id,
+                        send_trait_did,
+                        send_trait_did,
+                        sync_trait_did,
+                    ));
+                    // No bound makes a lock guard `Send`, so the impl has to go
+                    let is_guard = send_sync_analyses.contains(BehaviorFlag::GUARD_SEND);
+                    let bounds = if is_guard {
+                        explanations.push(format!(
+                            "`{}` is a lock guard that must be dropped on the locking thread",
+                            tcx.item_name(adt_def_id)
+                        ));
+                        Vec::new()
+                    } else {
+                        explain::suggested_bounds(tcx, impl_id, &missing_bounds, send_trait_did)
+                    };
+                    explanations.extend(bound_verdicts(&bounds, &missing_bounds));
+                    let report = Report::with_hir_id(
+                        tcx,
+                        send_sync_analyses.report_level(),
+                        AnalysisKind::SendSyncVariance(send_sync_analyses),
+                        suspicious_impl_message("Send", &explanations),
+                        impl_id,
+                    );
+                    let report = if is_guard {
+                        with_impl_removal(tcx, report, impl_id)
+                    } else {
+                        with_where_clause(
+                            tcx,
+                            report,
+                            impl_id,
+                            bounds_to_suggest(&bounds, &missing_bounds),
+                        )
+                    };
                     self.report_map
                         .entry(adt_def_id)
                         .or_insert_with(|| Vec::with_capacity(2))
-                        .push(Report::with_hir_id(
-                            tcx,
-                            send_sync_analyses.report_level(),
-                            AnalysisKind::SendSyncVariance(send_sync_analyses),
-                            "Suspicious impl of `Send` found",
-                            impl_id,
-                        ));
+                        .push(report);
                 }
             }
         }
@@ -120,20 +206,67 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
             if_chain! {
                 if let ItemKind::Impl(impl_item) = &item.kind;
                 if impl_item.polarity == ImplPolarity::Positive;
-                if let Some((struct_def_id, send_sync_analyses)) =
-                    self.suspicious_sync(impl_id, send_trait_did, sync_trait_did, copy_trait_did);
+                if let Some((struct_def_id, send_sync_analyses, missing_bounds)) = self
+                    .suspicious_sync_in_mode(
+                        impl_id,
+                        send_trait_did,
+                        sync_trait_did,
+                        copy_trait_did,
+                    );
                 if send_sync_analyses.report_level() >= self.rcx.report_level();
                 then {
                     let tcx = self.rcx.tcx();
+                    let mut explanations = explain::field_explanations(
+                        tcx,
+                        struct_def_id,
+                        impl_id,
+                        &missing_bounds,
+                        sync_trait_did,
+                        send_trait_did,
+                        sync_trait_did,
+                    );
+                    explanations.extend(explain::derived_explanation(
+                        tcx,
+                        struct_def_id,
+                        impl_id,
+                        sync_trait_did,
+                        send_trait_did,
+                        sync_trait_did,
+                    ));
+                    if send_sync_analyses.contains(BehaviorFlag::GUARD_SEND) {
+                        explanations.push(format!(
+                            "`{}` is a lock guard that shares its data with every thread",
+                            tcx.item_name(struct_def_id)
+                        ));
+                    }
+                    if send_sync_analyses.contains(BehaviorFlag::API_SYNC_FOR_SYNC) {
+                        let exposure = self.adt_exposure(struct_def_id);
+                        explanations.extend(explain::exposure_explanations(
+                            tcx,
+                            struct_def_id,
+                            impl_id,
+                            &missing_bounds.params,
+                            &exposure,
+                        ));
+                    }
+                    let bounds =
+                        explain::suggested_bounds(tcx, impl_id, &missing_bounds, sync_trait_did);
+                    explanations.extend(bound_verdicts(&bounds, &missing_bounds));
+                    let report = Report::with_hir_id(
+                        tcx,
+                        send_sync_analyses.report_level(),
+                        AnalysisKind::SendSyncVariance(send_sync_analyses),
+                        suspicious_impl_message("Sync", &explanations),
+                        impl_id,
+                    );
                     self.report_map
                         .entry(struct_def_id)
                         .or_insert_with(|| Vec::with_capacity(2))
-                        .push(Report::with_hir_id(
+                        .push(with_where_clause(
                             tcx,
-                            send_sync_analyses.report_level(),
-                            AnalysisKind::SendSyncVariance(send_sync_analyses),
-                            "Suspicious impl of `Sync` found",
+                            report,
                             impl_id,
+                            bounds_to_suggest(&bounds, &missing_bounds),
                         ));
                 }
             }
@@ -141,6 +274,153 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
     }
 }
 
+impl SendSyncVarianceChecker<'_> {
+    /// Run the `Send` checks of the selected mode on the impl
+    fn suspicious_send_in_mode(
+        &mut self,
+        impl_id: LocalDefId,
+        send_trait_did: DefId,
+        sync_trait_did: DefId,
+        copy_trait_did: DefId,
+    ) -> Option<(DefId, BehaviorFlag, MissingBounds)> {
+        let strict = match self.mode {
+            SendSyncMode::Relaxed => None,
+            _ => self.suspicious_send(impl_id, send_trait_did, sync_trait_did, copy_trait_did),
+        };
+        let relaxed = match self.mode {
+            SendSyncMode::Strict => None,
+            _ => self.suspicious_send_relaxed(impl_id, send_trait_did, sync_trait_did),
+        };
+        merge_modes(strict, relaxed)
+    }
+
+    /// Run the `Sync` checks of the selected mode on the impl
+    fn suspicious_sync_in_mode(
+        &mut self,
+        impl_id: LocalDefId,
+        send_trait_did: DefId,
+        sync_trait_did: DefId,
+        copy_trait_did: DefId,
+    ) -> Option<(DefId, BehaviorFlag, MissingBounds)> {
+        let strict = match self.mode {
+            SendSyncMode::Relaxed => None,
+            _ => self.suspicious_sync(impl_id, send_trait_did, sync_trait_did, copy_trait_did),
+        };
+        let relaxed = match self.mode {
+            SendSyncMode::Strict => None,
+            _ => self.suspicious_sync_relaxed(impl_id, sync_trait_did),
+        };
+        merge_modes(strict, relaxed)
+    }
+}
+
+/// Merge the results of both modes on the same impl into a single report
+fn merge_modes(
+    strict: Option<(DefId, BehaviorFlag, MissingBounds)>,
+    relaxed: Option<(DefId, BehaviorFlag, MissingBounds)>,
+) -> Option<(DefId, BehaviorFlag, MissingBounds)> {
+    match (strict, relaxed) {
+        // The strict mode knows which of the parameters are missing a bound
+        (Some((adt_did, strict_flag, mut missing_bounds)), Some((_, relaxed_flag, _))) => {
+            missing_bounds.relaxed_merged = true;
+            Some((adt_did, strict_flag | relaxed_flag, missing_bounds))
+        }
+        (strict, relaxed) => strict.or(relaxed),
+    }
+}
+
+/// Summary line of a suspicious impl report, followed by the offending fields
+fn suspicious_impl_message(trait_name: &str, explanations: &[String]) -> String {
+    if explanations.is_empty() {
+        format!("Suspicious impl of `{}` found", trait_name)
+    } else {
+        format!(
+            "Suspicious impl of `{}` found: {}",
+            trait_name,
+            explanations.join(", ")
+        )
+    }
+}
+
+/// Describe which generic parameter lacks which bound (e.g. "`T` lacks `Send + Sync`"),
+/// or which parameters one bound is needed for under the relaxed rules
+/// (e.g. "one of `T`, `U` needs `Send`")
+fn bound_verdicts(bounds: &[(String, String)], missing_bounds: &MissingBounds) -> Vec<String> {
+    let mut verdicts = match bounds {
+        [(_, traits), _, ..] if missing_bounds.any_param => {
+            let subjects = bounds
+                .iter()
+                .map(|(subject, _)| format!("`{}`", subject))
+                .collect::<Vec<_>>();
+            vec![format!("one of {} needs `{}`", subjects.join(", "), traits)]
+        }
+        _ => bounds
+            .iter()
+            .map(|(subject, traits)| format!("`{}` lacks `{}`", subject, traits))
+            .collect(),
+    };
+    if missing_bounds.relaxed_merged {
+        verdicts.push(
+            "the relaxed rules flag this impl as well, and the bounds follow the strict rules"
+                .to_owned(),
+        );
+    }
+    verdicts
+}
+
+/// Bounds to add to the impl: one of them suffices under the relaxed rules
+fn bounds_to_suggest<'a>(
+    bounds: &'a [(String, String)],
+    missing_bounds: &MissingBounds,
+) -> &'a [(String, String)] {
+    if missing_bounds.any_param {
+        &bounds[..bounds.len().min(1)]
+    } else {
+        bounds
+    }
+}
+
+/// Attach the `where` clause that adds `bounds` to the impl as a suggestion
+fn with_where_clause(
+    tcx: TyCtxt<'_>,
+    report: Report,
+    impl_id: LocalDefId,
+    bounds: &[(String, String)],
+) -> Report {
+    let generics = match tcx.hir().get_generics(impl_id) {
+        Some(generics) if !bounds.is_empty() => generics,
+        _ => return report,
+    };
+    // Insert after the last where predicate (or after the self type if there is none)
+    let span = generics.tail_span_for_predicate_suggestion();
+    if span.from_expansion() {
+        return report;
+    }
+    let predicates = bounds
+        .iter()
+        .map(|(subject, traits)| format!("{}: {}", subject, traits))
+        .collect::<Vec<_>>();
+    report.with_suggestion(
+        tcx,
+        "add the missing bounds to the impl",
+        span,
+        format!(
+            "{} {}",
+            generics.add_where_or_trailing_comma(),
+            predicates.join(", ")
+        ),
+    )
+}
+
+/// Attach the removal of the impl as a suggestion
+fn with_impl_removal(tcx: TyCtxt<'_>, report: Report, impl_id: LocalDefId) -> Report {
+    let span = tcx.hir().expect_item(impl_id).span;
+    if span.from_expansion() {
+        return report;
+    }
+    report.with_suggestion(tcx, "remove the impl", span, String::new())
+}
+
 /// Check Send Trait
 fn send_trait_def_id<'tcx>(tcx: TyCtxt<'tcx>) -> AnalysisResult<'tcx, DefId> {
     convert!(tcx
@@ -158,8 +438,33 @@ fn copy_trait_def_id<'tcx>(tcx: TyCtxt<'tcx>) -> AnalysisResult<'tcx, DefId> {
     convert!(tcx.lang_items().copy_trait().context(CopyTraitNotFound))
 }
 
+/// Find a trait by its def path, e.g. `bytemuck::Pod`
+fn trait_def_id<'tcx>(tcx: TyCtxt<'tcx>, path: &str) -> AnalysisResult<'tcx, DefId> {
+    let ext = tcx.ext();
+    let segments = path.split("::").collect::<Vec<_>>();
+    convert!(tcx
+        .all_traits()
+        .find(|&trait_did| ext.match_def_path(trait_did, &segments))
+        .context(UnsafeTraitNotFound { path }))
+}
+
+/// Self type of the impl, with lazy type aliases expanded
+/// (plain type aliases are already resolved by the compiler)
+fn impl_self_ty(tcx: TyCtxt<'_>, impl_id: LocalDefId) -> Option<Ty<'_>> {
+    let trait_ref = tcx.impl_trait_ref(impl_id)?;
+    Some(tcx.expand_weak_alias_tys(trait_ref.skip_binder().self_ty()))
+}
+
+/// Record an impl whose self type the checker cannot reason about
+fn log_non_adt_impl<'tcx>(tcx: TyCtxt<'tcx>, trait_did: DefId, self_ty: Ty<'tcx>) {
+    log_err!(NonAdtSelfTy {
+        trait_name: tcx.def_path_str(trait_did),
+        self_ty: self_ty.to_string(),
+    });
+}
+
 /// Check Clone Trait
-fn _clone_trait_def_id<'tcx>(tcx: TyCtxt<'tcx>) -> AnalysisResult<'tcx, DefId> {
+fn clone_trait_def_id<'tcx>(tcx: TyCtxt<'tcx>) -> AnalysisResult<'tcx, DefId> {
     convert!(tcx.lang_items().clone_trait().context(CloneTraitNotFound))
 }
 
@@ -169,6 +474,19 @@ pub enum SendSyncVarianceError {
     CopyTraitNotFound,
     SendTraitNotFound,
     SyncTraitNotFound,
+    #[snafu(display("Trait `{}` is not found in the crate graph", path))]
+    UnsafeTraitNotFound {
+        path: String,
+    },
+    #[snafu(display(
+        "Impl of `{}` for non-ADT type `{}` is not analyzed",
+        trait_name,
+        self_ty
+    ))]
+    NonAdtSelfTy {
+        trait_name: String,
+        self_ty: String,
+    },
     CatchAll,
 }
 
@@ -180,6 +498,9 @@ impl AnalysisError for SendSyncVarianceError {
             CopyTraitNotFound => AnalysisErrorKind::Unreachable,
             SendTraitNotFound => AnalysisErrorKind::Unreachable,
             SyncTraitNotFound => AnalysisErrorKind::Unreachable,
+            // The crate may not depend on the configured trait
+            UnsafeTraitNotFound { .. } => AnalysisErrorKind::OutOfScope,
+            NonAdtSelfTy { .. } => AnalysisErrorKind::OutOfScope,
             CatchAll => AnalysisErrorKind::Unreachable,
         }
     }
@@ -190,14 +511,34 @@ impl AnalysisError for SendSyncVarianceError {
 // different impl blocks, we need to map these indices back to its
 // original indices (`PostMapIdx`) to reason about generic parameters globally.
 #[derive(Copy, Clone, Eq, Hash, PartialEq)]
-pub struct PreMapIdx(u32);
+pub struct PreMapIdx(pub(crate) u32);
 // Index of generic type parameter in the ADT definition.
 #[derive(Copy, Clone, Eq, Hash, PartialEq)]
-pub struct PostMapIdx(u32);
+pub struct PostMapIdx(pub(crate) u32);
+/// Generic params and projections of an ADT that are missing a bound in a suspicious impl
+#[derive(Default)]
+pub struct MissingBounds {
+    pub params: Vec<PostMapIdx>,
+    pub projections: Vec<ProjectionIdx>,
+    /// Traits that each of `params` must implement to satisfy the checker.
+    /// Projections must implement the trait of the impl.
+    pub param_traits: FxHashMap<PostMapIdx, Vec<DefId>>,
+    /// A bound on any one of `params` satisfies the checker (relaxed rules)
+    pub any_param: bool,
+    /// The relaxed rules flagged the impl too, but only the strict missing bounds are kept
+    pub relaxed_merged: bool,
+}
+
+// Projection on a generic type parameter of the ADT definition (e.g. `I::Item`).
+#[derive(Copy, Clone, Eq, Hash, PartialEq)]
+pub struct ProjectionIdx {
+    pub(crate) param: PostMapIdx,
+    pub(crate) assoc_did: DefId,
+}
 
 bitflags! {
     #[derive(Default)]
-    pub struct BehaviorFlag: u8 {
+    pub struct BehaviorFlag: u16 {
         // T: Send for impl Sync (with api check & phantom check)
         const API_SEND_FOR_SYNC = 0b00000001;
         // T: Sync for impl Sync (with api check & phantom check)
@@ -212,15 +553,29 @@ bitflags! {
         const RELAX_SEND = 0b00100000;
         // Relaxed Sync for impl Sync (with phantom check)
         const RELAX_SYNC = 0b01000000;
+        // T: Bound for impl of a user-declared unsafe trait (with phantom check)
+        const PHANTOM_UNSAFE_TRAIT = 0b10000000;
+        // T: Bound for impl of a user-declared unsafe trait (no phantom check)
+        const NAIVE_UNSAFE_TRAIT = 0b100000000;
+        // T: Send + Sync for impl Send of pointer-like ADTs (with api check & phantom check)
+        const PTR_LIKE_SEND = 0b1000000000;
+        // Manual impl Send, or T: Sync for impl Sync of lock guards
+        const GUARD_SEND = 0b10000000000;
+        // Covariant T behind a raw pointer, but `&mut T` or a write of T through `&self`
+        const COVARIANT_MUT = 0b100000000000;
     }
 }
 
 impl IntoReportLevel for BehaviorFlag {
     fn report_level(&self) -> ReportLevel {
-        let high = BehaviorFlag::API_SEND_FOR_SYNC | BehaviorFlag::RELAX_SEND;
+        let high =
+            BehaviorFlag::API_SEND_FOR_SYNC | BehaviorFlag::RELAX_SEND | BehaviorFlag::GUARD_SEND;
         let med = BehaviorFlag::API_SYNC_FOR_SYNC
             | BehaviorFlag::PHANTOM_SEND_FOR_SEND
-            | BehaviorFlag::RELAX_SYNC;
+            | BehaviorFlag::RELAX_SYNC
+            | BehaviorFlag::PHANTOM_UNSAFE_TRAIT
+            | BehaviorFlag::PTR_LIKE_SEND
+            | BehaviorFlag::COVARIANT_MUT;
 
         if !(*self & high).is_empty() {
             ReportLevel::Error
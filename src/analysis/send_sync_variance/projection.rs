@@ -0,0 +1,64 @@
+//! Identify projections on generic parameters (e.g. `I::Item`) within fields of ADT.
+
+use super::*;
+
+/// For a given ADT (struct, enum, union), return the projections on its generic params
+/// that show up in field types, along with the indices of `T`s that only show up as
+/// the self type of such projections (e.g. `I` in `struct Wrapper<I: Iterator>(I::Item)`).
+pub fn projection_indices<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    adt_ty: Ty<'tcx>,
+) -> (Vec<ProjectionIdx>, Vec<u32>) {
+    let mut projections = Vec::new();
+    // Store indices of gen_params that are in/out of projections
+    let (mut in_projection, mut out_projection) = (FxHashSet::default(), FxHashSet::default());
+
+    if let ty::TyKind::Adt(adt_def, substs) = adt_ty.kind() {
+        for variant in adt_def.variants() {
+            for field in &variant.fields {
+                let field_ty = field.ty(tcx, substs);
+
+                let mut walker = field_ty.walk();
+                while let Some(node) = walker.next() {
+                    if let GenericArgKind::Type(inner_ty) = node.unpack() {
+                        if let Some((param_idx, assoc_did)) = projection_on_param(inner_ty) {
+                            walker.skip_current_subtree();
+
+                            in_projection.insert(param_idx);
+                            let projection_idx = ProjectionIdx {
+                                param: PostMapIdx(param_idx),
+                                assoc_did,
+                            };
+                            if !projections.contains(&projection_idx) {
+                                projections.push(projection_idx);
+                            }
+                            continue;
+                        }
+
+                        if let ty::TyKind::Param(ty) = inner_ty.kind() {
+                            out_projection.insert(ty.index);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Check for params that are both inside & outside of projections
+    let in_projection = in_projection
+        .into_iter()
+        .filter(|e| !out_projection.contains(e))
+        .collect();
+
+    (projections, in_projection)
+}
+
+/// `<T as Trait>::Assoc` => (index of `T`, DefId of `Assoc`)
+pub fn projection_on_param(ty: Ty<'_>) -> Option<(u32, DefId)> {
+    if let ty::TyKind::Alias(ty::AliasTyKind::Projection, alias_ty) = ty.kind() {
+        if let ty::TyKind::Param(param_ty) = alias_ty.self_ty().kind() {
+            return Some((param_ty.index, alias_ty.def_id));
+        }
+    }
+    None
+}
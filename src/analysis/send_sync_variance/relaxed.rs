@@ -1,127 +1,85 @@
 //! Unsafe Send/Sync impl detector (relaxed)
-#![allow(dead_code)]
 
 use super::*;
-use rustc_hir::{intravisit::Map, WherePredicateKind};
+use rustc_hir::{GenericParamKind, WherePredicateKind};
 
-// We may not use the relaxed versions at all,
-// but keeping them alive just in case..
-impl<'tcx> SendSyncVarianceChecker<'tcx> {
+impl SendSyncVarianceChecker<'_> {
     /// Detect suspicious `Send` with relaxed rules.
-    /// Report only if all generic parameters of `impl Send` aren't `Send`.
-    fn suspicious_send_relaxed(
+    /// Report only if none of the generic parameters of `impl Send` is `Send` or `Sync`.
+    pub fn suspicious_send_relaxed(
         &self,
-        hir_id: HirId,
+        impl_id: LocalDefId,
         send_trait_def_id: DefId,
         sync_trait_def_id: DefId,
-    ) -> bool {
-        let map = self.rcx.tcx().hir();
-        let node = map.hir_node(hir_id);
-        if_chain! {
-            if let Node::Item(item) = node;
-            if let ItemKind::Impl(Impl {
-                ref generics,
-                of_trait: Some(ref trait_ref),
-                ..
-            }) = item.kind;
-            if Some(send_trait_def_id) == trait_ref.trait_def_id();
-            then {
-                // If `impl Send` doesn't involve generic parameters, don't catch it.
-                if generics.params.len() == 0 {
-                    return false;
-                }
-
-                // Inspect immediate trait bounds on generic parameters
-                if self.trait_in_imm_relaxed(
-                    &[send_trait_def_id, sync_trait_def_id],
-                    generics.params,
-                    generics.predicates,
-                ) {
-                    return false;
-                }
-
-                // Inspect trait bounds in where clauses
-                return !self.trait_in_where_relaxed(
-                    &[send_trait_def_id, sync_trait_def_id],
-                    generics.predicates
-                );
-            }
-        }
-        return false;
+    ) -> Option<(DefId, BehaviorFlag, MissingBounds)> {
+        self.suspicious_impl_relaxed(
+            impl_id,
+            &[send_trait_def_id, sync_trait_def_id],
+            BehaviorFlag::RELAX_SEND,
+        )
     }
 
     /// Detect suspicious Sync with relaxed rules.
-    /// Report only if all generic parameters of `impl Sync` aren't Sync.
-    fn suspicious_sync_relaxed(
+    /// Report only if none of the generic parameters of `impl Sync` is Sync.
+    pub fn suspicious_sync_relaxed(
         &self,
-        // HirId of the `Impl Sync` item
-        hir_id: HirId,
+        impl_id: LocalDefId,
         sync_trait_def_id: DefId,
-    ) -> bool {
-        let map = self.rcx.tcx().hir();
-        let node = map.hir_node(hir_id);
+    ) -> Option<(DefId, BehaviorFlag, MissingBounds)> {
+        self.suspicious_impl_relaxed(impl_id, &[sync_trait_def_id], BehaviorFlag::RELAX_SYNC)
+    }
+
+    /// Returns `Some(DefId of ADT)` if no bound of the impl mentions one of `target_trait_def_ids`
+    fn suspicious_impl_relaxed(
+        &self,
+        impl_id: LocalDefId,
+        target_trait_def_ids: &[DefId],
+        behavior_flag: BehaviorFlag,
+    ) -> Option<(DefId, BehaviorFlag, MissingBounds)> {
+        let tcx = self.rcx.tcx();
+        let item = tcx.hir().expect_item(impl_id);
         if_chain! {
-            if let Node::Item(item) = node;
-            if let ItemKind::Impl(Impl {
-                ref generics,
-                of_trait: Some(ref trait_ref),
-                ..
-            }) = item.kind;
-            if Some(sync_trait_def_id) == trait_ref.trait_def_id();
+            if let ItemKind::Impl(Impl { generics, .. }) = item.kind;
+            if let Some(self_ty) = impl_self_ty(tcx, impl_id);
+            if let ty::TyKind::Adt(adt_def, impl_trait_substs) = self_ty.kind();
             then {
-                // If `impl Sync` doesn't involve generic parameters, don't catch it.
-                if generics.params.len() == 0 {
-                    return false;
+                // If the impl doesn't involve generic type parameters, don't catch it.
+                if !generics
+                    .params
+                    .iter()
+                    .any(|param| matches!(param.kind, GenericParamKind::Type { .. }))
+                {
+                    return None;
                 }
 
-                // Inspect immediate trait bounds on generic parameters
-                if self.trait_in_imm_relaxed(
-                   &[sync_trait_def_id],
-                   generics.params,
-                   generics.predicates,
-                ) {
-                   return false;
+                // Immediate trait bounds (`impl<T: Send>`) are lowered into where clauses
+                if self.trait_in_where_relaxed(target_trait_def_ids, generics.predicates) {
+                    return None;
                 }
 
-                return !self.trait_in_where_relaxed(
-                    &[sync_trait_def_id],
-                    generics.predicates
+                // None of the generic parameters of the ADT is bounded
+                let generic_param_idx_map = generic_param_idx_mapper(
+                    &tcx.generics_of(adt_def.did()).own_params,
+                    impl_trait_substs,
                 );
+                let mut params = generic_param_idx_map.into_values().collect::<Vec<_>>();
+                params.sort_unstable_by_key(|&PostMapIdx(idx)| idx);
+                params.dedup();
+                // Bounding any one of them by the implemented trait satisfies the check
+                let param_traits = params
+                    .iter()
+                    .map(|&idx| (idx, vec![target_trait_def_ids[0]]))
+                    .collect();
+                let missing_bounds = MissingBounds {
+                    params,
+                    param_traits,
+                    any_param: true,
+                    ..Default::default()
+                };
+                return Some((adt_def.did(), behavior_flag, missing_bounds));
             }
         }
-        return false;
-    }
-
-    fn trait_in_imm_relaxed(
-        &self,
-        _target_trait_def_ids: &[DefId],
-        _generic_params: &[GenericParam],
-        _predicates: &[WherePredicate],
-    ) -> bool {
-        // Can not get immediate trait bounds from generic parameters in current rustc version.
-        // for generic_param in generic_params {
-        //     if let GenericParamKind::Type { .. } = generic_param.kind {
-        //         for bound in generic_param.bounds {
-        //             if let GenericBound::Trait(x, ..) = bound {
-        //                 if let Some(def_id) = x.trait_ref.trait_def_id() {
-        //                     if target_trait_def_ids.contains(&def_id) {
-        //                         return true;
-        //                     }
-
-        //                     // Check super-traits
-        //                     for p in self.rcx.tcx().super_predicates_of(def_id).predicates {
-        //                         if let ClauseKind::Trait(x) = p.0.kind().skip_binder() {
-        //                             if target_trait_def_ids.contains(&x.trait_ref.def_id) {
-        //                                 return true;
-        //                             }
-        //                         }
-        //                     }
-        //                 }
-        //             }
-        //         }
-        //     }
-        // }
-        return false;
+        None
     }
 
     fn trait_in_where_relaxed(
@@ -138,6 +96,7 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                                 return true;
                             }
 
+                            // Check super-traits
                             for p in self.rcx.tcx().predicates_of(def_id).predicates {
                                 if let ClauseKind::Trait(z) = p.0.kind().skip_binder() {
                                     if target_trait_def_ids.contains(&z.trait_ref.def_id) {
@@ -150,6 +109,6 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                 }
             }
         }
-        return false;
+        false
     }
 }
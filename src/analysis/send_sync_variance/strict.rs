@@ -3,7 +3,8 @@
 use super::*;
 
 impl<'tcx> SendSyncVarianceChecker<'tcx> {
-    /// Returns Some(DefId of ADT) if `impl Sync` for the ADT looks suspicious
+    /// Returns Some(DefId of ADT) if `impl Sync` for the ADT looks suspicious,
+    /// along with the generic params and projections that are missing a bound
     /// (ADT: struct / enum / union)
     pub fn suspicious_sync(
         &mut self,
@@ -11,29 +12,35 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
         send_trait_def_id: DefId,
         sync_trait_def_id: DefId,
         copy_trait_def_id: DefId,
-    ) -> Option<(DefId, BehaviorFlag)> {
+    ) -> Option<(DefId, BehaviorFlag, MissingBounds)> {
         let rcx = self.rcx;
         let tcx = rcx.tcx();
-        if let Some(trait_ref) = tcx.impl_trait_ref(impl_id) {
-            if let ty::TyKind::Adt(adt_def, impl_trait_substs) =
-                trait_ref.skip_binder().self_ty().kind()
-            {
+        if let Some(self_ty) = impl_self_ty(tcx, impl_id) {
+            // rustc rejects `Send`/`Sync` impls on other self types (E0321)
+            if let ty::TyKind::Adt(adt_def, impl_trait_substs) = self_ty.kind() {
                 let adt_did = adt_def.did();
                 let adt_ty = tcx.type_of(adt_did).skip_binder();
 
                 let mut need_send_sync: FxHashMap<PostMapIdx, BehaviorFlag> = FxHashMap::default();
 
+                // Get `AdtBehavior` per generic parameter.
+                let adt_behavior = self.adt_behavior(adt_did);
+
+                // Lock guards hand out `&T`, and release their lock when dropped.
+                let is_guard = *self
+                    .guard_map
+                    .entry(adt_did)
+                    .or_insert_with(|| is_lock_guard(rcx, adt_did));
+
                 // Generic params that only occur within `PhantomData<_>`
                 let phantom_params = self
                     .phantom_map
                     .entry(adt_did)
                     .or_insert_with(|| phantom_indices(tcx, adt_ty));
 
-                // Get `AdtBehavior` per generic parameter.
-                let adt_behavior = self
-                    .behavior_map
-                    .entry(adt_did)
-                    .or_insert_with(|| adt_behavior(rcx, adt_did));
+                // Projections on generic params within fields (e.g. `I::Item`),
+                // and generic params that only occur as the self type of such projections
+                let (projections, projection_params) = projection_indices(tcx, adt_ty);
 
                 // Initialize sets `need_send` & `need_sync`.
                 if adt_def.is_struct() {
@@ -48,6 +55,22 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                                 continue;
                             }
 
+                            // Only the projection needs a bound for `I` of `I::Item`.
+                            if projection_params.contains(&gen_param.index) {
+                                need_send_sync.insert(post_map_idx, analyses);
+                                continue;
+                            }
+
+                            // Only exclusive access to `T` is shared, so `T: Send` suffices.
+                            if adt_behavior
+                                .get(&post_map_idx)
+                                .is_some_and(AdtBehavior::is_mutex_like)
+                            {
+                                need_send_sync
+                                    .insert(post_map_idx, BehaviorFlag::API_SEND_FOR_SYNC);
+                                continue;
+                            }
+
                             analyses.insert(BehaviorFlag::RELAX_SYNC);
                             if let Some(behavior) = adt_behavior.get(&post_map_idx) {
                                 if behavior.is_concurrent_queue() {
@@ -75,6 +98,12 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                                 continue;
                             }
 
+                            // Only the projection needs a bound for `I` of `I::Item`.
+                            if projection_params.contains(&gen_param.index) {
+                                need_send_sync.insert(post_map_idx, analyses);
+                                continue;
+                            }
+
                             analyses.insert(BehaviorFlag::RELAX_SYNC);
                             analyses.insert(BehaviorFlag::API_SYNC_FOR_SYNC);
                             if let Some(behavior) = adt_behavior.get(&post_map_idx) {
@@ -87,6 +116,26 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                     }
                 }
 
+                // Sharing a lock guard shares `&T`, so `T: Sync` is needed.
+                if is_guard {
+                    for (post_map_idx, analyses) in need_send_sync.iter_mut() {
+                        if !phantom_params.contains(&post_map_idx.0) {
+                            analyses.insert(BehaviorFlag::GUARD_SEND);
+                        }
+                    }
+                }
+
+                // Projections are treated like generic params without known API behavior.
+                let mut need_projection: FxHashMap<ProjectionIdx, BehaviorFlag> = projections
+                    .iter()
+                    .map(|&projection_idx| {
+                        (
+                            projection_idx,
+                            BehaviorFlag::NAIVE_SYNC_FOR_SYNC | BehaviorFlag::RELAX_SYNC,
+                        )
+                    })
+                    .collect();
+
                 // If the below assertion fails, there must be an issue with librustc we're using.
                 // assert_eq!(tcx.generics_of(adt_did).params.len(), substs.len());
                 let generic_param_idx_map = generic_param_idx_mapper(
@@ -110,8 +159,12 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                                     if let Some(analyses) = need_send_sync.get_mut(&mapped_idx) {
                                         analyses.remove(BehaviorFlag::API_SYNC_FOR_SYNC);
                                         analyses.remove(BehaviorFlag::NAIVE_SYNC_FOR_SYNC);
+                                        analyses.remove(BehaviorFlag::GUARD_SEND);
                                     }
-                                    for analyses in need_send_sync.values_mut() {
+                                    for analyses in need_send_sync
+                                        .values_mut()
+                                        .chain(need_projection.values_mut())
+                                    {
                                         analyses.remove(BehaviorFlag::RELAX_SYNC);
                                     }
                                 } else if (trait_did == send_trait_def_id)
@@ -122,29 +175,92 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                                     }
                                 }
                             }
+                        } else if let Some((param_idx, assoc_did)) =
+                            projection_on_param(trait_predicate.self_ty())
+                        {
+                            // e.g. `I::Item: Sync`
+                            if let Some(&mapped_idx) =
+                                generic_param_idx_map.get(&PreMapIdx(param_idx))
+                            {
+                                let projection_idx = ProjectionIdx {
+                                    param: mapped_idx,
+                                    assoc_did,
+                                };
+                                if trait_predicate.def_id() == sync_trait_def_id {
+                                    need_projection.remove(&projection_idx);
+                                    if projection_params.contains(&mapped_idx.0) {
+                                        if let Some(analyses) = need_send_sync.get_mut(&mapped_idx)
+                                        {
+                                            analyses.remove(BehaviorFlag::NAIVE_SYNC_FOR_SYNC);
+                                        }
+                                    }
+                                    for analyses in need_send_sync
+                                        .values_mut()
+                                        .chain(need_projection.values_mut())
+                                    {
+                                        analyses.remove(BehaviorFlag::RELAX_SYNC);
+                                    }
+                                }
+                            }
                         }
                     }
                 }
 
-                return if need_send_sync.is_empty() {
-                    None
-                } else {
-                    let mut detected = BehaviorFlag::empty();
-                    for &analyses in need_send_sync.values() {
+                let mut detected = BehaviorFlag::empty();
+                let mut missing_bounds = MissingBounds::default();
+                for (&post_map_idx, &analyses) in need_send_sync.iter() {
+                    detected.insert(analyses);
+                    if !analyses.is_empty() {
+                        missing_bounds.params.push(post_map_idx);
+                    }
+                }
+                missing_bounds.params.sort_unstable_by_key(|idx| idx.0);
+                for projection_idx in projections {
+                    if let Some(&analyses) = need_projection.get(&projection_idx) {
                         detected.insert(analyses);
+                        if !analyses.is_empty() {
+                            missing_bounds.projections.push(projection_idx);
+                        }
                     }
-                    if detected.is_empty() {
-                        None
-                    } else {
-                        Some((adt_did, detected))
+                }
+                for &post_map_idx in missing_bounds.params.iter() {
+                    // The bound on the projection covers `I` of `I::Item`
+                    if projection_params.contains(&post_map_idx.0)
+                        && missing_bounds
+                            .projections
+                            .iter()
+                            .any(|projection_idx| projection_idx.param == post_map_idx)
+                    {
+                        continue;
+                    }
+                    let analyses = need_send_sync[&post_map_idx];
+                    let mut trait_dids = Vec::new();
+                    if analyses.contains(BehaviorFlag::API_SEND_FOR_SYNC) {
+                        trait_dids.push(send_trait_def_id);
                     }
+                    if analyses.intersects(
+                        BehaviorFlag::API_SYNC_FOR_SYNC
+                            | BehaviorFlag::NAIVE_SYNC_FOR_SYNC
+                            | BehaviorFlag::GUARD_SEND
+                            | BehaviorFlag::RELAX_SYNC,
+                    ) {
+                        trait_dids.push(sync_trait_def_id);
+                    }
+                    missing_bounds.param_traits.insert(post_map_idx, trait_dids);
+                }
+
+                return if detected.is_empty() {
+                    None
+                } else {
+                    Some((adt_did, detected, missing_bounds))
                 };
             }
         }
         return None;
     }
 
-    /// Returns `Some(DefId of ADT)` if `impl Send` for the ADT looks suspicious
+    /// Returns `Some(DefId of ADT)` if `impl Send` for the ADT looks suspicious,
+    /// along with the generic params and projections that are missing a bound
     /// (ADT: struct / enum / union)
     pub fn suspicious_send(
         &mut self,
@@ -152,12 +268,12 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
         send_trait_def_id: DefId,
         sync_trait_def_id: DefId,
         copy_trait_def_id: DefId,
-    ) -> Option<(DefId, BehaviorFlag)> {
-        let tcx = self.rcx.tcx();
-        if let Some(trait_ref) = tcx.impl_trait_ref(impl_id) {
-            if let ty::TyKind::Adt(adt_def, impl_trait_substs) =
-                trait_ref.skip_binder().self_ty().kind()
-            {
+    ) -> Option<(DefId, BehaviorFlag, MissingBounds)> {
+        let rcx = self.rcx;
+        let tcx = rcx.tcx();
+        if let Some(self_ty) = impl_self_ty(tcx, impl_id) {
+            // rustc rejects `Send`/`Sync` impls on other self types (E0321)
+            if let ty::TyKind::Adt(adt_def, impl_trait_substs) = self_ty.kind() {
                 let adt_did = adt_def.did();
                 let adt_ty = tcx.type_of(adt_did).skip_binder();
 
@@ -166,12 +282,25 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
 
                 let mut need_send_sync: FxHashMap<PostMapIdx, BehaviorFlag> = FxHashMap::default();
 
+                // Get `AdtBehavior` per generic parameter.
+                let adt_behavior = self.adt_behavior(adt_did);
+
+                // Lock guards hand out `&T`, and release their lock when dropped.
+                let is_guard = *self
+                    .guard_map
+                    .entry(adt_did)
+                    .or_insert_with(|| is_lock_guard(rcx, adt_did));
+
                 // Generic params that only occur within `PhantomData<_>`
                 let phantom_params = self
                     .phantom_map
                     .entry(adt_did)
                     .or_insert_with(|| phantom_indices(tcx, adt_ty));
 
+                // Projections on generic params within fields (e.g. `I::Item`),
+                // and generic params that only occur as the self type of such projections
+                let (projections, projection_params) = projection_indices(tcx, adt_ty);
+
                 // If the below assertion fails, there must be an issue with librustc we're using.
                 // assert_eq!(tcx.generics_of(adt_did).params.len(), substs.len());
                 let generic_param_idx_map = generic_param_idx_mapper(
@@ -191,8 +320,19 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                             continue;
                         }
 
+                        // Only the projection needs a bound for `I` of `I::Item`.
+                        if projection_params.contains(&gen_param.index) {
+                            need_send_sync.insert(post_map_idx, analyses);
+                            continue;
+                        }
+
                         analyses.insert(BehaviorFlag::PHANTOM_SEND_FOR_SEND);
                         analyses.insert(BehaviorFlag::RELAX_SEND);
+                        if let Some(behavior) = adt_behavior.get(&post_map_idx) {
+                            if behavior.is_ptr_like() {
+                                analyses.insert(BehaviorFlag::PTR_LIKE_SEND);
+                            }
+                        }
                         need_send_sync.insert(post_map_idx, analyses);
                     }
                 }
@@ -211,8 +351,29 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                             unsafe impl<'a, T: Copy> Send for Atom1<'a, T> {}
                         ```
                         TODO: implement additional checking to catch above edge case.
+                    4. Pointer-like ADTs (e.g. `Arc<T>`) share `T` between their clones,
+                        so they require both `T: Send` and `T: Sync` for `impl Send`.
+                    5. Lock guards shouldn't implement `Send` at all,
+                        since they release the lock of the thread that acquired it.
                 */
 
+                // Projections are treated like generic params without known API behavior.
+                let mut need_projection: FxHashMap<ProjectionIdx, BehaviorFlag> = projections
+                    .iter()
+                    .map(|&projection_idx| {
+                        (
+                            projection_idx,
+                            BehaviorFlag::NAIVE_SEND_FOR_SEND
+                                | BehaviorFlag::PHANTOM_SEND_FOR_SEND
+                                | BehaviorFlag::RELAX_SEND,
+                        )
+                    })
+                    .collect();
+
+                // Generic params bounded by `Send` and by `Sync`, respectively
+                let mut send_bounded = FxHashSet::default();
+                let mut sync_bounded = FxHashSet::default();
+
                 // Iterate over predicates to check trait bounds on generic params.
                 for atom in tcx
                     .param_env(impl_id)
@@ -229,8 +390,49 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                                     || trait_did == sync_trait_def_id
                                     || trait_did == copy_trait_def_id
                                 {
-                                    need_send_sync.remove(&mapped_idx);
-                                    for analyses in need_send_sync.values_mut() {
+                                    if let Some(analyses) = need_send_sync.get_mut(mapped_idx) {
+                                        analyses.remove(!BehaviorFlag::PTR_LIKE_SEND);
+                                    }
+                                    for analyses in need_send_sync
+                                        .values_mut()
+                                        .chain(need_projection.values_mut())
+                                    {
+                                        analyses.remove(BehaviorFlag::RELAX_SEND);
+                                    }
+                                }
+                                if trait_did == send_trait_def_id {
+                                    send_bounded.insert(*mapped_idx);
+                                } else if trait_did == sync_trait_def_id {
+                                    sync_bounded.insert(*mapped_idx);
+                                }
+                            }
+                        } else if let Some((param_idx, assoc_did)) =
+                            projection_on_param(trait_predicate.self_ty())
+                        {
+                            // e.g. `I::Item: Send`
+                            if let Some(&mapped_idx) =
+                                generic_param_idx_map.get(&PreMapIdx(param_idx))
+                            {
+                                let projection_idx = ProjectionIdx {
+                                    param: mapped_idx,
+                                    assoc_did,
+                                };
+                                let trait_did = trait_predicate.def_id();
+                                if trait_did == send_trait_def_id
+                                    || trait_did == sync_trait_def_id
+                                    || trait_did == copy_trait_def_id
+                                {
+                                    need_projection.remove(&projection_idx);
+                                    if projection_params.contains(&mapped_idx.0) {
+                                        if let Some(analyses) = need_send_sync.get_mut(&mapped_idx)
+                                        {
+                                            analyses.remove(BehaviorFlag::NAIVE_SEND_FOR_SEND);
+                                        }
+                                    }
+                                    for analyses in need_send_sync
+                                        .values_mut()
+                                        .chain(need_projection.values_mut())
+                                    {
                                         analyses.remove(BehaviorFlag::RELAX_SEND);
                                     }
                                 }
@@ -238,19 +440,66 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                         }
                     }
                 }
+                for mapped_idx in send_bounded.intersection(&sync_bounded) {
+                    if let Some(analyses) = need_send_sync.get_mut(mapped_idx) {
+                        analyses.remove(BehaviorFlag::PTR_LIKE_SEND);
+                    }
+                }
 
-                return if need_send_sync.is_empty() {
-                    None
+                // A lock guard must be dropped on the thread that acquired the lock,
+                // so no bound clears this flag, and the report suggests removing the impl.
+                let mut detected = if is_guard {
+                    BehaviorFlag::GUARD_SEND
                 } else {
-                    let mut detected = BehaviorFlag::empty();
-                    for &analyses in need_send_sync.values() {
+                    BehaviorFlag::empty()
+                };
+                let mut missing_bounds = MissingBounds::default();
+                for (&post_map_idx, &analyses) in need_send_sync.iter() {
+                    detected.insert(analyses);
+                    if !analyses.is_empty() {
+                        missing_bounds.params.push(post_map_idx);
+                    }
+                }
+                missing_bounds.params.sort_unstable_by_key(|idx| idx.0);
+                for projection_idx in projections {
+                    if let Some(&analyses) = need_projection.get(&projection_idx) {
                         detected.insert(analyses);
+                        if !analyses.is_empty() {
+                            missing_bounds.projections.push(projection_idx);
+                        }
                     }
-                    if detected.is_empty() {
-                        None
-                    } else {
-                        Some((adt_did, detected))
+                }
+                for &post_map_idx in missing_bounds.params.iter() {
+                    // The bound on the projection covers `I` of `I::Item`
+                    if projection_params.contains(&post_map_idx.0)
+                        && missing_bounds
+                            .projections
+                            .iter()
+                            .any(|projection_idx| projection_idx.param == post_map_idx)
+                    {
+                        continue;
                     }
+                    let analyses = need_send_sync[&post_map_idx];
+                    let trait_dids = if analyses.contains(BehaviorFlag::PTR_LIKE_SEND) {
+                        // Pointer-like ADTs need whichever of `Send` and `Sync` is missing
+                        let mut trait_dids = Vec::new();
+                        if !send_bounded.contains(&post_map_idx) {
+                            trait_dids.push(send_trait_def_id);
+                        }
+                        if !sync_bounded.contains(&post_map_idx) {
+                            trait_dids.push(sync_trait_def_id);
+                        }
+                        trait_dids
+                    } else {
+                        vec![send_trait_def_id]
+                    };
+                    missing_bounds.param_traits.insert(post_map_idx, trait_dids);
+                }
+
+                return if detected.is_empty() {
+                    None
+                } else {
+                    Some((adt_did, detected, missing_bounds))
                 };
             }
         }
@@ -0,0 +1,171 @@
+//! Unsafe impl detector for user-declared `unsafe trait`s (e.g. `bytemuck::Pod`)
+
+use rustc_middle::ty::TypeVisitableExt;
+
+use super::*;
+
+impl SendSyncVarianceChecker<'_> {
+    /// Detect cases where the wrapper of T implements an `unsafe trait`,
+    /// but T may not satisfy the bound that the trait requires
+    pub fn analyze_unsafe_trait(&mut self, trait_did: DefId, bound_did: DefId) {
+        // Iterate over `impl`s that implement the trait.
+        let tcx = self.rcx.tcx();
+        let hir = tcx.hir();
+        for &impl_id in hir.trait_impls(trait_did) {
+            let owner_id = tcx.local_def_id_to_hir_id(impl_id).as_owner().unwrap();
+            let item = hir.item(ItemId { owner_id });
+            if_chain! {
+                if let ItemKind::Impl(impl_item) = &item.kind;
+                if impl_item.polarity == ImplPolarity::Positive;
+                if let Some((adt_def_id, send_sync_analyses)) =
+                    self.suspicious_unsafe_trait_impl(impl_id, trait_did, bound_did);
+                if send_sync_analyses.report_level() >= self.rcx.report_level();
+                then {
+                    self.report_map
+                        .entry(adt_def_id)
+                        .or_insert_with(|| Vec::with_capacity(2))
+                        .push(Report::with_hir_id(
+                            tcx,
+                            send_sync_analyses.report_level(),
+                            AnalysisKind::SendSyncVariance(send_sync_analyses),
+                            format!("Suspicious impl of `{}` found", tcx.def_path_str(trait_did)),
+                            impl_id,
+                        ));
+                }
+            }
+        }
+    }
+
+    /// Returns `Some(DefId of ADT)` if the impl of `trait_did` for the ADT looks suspicious
+    /// (ADT: struct / enum / union)
+    pub fn suspicious_unsafe_trait_impl(
+        &mut self,
+        impl_id: LocalDefId,
+        trait_did: DefId,
+        bound_did: DefId,
+    ) -> Option<(DefId, BehaviorFlag)> {
+        let tcx = self.rcx.tcx();
+        let self_ty = impl_self_ty(tcx, impl_id)?;
+        let (adt_def, impl_trait_substs) = match self_ty.kind() {
+            ty::TyKind::Adt(adt_def, impl_trait_substs) => (adt_def, impl_trait_substs),
+            ty::TyKind::Tuple(..) | ty::TyKind::Array(..) | ty::TyKind::Slice(..) => {
+                return suspicious_composite_impl(tcx, impl_id, self_ty, trait_did, bound_did);
+            }
+            // e.g. `unsafe impl Pod for dyn Shape`
+            ty::TyKind::Dynamic(..) => {
+                log_non_adt_impl(tcx, trait_did, self_ty);
+                return None;
+            }
+            // e.g. `unsafe impl Pod for u32`
+            _ if !self_ty.has_param() => return None,
+            _ => {
+                log_non_adt_impl(tcx, trait_did, self_ty);
+                return None;
+            }
+        };
+
+        let adt_did = adt_def.did();
+        let adt_ty = tcx.type_of(adt_did).skip_binder();
+
+        // Keep track of generic params that need to satisfy the bound.
+        let mut need_bound: FxHashMap<PostMapIdx, BehaviorFlag> = FxHashMap::default();
+
+        // Generic params that only occur within `PhantomData<_>`
+        let phantom_params = self
+            .phantom_map
+            .entry(adt_did)
+            .or_insert_with(|| phantom_indices(tcx, adt_ty));
+
+        let generic_param_idx_map =
+            generic_param_idx_mapper(&tcx.generics_of(adt_did).own_params, impl_trait_substs);
+
+        for gen_param in tcx.generics_of(adt_did).own_params.iter() {
+            if let GenericParamDefKind::Type { .. } = gen_param.kind {
+                let post_map_idx = PostMapIdx(gen_param.index);
+                let mut analyses = BehaviorFlag::NAIVE_UNSAFE_TRAIT;
+
+                // Skip generic parameters that are only within `PhantomData<T>`.
+                if !phantom_params.contains(&gen_param.index) {
+                    analyses.insert(BehaviorFlag::PHANTOM_UNSAFE_TRAIT);
+                }
+                need_bound.insert(post_map_idx, analyses);
+            }
+        }
+
+        // Both the required bound and the trait itself (`T: Pod` for `impl Pod`)
+        // make a generic parameter safe to wrap.
+        for atom in tcx
+            .param_env(impl_id)
+            .caller_bounds()
+            .iter()
+            .map(|x| x.kind().skip_binder())
+        {
+            if let ClauseKind::Trait(trait_predicate) = atom {
+                if let ty::TyKind::Param(param_ty) = trait_predicate.self_ty().kind() {
+                    let pre_map_idx = PreMapIdx(param_ty.index);
+                    if let Some(mapped_idx) = generic_param_idx_map.get(&pre_map_idx) {
+                        let predicate_did = trait_predicate.def_id();
+                        if predicate_did == bound_did || predicate_did == trait_did {
+                            need_bound.remove(mapped_idx);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut detected = BehaviorFlag::empty();
+        for &analyses in need_bound.values() {
+            detected.insert(analyses);
+        }
+        if detected.is_empty() {
+            None
+        } else {
+            Some((adt_did, detected))
+        }
+    }
+}
+
+/// Returns `Some(DefId of impl)` if the impl of `trait_did` for a tuple, array or slice
+/// of generic parameters doesn't bound each of them, e.g. `unsafe impl<T> Pod for (T, T)`
+fn suspicious_composite_impl<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    impl_id: LocalDefId,
+    self_ty: Ty<'tcx>,
+    trait_did: DefId,
+    bound_did: DefId,
+) -> Option<(DefId, BehaviorFlag)> {
+    // The elements are owned by the composite type, like the fields of an ADT
+    let element_ty = match self_ty.kind() {
+        ty::TyKind::Slice(element_ty) => *element_ty,
+        _ => self_ty,
+    };
+    let mut need_bound: FxHashSet<u32> = owned_generic_params_in_ty(tcx, element_ty)
+        .into_iter()
+        .map(|PreMapIdx(idx)| idx)
+        .collect();
+
+    for atom in tcx
+        .param_env(impl_id)
+        .caller_bounds()
+        .iter()
+        .map(|x| x.kind().skip_binder())
+    {
+        if let ClauseKind::Trait(trait_predicate) = atom {
+            if let ty::TyKind::Param(param_ty) = trait_predicate.self_ty().kind() {
+                let predicate_did = trait_predicate.def_id();
+                if predicate_did == bound_did || predicate_did == trait_did {
+                    need_bound.remove(&param_ty.index);
+                }
+            }
+        }
+    }
+
+    if need_bound.is_empty() {
+        None
+    } else {
+        Some((
+            impl_id.to_def_id(),
+            BehaviorFlag::PHANTOM_UNSAFE_TRAIT | BehaviorFlag::NAIVE_UNSAFE_TRAIT,
+        ))
+    }
+}
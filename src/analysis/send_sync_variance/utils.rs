@@ -203,3 +203,95 @@ pub fn find_pseudo_owned_in_fn_ctxt<'tcx>(
 
     fn_ctxt_pseudo_owned_param_idx_map
 }
+
+// Return generic parameters that are handed to a callback as `&T`.
+// Callbacks are either `Fn*`-bounded generic parameters, `dyn Fn*` trait objects,
+// or fn pointers within the inputs of the given function.
+// Callbacks taking `&mut T` are not included, since they only grant exclusive access
+// to `T` (e.g. `Mutex`-like APIs).
+//
+// example)
+//    impl<T> Lock<T> {
+//        fn with<F: FnOnce(&T)>(&self, f: F) {}
+//    }
+pub fn callback_borrowed_generic_params(
+    tcx: TyCtxt<'_>,
+    fn_did: DefId,
+) -> impl IntoIterator<Item = PreMapIdx> {
+    callback_arg_tys(tcx, fn_did)
+        .into_iter()
+        .flat_map(|ty| borrowed_generic_params_in_ty(tcx, ty))
+        .collect::<FxHashSet<_>>()
+}
+
+// Return generic parameters that are handed to a callback as `&mut T`.
+//
+// example)
+//    impl<T> Lock<T> {
+//        fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {}
+//    }
+pub fn callback_mut_borrowed_generic_params(
+    tcx: TyCtxt<'_>,
+    fn_did: DefId,
+) -> impl IntoIterator<Item = PreMapIdx> {
+    let mut mut_borrowed_generic_params = FxHashSet::default();
+    for ty in callback_arg_tys(tcx, fn_did) {
+        for node in ty.walk() {
+            if let GenericArgKind::Type(ty) = node.unpack() {
+                if let ty::TyKind::Ref(_, borrowed_ty, Mutability::Mut) = ty.kind() {
+                    if let ty::TyKind::Param(param_ty) = borrowed_ty.kind() {
+                        mut_borrowed_generic_params.insert(PreMapIdx(param_ty.index));
+                    }
+                }
+            }
+        }
+    }
+    mut_borrowed_generic_params
+}
+
+// Argument types of the callbacks within the inputs of the given function.
+fn callback_arg_tys(tcx: TyCtxt<'_>, fn_did: DefId) -> Vec<Ty<'_>> {
+    let mut callback_arg_tys = Vec::new();
+
+    // `F: Fn(&T)`, `impl Fn(&T)`
+    for atom in tcx
+        .param_env(fn_did)
+        .caller_bounds()
+        .iter()
+        .map(|x| x.kind().skip_binder())
+    {
+        if let ClauseKind::Trait(trait_predicate) = atom {
+            if tcx
+                .fn_trait_kind_from_def_id(trait_predicate.def_id())
+                .is_some()
+            {
+                callback_arg_tys.push(trait_predicate.trait_ref.args.type_at(1));
+            }
+        }
+    }
+
+    // `&dyn Fn(&T)`, `Box<dyn Fn(&T)>`, `fn(&T)`
+    let fn_sig = tcx.fn_sig(fn_did).skip_binder();
+    for input_ty in fn_sig.inputs().skip_binder() {
+        for node in input_ty.walk() {
+            if let GenericArgKind::Type(ty) = node.unpack() {
+                match ty.kind() {
+                    ty::TyKind::Dynamic(predicates, _, _) => {
+                        if let Some(principal) = predicates.principal() {
+                            let principal = principal.skip_binder();
+                            if tcx.fn_trait_kind_from_def_id(principal.def_id).is_some() {
+                                callback_arg_tys.push(principal.args.type_at(0));
+                            }
+                        }
+                    }
+                    ty::TyKind::FnPtr(sig_tys, _) => {
+                        callback_arg_tys.extend(sig_tys.skip_binder().inputs().iter());
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    callback_arg_tys
+}
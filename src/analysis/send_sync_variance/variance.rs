@@ -0,0 +1,258 @@
+//! Compare the variance inferred for generic parameters behind raw pointers with their usage.
+
+use rustc_hir::def::DefKind;
+use rustc_middle::mir::{Local, Rvalue, StatementKind};
+
+use super::*;
+use crate::ir;
+use crate::paths::{NON_NULL, PTR_WRITE_LIST};
+
+impl SendSyncVarianceChecker<'_> {
+    /// Detect ADTs that are covariant in `T` because `T` is behind `*const T` or `NonNull<T>`,
+    /// but hand out `&mut T` or write `T` through `&self` like `Cell<T>` does
+    pub fn analyze_variance(&mut self) {
+        let tcx = self.rcx.tcx();
+        for local_did in tcx.hir_crate_items(()).definitions() {
+            let adt_did = local_did.to_def_id();
+            if !matches!(
+                tcx.def_kind(adt_did),
+                DefKind::Struct | DefKind::Enum | DefKind::Union
+            ) {
+                continue;
+            }
+
+            let covariant_params = covariant_ptr_params(tcx, adt_did);
+            if covariant_params.is_empty() {
+                continue;
+            }
+
+            let explanations = self.invariant_uses(adt_did, &covariant_params);
+            let send_sync_analyses = BehaviorFlag::COVARIANT_MUT;
+            if explanations.is_empty()
+                || send_sync_analyses.report_level() < self.rcx.report_level()
+            {
+                continue;
+            }
+
+            let generics = tcx.generics_of(adt_did);
+            let param_names = covariant_params
+                .iter()
+                .map(|&idx| format!("`{}`", generics.param_at(idx as usize, tcx).name))
+                .collect::<Vec<_>>();
+            self.report_map
+                .entry(adt_did)
+                .or_insert_with(|| Vec::with_capacity(2))
+                .push(Report::with_hir_id(
+                    tcx,
+                    send_sync_analyses.report_level(),
+                    AnalysisKind::SendSyncVariance(send_sync_analyses),
+                    format!(
+                        "`{}` is covariant in {} but should be invariant: {}",
+                        tcx.def_path_str(adt_did),
+                        param_names.join(", "),
+                        explanations.join(", ")
+                    ),
+                    local_did,
+                ));
+        }
+    }
+
+    /// Describe the safe `&self` methods that hand out `&mut T` or write `T`
+    /// for one of `covariant_params`
+    fn invariant_uses(&self, adt_did: DefId, covariant_params: &[u32]) -> Vec<String> {
+        let tcx = self.rcx.tcx();
+        let relevant_impls = match self.rcx.index_adt_cache(&adt_did) {
+            Some(relevant_impls) => relevant_impls,
+            None => return Vec::new(),
+        };
+        let generics = tcx.generics_of(adt_did);
+
+        let mut explanations = Vec::new();
+        for (impl_did, impl_self_ty) in relevant_impls.iter() {
+            let impl_substs = match impl_self_ty.kind() {
+                ty::TyKind::Adt(_, impl_substs) => impl_substs,
+                _ => continue,
+            };
+            let generic_param_idx_map = generic_param_idx_mapper(&generics.own_params, impl_substs);
+            // ADT parameters of interest that occur in `ty`
+            let covariant_params_in = |ty: Ty<'_>| {
+                let mut params = ty
+                    .walk()
+                    .filter_map(|node| match node.unpack() {
+                        GenericArgKind::Type(inner_ty) => match inner_ty.kind() {
+                            ty::TyKind::Param(param_ty) => {
+                                generic_param_idx_map.get(&PreMapIdx(param_ty.index))
+                            }
+                            _ => None,
+                        },
+                        _ => None,
+                    })
+                    .map(|&PostMapIdx(idx)| idx)
+                    .filter(|idx| covariant_params.contains(idx))
+                    .collect::<Vec<_>>();
+                params.sort_unstable();
+                params.dedup();
+                params
+            };
+
+            for assoc_item in tcx
+                .associated_items(impl_did.to_def_id())
+                .in_definition_order()
+            {
+                if assoc_item.kind != AssocKind::Fn || !assoc_item.fn_has_self_parameter {
+                    continue;
+                }
+                let fn_did = assoc_item.def_id;
+                let fn_sig = tcx.fn_sig(fn_did).skip_binder().skip_binder();
+                if let rustc_hir::Safety::Unsafe = fn_sig.safety {
+                    continue;
+                }
+                // `&mut self` excludes covariant coercions of the receiver
+                let (self_ty, other_inputs) = match fn_sig.inputs().split_first() {
+                    Some(inputs) => inputs,
+                    None => continue,
+                };
+                if !matches!(self_ty.kind(), ty::TyKind::Ref(_, _, Mutability::Not)) {
+                    continue;
+                }
+                let fn_path = tcx.def_path_str(fn_did);
+
+                // e.g. `fn get_mut(&self) -> &mut T`
+                let mut handed_out = Vec::new();
+                for node in fn_sig.output().walk() {
+                    if let GenericArgKind::Type(ty) = node.unpack() {
+                        if let ty::TyKind::Ref(_, pointee_ty, Mutability::Mut) = ty.kind() {
+                            handed_out.extend(covariant_params_in(*pointee_ty));
+                        }
+                    }
+                }
+                handed_out.sort_unstable();
+                handed_out.dedup();
+                for idx in handed_out {
+                    explanations.push(format!(
+                        "`{}` hands out `&mut {}` from `&self`",
+                        fn_path,
+                        generics.param_at(idx as usize, tcx).name
+                    ));
+                }
+
+                // e.g. `fn set(&self, value: T)` that writes through a raw pointer
+                let mut written = Vec::new();
+                if other_inputs
+                    .iter()
+                    .any(|&input_ty| !covariant_params_in(input_ty).is_empty())
+                {
+                    let written_args = args_written_through_ptr(self.rcx, fn_did);
+                    for (input_idx, &input_ty) in other_inputs.iter().enumerate() {
+                        // Local 1 is `self`
+                        if written_args.contains(&(input_idx + 2)) {
+                            written.extend(covariant_params_in(input_ty));
+                        }
+                    }
+                }
+                written.sort_unstable();
+                written.dedup();
+                for idx in written {
+                    explanations.push(format!(
+                        "`{}` writes `{}` through `&self`",
+                        fn_path,
+                        generics.param_at(idx as usize, tcx).name
+                    ));
+                }
+            }
+        }
+
+        explanations
+    }
+}
+
+/// For a given ADT, return the indices of `T`s that the compiler infers as covariant
+/// and that occur within `*const T` or `NonNull<T>`.
+pub fn covariant_ptr_params(tcx: TyCtxt<'_>, adt_did: DefId) -> Vec<u32> {
+    let variances = tcx.variances_of(adt_did);
+    let adt_def = tcx.adt_def(adt_did);
+
+    let mut behind_ptr = FxHashSet::default();
+    for field in adt_def.all_fields() {
+        let field_ty = tcx.type_of(field.did).instantiate_identity();
+        for node in field_ty.walk() {
+            if let GenericArgKind::Type(ty) = node.unpack() {
+                let pointee_ty = match ty.kind() {
+                    ty::TyKind::RawPtr(pointee_ty, Mutability::Not) => *pointee_ty,
+                    ty::TyKind::Adt(adt_def, substs)
+                        if tcx.ext().match_def_path(adt_def.did(), &NON_NULL) =>
+                    {
+                        substs.type_at(0)
+                    }
+                    _ => continue,
+                };
+                for pointee_node in pointee_ty.walk() {
+                    if let GenericArgKind::Type(pointee_ty) = pointee_node.unpack() {
+                        if let ty::TyKind::Param(param_ty) = pointee_ty.kind() {
+                            behind_ptr.insert(param_ty.index);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut covariant_params = tcx
+        .generics_of(adt_did)
+        .own_params
+        .iter()
+        .filter(|gen_param| matches!(gen_param.kind, GenericParamDefKind::Type { .. }))
+        .map(|gen_param| gen_param.index)
+        .filter(|&idx| {
+            behind_ptr.contains(&idx) && variances[idx as usize] == ty::Variance::Covariant
+        })
+        .collect::<Vec<_>>();
+    covariant_params.sort_unstable();
+    covariant_params
+}
+
+/// Returns the argument locals whose values the body stores through a raw pointer
+/// (e.g. `*ptr = value`, `ptr::write(ptr, value)`).
+fn args_written_through_ptr(rcx: RudraCtxt<'_>, fn_did: DefId) -> FxHashSet<usize> {
+    let tcx = rcx.tcx();
+    let translation = rcx.translate_body(fn_did);
+    let body = unwrap_or!(translation.as_ref() => return FxHashSet::default());
+
+    let mut stored = Vec::new();
+    for statement in body.statements() {
+        if let StatementKind::Assign(box (place, rvalue)) = &statement.kind {
+            if place.is_indirect_first_projection()
+                && body.local_decls[place.local.index()].ty.is_raw_ptr()
+            {
+                match rvalue {
+                    Rvalue::Use(operand) => stored.push(operand),
+                    Rvalue::Aggregate(_, operands) => stored.extend(operands.iter()),
+                    _ => (),
+                }
+            }
+        }
+    }
+    for terminator in body.terminators() {
+        if let ir::TerminatorKind::StaticCall {
+            callee_did,
+            ref args,
+            ..
+        } = terminator.kind
+        {
+            if PTR_WRITE_LIST.contains(&tcx.ext().get_def_path(callee_did)) {
+                stored.extend(args.get(1));
+            }
+        }
+    }
+
+    (1..=body.original.arg_count)
+        .filter(|&arg_idx| {
+            let derived = body.derived_locals(Local::from_usize(arg_idx));
+            stored.iter().any(|operand| {
+                operand
+                    .place()
+                    .is_some_and(|place| derived.contains(&place.local))
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,431 @@
+//! Data race detector for `static mut` items
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_middle::mir::visit::{MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor};
+use rustc_middle::mir::{
+    self, AggregateKind, Location, Operand, Rvalue, StatementKind, RETURN_PLACE,
+};
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
+
+use snafu::{Backtrace, Snafu};
+use termcolor::Color;
+
+use crate::prelude::*;
+use crate::{
+    analysis::{AnalysisKind, IntoReportLevel},
+    ir, paths,
+    report::{Report, ReportLevel},
+    utils,
+};
+
+#[derive(Debug, Snafu)]
+pub enum StaticMutError {
+    InvalidSpan { backtrace: Backtrace },
+}
+
+impl AnalysisError for StaticMutError {
+    fn kind(&self) -> AnalysisErrorKind {
+        use StaticMutError::*;
+        match self {
+            InvalidSpan { .. } => AnalysisErrorKind::Unreachable,
+        }
+    }
+}
+
+pub struct StaticMutChecker<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+}
+
+impl<'tcx> StaticMutChecker<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+        StaticMutChecker { rcx }
+    }
+
+    pub fn analyze(self) {
+        let tcx = self.rcx.tcx();
+        let source_map = tcx.sess.source_map();
+
+        let statics: Vec<DefId> = tcx
+            .hir_crate_items(())
+            .definitions()
+            .map(LocalDefId::to_def_id)
+            .filter(|&def_id| tcx.is_mutable_static(def_id))
+            .collect();
+        if statics.is_empty() {
+            return;
+        }
+
+        // Accesses in every function and closure body of the crate
+        let mut collector = inner::StaticAccessCollector::new(self.rcx);
+        for body_did in tcx.hir().body_owners() {
+            if tcx.def_kind(body_did).is_fn_like() {
+                collector.collect_body(body_did);
+            }
+        }
+        let unsynchronized = collector.unsynchronized_bodies();
+
+        // Group accesses per static
+        for static_did in statics {
+            let accesses: Vec<_> = collector
+                .accesses()
+                .iter()
+                .filter(|access| access.static_did == static_did)
+                .collect();
+
+            // Statics exported from the crate can be written by other crates
+            let written = tcx
+                .effective_visibilities(())
+                .is_exported(static_did.expect_local())
+                || accesses
+                    .iter()
+                    .any(|access| access.kind != inner::AccessKind::Read);
+
+            let mut behavior_flag = BehaviorFlag::empty();
+            let mut flagged_spans: Vec<(BehaviorFlag, Span)> = Vec::new();
+            let mut other_spans: Vec<Span> = Vec::new();
+            for access in accesses.iter() {
+                let flag = match access.kind {
+                    inner::AccessKind::Escape => BehaviorFlag::ESCAPE_REF,
+                    _ if !unsynchronized.contains(&access.body_did) => BehaviorFlag::empty(),
+                    inner::AccessKind::Write => BehaviorFlag::WRITE,
+                    inner::AccessKind::Read if written => BehaviorFlag::READ,
+                    inner::AccessKind::Read => BehaviorFlag::empty(),
+                };
+                if flag.is_empty() {
+                    other_spans.push(access.span);
+                } else {
+                    behavior_flag |= flag;
+                    // `COUNTER += 1` both reads and writes the static
+                    match flagged_spans
+                        .iter_mut()
+                        .find(|(_, span)| *span == access.span)
+                    {
+                        Some((span_flag, _)) => *span_flag |= flag,
+                        None => flagged_spans.push((flag, access.span)),
+                    }
+                }
+            }
+            other_spans.sort();
+            other_spans.dedup();
+            other_spans.retain(|span| flagged_spans.iter().all(|(_, flagged)| flagged != span));
+
+            if behavior_flag.is_empty() || behavior_flag.report_level() < self.rcx.report_level() {
+                continue;
+            }
+
+            // Extend the reported span to cover all accesses in the same file
+            let static_span = tcx.def_span(static_did);
+            let main_span = accesses
+                .iter()
+                .map(|access| access.span)
+                .filter(|span| {
+                    !span.from_expansion()
+                        && source_map.span_to_filename(*span)
+                            == source_map.span_to_filename(static_span)
+                })
+                .fold(static_span, |main_span, span| main_span.to(span));
+
+            let mut color_span = unwrap_or!(
+                utils::ColorSpan::new(tcx, main_span).context(InvalidSpan) => continue
+            );
+
+            color_span.add_sub_span(Color::Cyan, static_span);
+            for span in other_spans {
+                color_span.add_sub_span(Color::Yellow, span);
+            }
+            for (flag, span) in flagged_spans {
+                let color = if flag == BehaviorFlag::READ {
+                    Color::Yellow
+                } else {
+                    Color::Red
+                };
+                color_span.add_sub_span(color, span);
+            }
+
+            rudra_report(Report::with_color_span(
+                tcx,
+                behavior_flag.report_level(),
+                AnalysisKind::StaticMut(behavior_flag),
+                format!(
+                    "Unsynchronized access to `static mut` `{}`",
+                    tcx.def_path_str(static_did)
+                ),
+                &color_span,
+            ))
+        }
+    }
+}
+
+mod inner {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum AccessKind {
+        Read,
+        Write,
+        // A reference to the static is returned or stored through an argument
+        Escape,
+    }
+
+    #[derive(Debug)]
+    pub struct StaticAccess {
+        pub static_did: DefId,
+        pub body_did: LocalDefId,
+        pub kind: AccessKind,
+        pub span: Span,
+    }
+
+    pub struct StaticAccessCollector<'tcx> {
+        rcx: RudraCtxt<'tcx>,
+        accesses: Vec<StaticAccess>,
+        // Local functions and closures used by each body
+        callees: FxHashMap<LocalDefId, Vec<LocalDefId>>,
+        // Bodies that acquire a lock or run code only once
+        synchronized: FxHashSet<LocalDefId>,
+    }
+
+    impl<'tcx> StaticAccessCollector<'tcx> {
+        pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+            StaticAccessCollector {
+                rcx,
+                accesses: Vec::new(),
+                callees: FxHashMap::default(),
+                synchronized: FxHashSet::default(),
+            }
+        }
+
+        pub fn accesses(&self) -> &Vec<StaticAccess> {
+            &self.accesses
+        }
+
+        pub fn collect_body(&mut self, body_did: LocalDefId) {
+            let tcx = self.rcx.tcx();
+            let ext = tcx.ext();
+
+            let translation = self.rcx.translate_body(body_did.to_def_id());
+            let body = match translation.as_ref() {
+                Err(e) => {
+                    // MIR is not available for def - log it and continue
+                    e.log();
+                    return;
+                }
+                Ok(body) => body,
+            };
+
+            let mut callees = Vec::new();
+            for terminator in body.terminators() {
+                if let ir::TerminatorKind::StaticCall { callee_did, .. } = terminator.kind {
+                    if paths::SYNCHRONIZATION_LIST.contains(&ext.get_def_path(callee_did)) {
+                        self.synchronized.insert(body_did);
+                    }
+                    if let Some(callee_did) = callee_did.as_local() {
+                        callees.push(callee_did);
+                    }
+                }
+            }
+
+            // `_1 = const {alloc: *mut T}` is a pointer to a static
+            let mut static_ptrs = FxHashMap::default();
+            for statement in body.statements() {
+                match &statement.kind {
+                    StatementKind::Assign(box (
+                        place,
+                        Rvalue::Use(Operand::Constant(constant)),
+                    )) => {
+                        if let Some(static_did) = constant.check_static_ptr(tcx) {
+                            if tcx.is_mutable_static(static_did) {
+                                static_ptrs.insert(place.local, static_did);
+                            }
+                        }
+                    }
+                    StatementKind::Assign(box (
+                        _,
+                        Rvalue::Aggregate(box AggregateKind::Closure(closure_did, _), _),
+                    )) => {
+                        if let Some(closure_did) = closure_did.as_local() {
+                            callees.push(closure_did);
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            self.callees.insert(body_did, callees);
+
+            for (&ptr, &static_did) in static_ptrs.iter() {
+                let aliases: FxHashSet<_> = body
+                    .derived_locals(ptr)
+                    .into_iter()
+                    .filter(|local| {
+                        let ty = body.local_decls[local.index()].ty;
+                        ty.is_ref() || ty.is_raw_ptr()
+                    })
+                    .collect();
+
+                let mut access_visitor = AccessVisitor {
+                    aliases: &aliases,
+                    accesses: Vec::new(),
+                    borrows: Vec::new(),
+                    current_span: None,
+                };
+                access_visitor.visit_body(&body.original);
+
+                for (kind, span) in access_visitor.accesses {
+                    self.accesses.push(StaticAccess {
+                        static_did,
+                        body_did,
+                        kind,
+                        span,
+                    });
+                }
+
+                // Unsafe functions leave it to their callers to not keep the reference alive
+                if is_unsafe_fn(tcx, body_did) {
+                    continue;
+                }
+                for reference in access_visitor.borrows {
+                    for span in escapes(body, &body.derived_locals(reference)) {
+                        self.accesses.push(StaticAccess {
+                            static_did,
+                            body_did,
+                            kind: AccessKind::Escape,
+                            span,
+                        });
+                    }
+                }
+            }
+        }
+
+        /// Returns the bodies reachable from a safe exported function
+        /// without passing through a synchronized body.
+        pub fn unsynchronized_bodies(&self) -> FxHashSet<LocalDefId> {
+            let ext = self.rcx.tcx().ext();
+            let mut visited = FxHashSet::default();
+            let mut worklist: Vec<LocalDefId> = self
+                .callees
+                .keys()
+                .copied()
+                .filter(|&body_did| ext.is_exported_safe_fn(body_did))
+                .collect();
+
+            while let Some(body_did) = worklist.pop() {
+                if self.synchronized.contains(&body_did) || !visited.insert(body_did) {
+                    continue;
+                }
+                if let Some(callees) = self.callees.get(&body_did) {
+                    worklist.extend(callees.iter().copied());
+                }
+            }
+
+            visited
+        }
+    }
+
+    fn is_unsafe_fn(tcx: TyCtxt<'_>, def_id: LocalDefId) -> bool {
+        matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+            && tcx.fn_sig(def_id).skip_binder().safety().is_unsafe()
+    }
+
+    /// Returns the spans of statements that return `references`
+    /// or store them through a pointer passed as an argument.
+    fn escapes(body: &ir::Body<'_>, references: &FxHashSet<mir::Local>) -> Vec<Span> {
+        let arg_count = body.original.arg_count;
+        let is_reference = |operand: &Operand<'_>| {
+            operand.place().is_some_and(|place| {
+                place.projection.is_empty() && references.contains(&place.local)
+            })
+        };
+
+        body.statements()
+            .filter(|statement| match &statement.kind {
+                StatementKind::Assign(box (place, rvalue)) => {
+                    let stores_reference = match rvalue {
+                        Rvalue::Use(operand) | Rvalue::Cast(_, operand, _) => is_reference(operand),
+                        Rvalue::Aggregate(_, operands) => operands.iter().any(is_reference),
+                        _ => false,
+                    };
+                    stores_reference
+                        && (place.local == RETURN_PLACE
+                            || place.is_indirect_first_projection()
+                                && (1..=arg_count)
+                                    .contains(&body.borrowed_root(place.local).index()))
+                }
+                _ => false,
+            })
+            .map(|statement| statement.source_info.span)
+            .collect()
+    }
+
+    /// Collects reads and writes through a pointer to a static,
+    /// and references created from it (`&mut *_1`).
+    struct AccessVisitor<'a> {
+        aliases: &'a FxHashSet<mir::Local>,
+        accesses: Vec<(AccessKind, Span)>,
+        borrows: Vec<mir::Local>,
+        current_span: Option<Span>,
+    }
+
+    impl<'tcx> Visitor<'tcx> for AccessVisitor<'_> {
+        fn visit_statement(&mut self, statement: &mir::Statement<'tcx>, location: Location) {
+            self.current_span = Some(statement.source_info.span);
+            if let StatementKind::Assign(box (place, Rvalue::Ref(_, _, source))) = &statement.kind {
+                if source.is_indirect_first_projection() && self.aliases.contains(&source.local) {
+                    self.borrows.push(place.local);
+                }
+            }
+            self.super_statement(statement, location);
+        }
+
+        fn visit_terminator(&mut self, terminator: &mir::Terminator<'tcx>, location: Location) {
+            self.current_span = Some(terminator.source_info.span);
+            self.super_terminator(terminator, location);
+        }
+
+        fn visit_place(
+            &mut self,
+            place: &mir::Place<'tcx>,
+            context: PlaceContext,
+            _location: Location,
+        ) {
+            // Taking the address of the static does not access it
+            let kind = match context {
+                PlaceContext::NonMutatingUse(NonMutatingUseContext::RawBorrow)
+                | PlaceContext::MutatingUse(MutatingUseContext::RawBorrow)
+                | PlaceContext::NonUse(_) => return,
+                PlaceContext::MutatingUse(_) => AccessKind::Write,
+                PlaceContext::NonMutatingUse(_) => AccessKind::Read,
+            };
+            if_chain! {
+                if place.is_indirect_first_projection();
+                if self.aliases.contains(&place.local);
+                if let Some(span) = self.current_span;
+                then {
+                    self.accesses.push((kind, span));
+                }
+            }
+        }
+    }
+}
+
+bitflags! {
+    #[derive(Default)]
+    pub struct BehaviorFlag: u8 {
+        // Unsynchronized read of a static that is written somewhere
+        const READ = 0b00000001;
+        // Unsynchronized write
+        const WRITE = 0b00000010;
+        // A reference to the static outlives the function
+        const ESCAPE_REF = 0b00000100;
+    }
+}
+
+impl IntoReportLevel for BehaviorFlag {
+    fn report_level(&self) -> ReportLevel {
+        if self.intersects(BehaviorFlag::WRITE | BehaviorFlag::ESCAPE_REF) {
+            ReportLevel::Error
+        } else {
+            ReportLevel::Warning
+        }
+    }
+}
@@ -0,0 +1,587 @@
+//! Transmute validity checker based on type layouts
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::def::Res;
+use rustc_hir::intravisit::{self, VisitorExt};
+use rustc_hir::{BodyId, Expr, ExprKind, FnRetTy, GenericArg, HirId, LifetimeName, QPath};
+use rustc_middle::hir::nested_filter::OnlyBodies;
+use rustc_middle::mir::visit::{PlaceContext, Visitor};
+use rustc_middle::mir::{self, CastKind, Location, Mutability, Operand, Rvalue, StatementKind};
+use rustc_middle::ty::layout::TyAndLayout;
+use rustc_middle::ty::{self, Ty, TyCtxt, TyKind, TypingEnv};
+use rustc_span::Span;
+
+use snafu::{Backtrace, Snafu};
+use termcolor::Color;
+
+use crate::prelude::*;
+use crate::{
+    analysis::{AnalysisKind, IntoReportLevel},
+    ir,
+    paths::*,
+    report::{Report, ReportLevel},
+    utils,
+    visitor::ContainsUnsafe,
+};
+
+#[derive(Debug, Snafu)]
+pub enum TransmuteValidityError {
+    InvalidSpan { backtrace: Backtrace },
+}
+
+impl AnalysisError for TransmuteValidityError {
+    fn kind(&self) -> AnalysisErrorKind {
+        use TransmuteValidityError::*;
+        match self {
+            InvalidSpan { .. } => AnalysisErrorKind::Unreachable,
+        }
+    }
+}
+
+pub struct TransmuteValidityChecker<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+}
+
+impl<'tcx> TransmuteValidityChecker<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+        TransmuteValidityChecker { rcx }
+    }
+
+    pub fn analyze(self) {
+        let tcx = self.rcx.tcx();
+        let hir_map = tcx.hir();
+
+        // Iterates all (type, related function) pairs
+        for (_ty_hir_id, (body_id, related_item_span)) in self.rcx.types_with_related_items() {
+            if let Some(status) =
+                inner::TransmuteValidityBodyAnalyzer::analyze_body(self.rcx, body_id)
+            {
+                let behavior_flag = status.behavior_flag();
+                if !behavior_flag.is_empty()
+                    && behavior_flag.report_level() >= self.rcx.report_level()
+                {
+                    let mut color_span = unwrap_or!(
+                        utils::ColorSpan::new(tcx, related_item_span).context(InvalidSpan) => continue
+                    );
+
+                    for &span in status.invalid_transmute_spans() {
+                        color_span.add_sub_span(Color::Red, span);
+                    }
+
+                    rudra_report(Report::with_color_span(
+                        tcx,
+                        behavior_flag.report_level(),
+                        AnalysisKind::TransmuteValidity(behavior_flag),
+                        format!(
+                            "Potentially invalid transmute in `{}`",
+                            tcx.def_path_str(hir_map.body_owner_def_id(body_id).to_def_id())
+                        ),
+                        &color_span,
+                    ))
+                }
+            }
+        }
+    }
+}
+
+mod inner {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct TransmuteValidityStatus {
+        invalid_transmutes: Vec<Span>,
+        behavior_flag: BehaviorFlag,
+    }
+
+    impl TransmuteValidityStatus {
+        pub fn behavior_flag(&self) -> BehaviorFlag {
+            self.behavior_flag
+        }
+
+        pub fn invalid_transmute_spans(&self) -> &Vec<Span> {
+            &self.invalid_transmutes
+        }
+
+        fn record(&mut self, flag: BehaviorFlag, span: Span) {
+            if !flag.is_empty() {
+                self.behavior_flag |= flag;
+                self.invalid_transmutes.push(span);
+            }
+        }
+    }
+
+    /// How the destination of a reinterpretation is accessed.
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Reinterpret {
+        /// `mem::transmute`: the value itself is reinterpreted.
+        Value,
+        /// `mem::transmute_copy`: the destination is read (unaligned) from the source.
+        Copy,
+        /// Pointer cast followed by a dereference: only the pointee is reinterpreted.
+        Pointee,
+    }
+
+    pub struct TransmuteValidityBodyAnalyzer<'a, 'tcx> {
+        rcx: RudraCtxt<'tcx>,
+        body_id: BodyId,
+        body: &'a ir::Body<'tcx>,
+        typing_env: TypingEnv<'tcx>,
+        status: TransmuteValidityStatus,
+    }
+
+    impl<'a, 'tcx> TransmuteValidityBodyAnalyzer<'a, 'tcx> {
+        fn new(rcx: RudraCtxt<'tcx>, body_id: BodyId, body: &'a ir::Body<'tcx>) -> Self {
+            TransmuteValidityBodyAnalyzer {
+                rcx,
+                body_id,
+                body,
+                typing_env: body.original.typing_env(rcx.tcx()),
+                status: Default::default(),
+            }
+        }
+
+        pub fn analyze_body(
+            rcx: RudraCtxt<'tcx>,
+            body_id: BodyId,
+        ) -> Option<TransmuteValidityStatus> {
+            let hir_map = rcx.tcx().hir();
+            let body_did = hir_map.body_owner_def_id(body_id).to_def_id();
+
+            if ContainsUnsafe::contains_unsafe(rcx.tcx(), body_id) {
+                match rcx.translate_body(body_did).as_ref() {
+                    Err(e) => {
+                        // MIR is not available for def - log it and continue
+                        e.log();
+                        None
+                    }
+                    Ok(body) => {
+                        let body_analyzer = TransmuteValidityBodyAnalyzer::new(rcx, body_id, body);
+                        Some(body_analyzer.analyze())
+                    }
+                }
+            } else {
+                // Transmutes and raw pointer dereferences require unsafe code
+                Some(Default::default())
+            }
+        }
+
+        fn analyze(mut self) -> TransmuteValidityStatus {
+            let tcx = self.rcx.tcx();
+            let ext = tcx.ext();
+
+            let mut deref_collector = DerefCollector::default();
+            deref_collector.visit_body(&self.body.original);
+            let dereferenced = deref_collector.dereferenced;
+
+            // `mem::transmute` and pointer casts
+            for statement in self.body.statements() {
+                if let StatementKind::Assign(box (
+                    place,
+                    Rvalue::Cast(cast_kind, operand, dst_ty),
+                )) = &statement.kind
+                {
+                    let src_ty = operand.ty(self.body, tcx);
+                    let flag = match cast_kind {
+                        CastKind::Transmute => self.check(src_ty, *dst_ty, Reinterpret::Value),
+                        CastKind::PtrToPtr
+                            if self
+                                .body
+                                .derived_locals(place.local)
+                                .iter()
+                                .any(|local| dereferenced.contains(local)) =>
+                        {
+                            let mut flag = self.check(src_ty, *dst_ty, Reinterpret::Pointee);
+                            if !self.exceeds_source_buffer(operand, *dst_ty) {
+                                flag.remove(BehaviorFlag::SIZE_MISMATCH);
+                            }
+                            flag
+                        }
+                        _ => continue,
+                    };
+                    self.status.record(flag, statement.source_info.span);
+                }
+            }
+
+            // `mem::transmute_copy`
+            for terminator in self.body.terminators() {
+                if_chain! {
+                    if let ir::TerminatorKind::StaticCall {
+                        callee_did,
+                        callee_substs,
+                        ..
+                    } = terminator.kind;
+                    if ext.match_def_path(callee_did, &MEM_TRANSMUTE_COPY);
+                    let mut types = callee_substs.types();
+                    if let (Some(src_ty), Some(dst_ty)) = (types.next(), types.next());
+                    then {
+                        let flag = self.check(src_ty, dst_ty, Reinterpret::Copy);
+                        self.status.record(flag, terminator.original.source_info.span);
+                    }
+                }
+            }
+
+            // `mem::transmute` that extends a lifetime
+            for span in LifetimeExtensionCollector::collect(tcx, self.body_id) {
+                self.status.record(BehaviorFlag::LIFETIME_EXTENSION, span);
+            }
+
+            self.status
+        }
+
+        /// A raw pointer returned from a call (`buf.as_ptr()`) may point into a buffer that is
+        /// larger than its pointee. Its size is only known when the buffer is an array.
+        fn exceeds_source_buffer(&self, operand: &Operand<'tcx>, dst_ty: Ty<'tcx>) -> bool {
+            let tcx = self.rcx.tcx();
+            let root = match operand.place() {
+                Some(place) => self.body.borrowed_root(place.local),
+                None => return true,
+            };
+            if !self.body.local_decls[root.index()].ty.is_raw_ptr() {
+                return true;
+            }
+
+            let buffer_ty = self
+                .body
+                .terminators()
+                .find_map(|terminator| match &terminator.kind {
+                    ir::TerminatorKind::StaticCall {
+                        args,
+                        destination: Some((place, _)),
+                        ..
+                    } if place.local == root => {
+                        let arg = args.first().and_then(Operand::place)?;
+                        let buffer = self.body.borrowed_root(arg.local);
+                        Some(self.body.local_decls[buffer.index()].ty.peel_refs())
+                    }
+                    _ => None,
+                });
+
+            match (buffer_ty, pointee(dst_ty)) {
+                (Some(buffer_ty), Some(dst_ty)) if buffer_ty.is_array() => match (
+                    layout_of(tcx, self.typing_env, buffer_ty),
+                    layout_of(tcx, self.typing_env, dst_ty),
+                ) {
+                    (Some(buffer_layout), Some(dst_layout)) => dst_layout.size > buffer_layout.size,
+                    _ => false,
+                },
+                _ => false,
+            }
+        }
+
+        fn check(&self, src_ty: Ty<'tcx>, dst_ty: Ty<'tcx>, kind: Reinterpret) -> BehaviorFlag {
+            let tcx = self.rcx.tcx();
+            let mut flag = BehaviorFlag::empty();
+
+            if kind != Reinterpret::Pointee {
+                if let (TyKind::Ref(_, _, Mutability::Not), TyKind::Ref(_, _, Mutability::Mut)) =
+                    (src_ty.kind(), dst_ty.kind())
+                {
+                    flag |= BehaviorFlag::REF_TO_MUT;
+                }
+            }
+
+            let is_pointer_cast = kind == Reinterpret::Pointee;
+
+            // Compare the pointees when a pointer is reinterpreted as another pointer.
+            let (src_ty, dst_ty, kind) = match (kind, pointee(src_ty), pointee(dst_ty)) {
+                (Reinterpret::Pointee, Some(src), Some(dst)) => (src, dst, kind),
+                (Reinterpret::Value, Some(src), Some(dst)) => (src, dst, Reinterpret::Pointee),
+                (Reinterpret::Pointee, _, _) => return flag,
+                _ => (src_ty, dst_ty, kind),
+            };
+
+            // `*mut c_void` is an opaque pointer that is cast back to its original type
+            if let TyKind::Adt(adt_def, _) = src_ty.kind() {
+                if kind == Reinterpret::Pointee
+                    && tcx.ext().match_def_path(adt_def.did(), &FFI_C_VOID)
+                {
+                    return flag;
+                }
+            }
+
+            let (src_layout, dst_layout) = match (
+                layout_of(tcx, self.typing_env, src_ty),
+                layout_of(tcx, self.typing_env, dst_ty),
+            ) {
+                (Some(src_layout), Some(dst_layout)) => (src_layout, dst_layout),
+                // Layouts of generic types are unknown
+                _ => return flag,
+            };
+
+            if dst_layout.size > src_layout.size && dst_layout.is_sized() && src_layout.is_sized() {
+                flag |= BehaviorFlag::SIZE_MISMATCH;
+            }
+
+            // `transmute_copy` uses an unaligned read, and the alignment of pointer casts
+            // is checked by `UnalignedAccess`
+            if kind == Reinterpret::Pointee
+                && !is_pointer_cast
+                && dst_layout.align.abi > src_layout.align.abi
+            {
+                flag |= BehaviorFlag::ALIGN_MISMATCH;
+            }
+
+            if has_invalid_bit_patterns(dst_layout) && !has_invalid_bit_patterns(src_layout) {
+                flag |= BehaviorFlag::INVALID_VALUE;
+            }
+
+            flag
+        }
+    }
+
+    /// Collects locals that are dereferenced (`*_1`) in a MIR body.
+    #[derive(Default)]
+    struct DerefCollector {
+        dereferenced: FxHashSet<mir::Local>,
+    }
+
+    impl<'tcx> Visitor<'tcx> for DerefCollector {
+        fn visit_place(
+            &mut self,
+            place: &mir::Place<'tcx>,
+            _context: PlaceContext,
+            _location: Location,
+        ) {
+            if place.is_indirect_first_projection() {
+                self.dereferenced.insert(place.local);
+            }
+        }
+    }
+
+    /// Collects `mem::transmute` calls that extend a lifetime. Regions are erased in MIR,
+    /// so the lifetimes written in the turbofish of the call are compared instead, or those
+    /// in the signature of the function when a parameter is transmuted into the return value.
+    struct LifetimeExtensionCollector<'tcx> {
+        tcx: TyCtxt<'tcx>,
+        body_id: BodyId,
+        returned: FxHashSet<HirId>,
+        spans: Vec<Span>,
+    }
+
+    impl<'tcx> LifetimeExtensionCollector<'tcx> {
+        fn collect(tcx: TyCtxt<'tcx>, body_id: BodyId) -> Vec<Span> {
+            use intravisit::Visitor;
+
+            let body = tcx.hir().body(body_id);
+            let mut collector = LifetimeExtensionCollector {
+                tcx,
+                body_id,
+                returned: FxHashSet::default(),
+                spans: Vec::new(),
+            };
+            // The tail expression of the body is returned
+            let mut tail = Some(body.value);
+            while let Some(expr) = tail {
+                collector.returned.insert(expr.hir_id);
+                tail = match expr.kind {
+                    ExprKind::Block(block, _) => block.expr,
+                    _ => None,
+                };
+            }
+            collector.visit_body(body);
+            collector.spans
+        }
+
+        /// Lifetimes written in the source and the destination type of a transmute call
+        fn written_lifetimes(
+            &self,
+            call: &'tcx Expr<'tcx>,
+            func: &'tcx Expr<'tcx>,
+            args: &'tcx [Expr<'tcx>],
+        ) -> (Option<Vec<LifetimeName>>, Option<Vec<LifetimeName>>) {
+            let hir = self.tcx.hir();
+            let body = hir.body(self.body_id);
+            let fn_decl = hir.fn_decl_by_hir_id(
+                self.tcx
+                    .local_def_id_to_hir_id(hir.body_owner_def_id(self.body_id)),
+            );
+
+            let mut turbofish = match &func.kind {
+                ExprKind::Path(QPath::Resolved(_, path)) => path
+                    .segments
+                    .last()
+                    .and_then(|segment| segment.args)
+                    .map(|generic_args| {
+                        generic_args
+                            .args
+                            .iter()
+                            .map(|arg| match arg {
+                                GenericArg::Type(ty) => {
+                                    let mut collector = LifetimeCollector::default();
+                                    intravisit::Visitor::visit_ty(&mut collector, ty);
+                                    Some(collector.lifetimes)
+                                }
+                                _ => None,
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default(),
+                _ => Vec::new(),
+            }
+            .into_iter();
+            let written = |lifetimes: &Option<Vec<LifetimeName>>| {
+                lifetimes.as_ref().is_some_and(|lifetimes| {
+                    lifetimes
+                        .iter()
+                        .all(|lifetime| !matches!(lifetime, LifetimeName::Infer))
+                })
+            };
+            let from_signature = |ty: &'tcx rustc_hir::Ty<'tcx>| {
+                let mut collector = LifetimeCollector::default();
+                collector.visit_ty_unambig(ty);
+                Some(collector.lifetimes)
+            };
+
+            let mut src = turbofish.next().flatten();
+            if !written(&src) {
+                // A parameter of the function is transmuted
+                let param_ty = args.first().and_then(|arg| match &arg.kind {
+                    ExprKind::Path(QPath::Resolved(None, path)) => match path.res {
+                        Res::Local(hir_id) => {
+                            let idx = body
+                                .params
+                                .iter()
+                                .position(|param| param.pat.hir_id == hir_id)?;
+                            fn_decl?.inputs.get(idx)
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                });
+                src = param_ty.and_then(from_signature).or(src);
+            }
+
+            let mut dst = turbofish.next().flatten();
+            if !written(&dst) && self.returned.contains(&call.hir_id) {
+                if let Some(FnRetTy::Return(ret_ty)) = fn_decl.map(|fn_decl| fn_decl.output) {
+                    dst = from_signature(ret_ty);
+                }
+            }
+
+            (src, dst)
+        }
+
+        /// Returns true if `dst` outlives `src` for some pair of corresponding lifetimes
+        fn extends(&self, src: &[LifetimeName], dst: &[LifetimeName]) -> bool {
+            let hir = self.tcx.hir();
+            let param_env = self
+                .tcx
+                .param_env(hir.body_owner_def_id(self.body_id).to_def_id());
+            // `'a: 'b` in the where clauses
+            let outlives = |a, b| {
+                param_env.caller_bounds().iter().any(|clause| {
+                    clause.as_region_outlives_clause().is_some_and(|predicate| {
+                        let ty::OutlivesPredicate(longer, shorter) = predicate.skip_binder();
+                        longer.get_name() == Some(self.tcx.item_name(a))
+                            && shorter.get_name() == Some(self.tcx.item_name(b))
+                    })
+                })
+            };
+
+            src.len() == dst.len()
+                && src.iter().zip(dst.iter()).any(|pair| match pair {
+                    (LifetimeName::Static, _) => false,
+                    (LifetimeName::Param(_) | LifetimeName::Infer, LifetimeName::Static) => true,
+                    (LifetimeName::Param(a), LifetimeName::Param(b)) => {
+                        a != b && !outlives(a.to_def_id(), b.to_def_id())
+                    }
+                    _ => false,
+                })
+        }
+    }
+
+    impl<'tcx> intravisit::Visitor<'tcx> for LifetimeExtensionCollector<'tcx> {
+        type NestedFilter = OnlyBodies;
+
+        fn nested_visit_map(&mut self) -> Self::Map {
+            self.tcx.hir()
+        }
+
+        fn visit_expr(&mut self, expr: &'tcx Expr<'tcx>) {
+            match expr.kind {
+                ExprKind::Ret(Some(returned)) => {
+                    self.returned.insert(returned.hir_id);
+                }
+                ExprKind::Call(func, args)
+                    if expr.ext().as_fn_def_id(self.tcx).is_some_and(|def_id| {
+                        self.tcx.ext().match_def_path(def_id, &INTRINSICS_TRANSMUTE)
+                    }) =>
+                {
+                    if let (Some(src), Some(dst)) = self.written_lifetimes(expr, func, args) {
+                        if self.extends(&src, &dst) {
+                            self.spans.push(expr.span);
+                        }
+                    }
+                }
+                _ => (),
+            }
+            intravisit::walk_expr(self, expr);
+        }
+    }
+
+    /// Collects the lifetimes written in a type, in order.
+    #[derive(Default)]
+    struct LifetimeCollector {
+        lifetimes: Vec<LifetimeName>,
+    }
+
+    impl<'tcx> intravisit::Visitor<'tcx> for LifetimeCollector {
+        fn visit_lifetime(&mut self, lifetime: &'tcx rustc_hir::Lifetime) {
+            self.lifetimes.push(lifetime.res);
+        }
+    }
+
+    fn pointee(ty: Ty<'_>) -> Option<Ty<'_>> {
+        match ty.kind() {
+            TyKind::Ref(_, pointee_ty, _) | TyKind::RawPtr(pointee_ty, _) => Some(*pointee_ty),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the layout of `ty`, or `None` if it cannot be computed (e.g. generic types).
+pub(crate) fn layout_of<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    typing_env: TypingEnv<'tcx>,
+    ty: Ty<'tcx>,
+) -> Option<TyAndLayout<'tcx>> {
+    tcx.layout_of(typing_env.as_query_input(ty)).ok()
+}
+
+/// A type has invalid bit patterns if its layout has a niche
+/// (e.g. `bool`, `char`, references, `NonNull`, `NonZero*` and most enums).
+pub(crate) fn has_invalid_bit_patterns(layout: TyAndLayout<'_>) -> bool {
+    layout.largest_niche.is_some()
+}
+
+bitflags! {
+    #[derive(Default)]
+    pub struct BehaviorFlag: u8 {
+        // Destination is larger than the source
+        const SIZE_MISMATCH = 0b00000001;
+        // Destination pointee requires a larger alignment than the source pointee
+        const ALIGN_MISMATCH = 0b00000010;
+        // Destination has invalid bit patterns that the source may contain
+        const INVALID_VALUE = 0b00000100;
+        // Source and destination only differ in their lifetimes
+        const LIFETIME_EXTENSION = 0b00001000;
+        // `&T` is transmuted into `&mut T`
+        const REF_TO_MUT = 0b00010000;
+    }
+}
+
+impl IntoReportLevel for BehaviorFlag {
+    fn report_level(&self) -> ReportLevel {
+        use BehaviorFlag as Flag;
+
+        let high = Flag::SIZE_MISMATCH | Flag::REF_TO_MUT;
+        let med = Flag::INVALID_VALUE | Flag::ALIGN_MISMATCH;
+
+        if !(*self & high).is_empty() {
+            ReportLevel::Error
+        } else if !(*self & med).is_empty() {
+            ReportLevel::Warning
+        } else {
+            ReportLevel::Info
+        }
+    }
+}
@@ -0,0 +1,385 @@
+//! Unaligned access detector for pointer casts
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::BodyId;
+use rustc_middle::mir::visit::{MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor};
+use rustc_middle::mir::{self, CastKind, Location, Operand, Rvalue, StatementKind};
+use rustc_middle::ty::{Ty, TyKind, TypeVisitableExt, TypingEnv};
+use rustc_span::Span;
+
+use snafu::{Backtrace, Snafu};
+use termcolor::Color;
+
+use crate::prelude::*;
+use crate::{
+    analysis::{transmute_validity::layout_of, AnalysisKind, IntoReportLevel},
+    ir,
+    paths::{self, *},
+    report::{Report, ReportLevel},
+    utils,
+    visitor::ContainsUnsafe,
+};
+
+#[derive(Debug, Snafu)]
+pub enum UnalignedAccessError {
+    InvalidSpan { backtrace: Backtrace },
+}
+
+impl AnalysisError for UnalignedAccessError {
+    fn kind(&self) -> AnalysisErrorKind {
+        use UnalignedAccessError::*;
+        match self {
+            InvalidSpan { .. } => AnalysisErrorKind::Unreachable,
+        }
+    }
+}
+
+pub struct UnalignedAccessChecker<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+}
+
+impl<'tcx> UnalignedAccessChecker<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+        UnalignedAccessChecker { rcx }
+    }
+
+    pub fn analyze(self) {
+        let tcx = self.rcx.tcx();
+        let hir_map = tcx.hir();
+
+        // Iterates all (type, related function) pairs
+        for (_ty_hir_id, (body_id, related_item_span)) in self.rcx.types_with_related_items() {
+            if let Some(status) =
+                inner::UnalignedAccessBodyAnalyzer::analyze_body(self.rcx, body_id)
+            {
+                let behavior_flag = status.behavior_flag();
+                if !behavior_flag.is_empty()
+                    && behavior_flag.report_level() >= self.rcx.report_level()
+                {
+                    let mut color_span = unwrap_or!(
+                        utils::ColorSpan::new(tcx, related_item_span).context(InvalidSpan) => continue
+                    );
+
+                    for &span in status.cast_spans() {
+                        color_span.add_sub_span(Color::Yellow, span);
+                    }
+
+                    for &span in status.access_spans() {
+                        color_span.add_sub_span(Color::Red, span);
+                    }
+
+                    rudra_report(Report::with_color_span(
+                        tcx,
+                        behavior_flag.report_level(),
+                        AnalysisKind::UnalignedAccess(behavior_flag),
+                        format!(
+                            "Potential unaligned access through a pointer cast in `{}`",
+                            tcx.def_path_str(hir_map.body_owner_def_id(body_id).to_def_id())
+                        ),
+                        &color_span,
+                    ))
+                }
+            }
+        }
+    }
+}
+
+mod inner {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct UnalignedAccessStatus {
+        casts: Vec<Span>,
+        accesses: Vec<Span>,
+        behavior_flag: BehaviorFlag,
+    }
+
+    impl UnalignedAccessStatus {
+        pub fn behavior_flag(&self) -> BehaviorFlag {
+            self.behavior_flag
+        }
+
+        pub fn cast_spans(&self) -> &Vec<Span> {
+            &self.casts
+        }
+
+        pub fn access_spans(&self) -> &Vec<Span> {
+            &self.accesses
+        }
+    }
+
+    pub struct UnalignedAccessBodyAnalyzer<'a, 'tcx> {
+        rcx: RudraCtxt<'tcx>,
+        body: &'a ir::Body<'tcx>,
+        typing_env: TypingEnv<'tcx>,
+        status: UnalignedAccessStatus,
+    }
+
+    impl<'a, 'tcx> UnalignedAccessBodyAnalyzer<'a, 'tcx> {
+        fn new(rcx: RudraCtxt<'tcx>, body: &'a ir::Body<'tcx>) -> Self {
+            UnalignedAccessBodyAnalyzer {
+                rcx,
+                body,
+                typing_env: body.original.typing_env(rcx.tcx()),
+                status: Default::default(),
+            }
+        }
+
+        pub fn analyze_body(
+            rcx: RudraCtxt<'tcx>,
+            body_id: BodyId,
+        ) -> Option<UnalignedAccessStatus> {
+            let hir_map = rcx.tcx().hir();
+            let body_did = hir_map.body_owner_def_id(body_id).to_def_id();
+
+            if ContainsUnsafe::contains_unsafe(rcx.tcx(), body_id) {
+                match rcx.translate_body(body_did).as_ref() {
+                    Err(e) => {
+                        // MIR is not available for def - log it and continue
+                        e.log();
+                        None
+                    }
+                    Ok(body) => {
+                        let body_analyzer = UnalignedAccessBodyAnalyzer::new(rcx, body);
+                        Some(body_analyzer.analyze())
+                    }
+                }
+            } else {
+                // Raw pointers can only be accessed in unsafe code
+                Some(Default::default())
+            }
+        }
+
+        fn analyze(mut self) -> UnalignedAccessStatus {
+            let mut access_collector = AccessCollector::default();
+            access_collector.visit_body(&self.body.original);
+            let checked_locals = self.alignment_checked_locals();
+            let aligned_locals = self.aligned_source_locals();
+
+            for statement in self.body.statements() {
+                if_chain! {
+                    if let StatementKind::Assign(box (
+                        place,
+                        Rvalue::Cast(CastKind::PtrToPtr, operand, dst_ty),
+                    )) = &statement.kind;
+                    if let Some(src_place) = operand.place();
+                    let src_ty = operand.ty(self.body, self.rcx.tcx());
+                    if let (TyKind::RawPtr(src_pointee, _), TyKind::RawPtr(dst_pointee, _)) =
+                        (src_ty.kind(), dst_ty.kind());
+                    if let Some(alignment_flag) = self.alignment_increase(*src_pointee, *dst_pointee);
+                    then {
+                        let aliases = self.body.derived_locals(place.local);
+                        if checked_locals.contains(&place.local)
+                            || checked_locals.contains(&self.body.borrowed_root(src_place.local))
+                        {
+                            // The alignment is checked (`ptr.is_aligned()`, `ptr as usize % 4`)
+                            continue;
+                        }
+                        if aligned_locals.contains(&src_place.local) {
+                            // The pointer comes from an allocator or `Box::into_raw`
+                            continue;
+                        }
+
+                        let mut accesses = Vec::new();
+                        let mut access_flag = BehaviorFlag::empty();
+                        for &(local, span) in access_collector.derefs.iter() {
+                            if aliases.contains(&local) {
+                                access_flag |= BehaviorFlag::DEREF;
+                                accesses.push(span);
+                            }
+                        }
+                        for (local, flag, span) in self.aligned_accesses() {
+                            if aliases.contains(&local) {
+                                access_flag |= flag;
+                                accesses.push(span);
+                            }
+                        }
+
+                        if !access_flag.is_empty() {
+                            self.status.behavior_flag |= alignment_flag | access_flag;
+                            self.status.casts.push(statement.source_info.span);
+                            self.status.accesses.extend(accesses);
+                        }
+                    }
+                }
+            }
+
+            self.status
+        }
+
+        /// Returns `Some` if `dst` may require a larger alignment than `src`.
+        fn alignment_increase(&self, src: Ty<'tcx>, dst: Ty<'tcx>) -> Option<BehaviorFlag> {
+            let tcx = self.rcx.tcx();
+            // `*mut c_void` is an opaque pointer that is cast back to its original type
+            if let TyKind::Adt(adt_def, _) = src.kind() {
+                if tcx.ext().match_def_path(adt_def.did(), &FFI_C_VOID) {
+                    return None;
+                }
+            }
+            let src_layout = layout_of(tcx, self.typing_env, src)?;
+            match layout_of(tcx, self.typing_env, dst) {
+                Some(dst_layout) if dst_layout.align.abi > src_layout.align.abi => {
+                    Some(BehaviorFlag::empty())
+                }
+                Some(_) => None,
+                // The alignment of a type parameter is chosen by the user
+                None if dst.has_param() && src_layout.align.abi.bytes() == 1 => {
+                    Some(BehaviorFlag::GENERIC)
+                }
+                None => None,
+            }
+        }
+
+        /// Calls that require an aligned pointer (`ptr::read`, `slice::from_raw_parts`).
+        fn aligned_accesses(&self) -> Vec<(mir::Local, BehaviorFlag, Span)> {
+            let ext = self.rcx.tcx().ext();
+            self.body
+                .terminators()
+                .filter_map(|terminator| match &terminator.kind {
+                    ir::TerminatorKind::StaticCall {
+                        callee_did, args, ..
+                    } => {
+                        let flag = *ALIGNED_ACCESS_MAP.get(&ext.get_def_path(*callee_did))?;
+                        let place = args.first().and_then(Operand::place)?;
+                        Some((place.local, flag, terminator.original.source_info.span))
+                    }
+                    _ => None,
+                })
+                .collect()
+        }
+
+        /// Locals whose address is inspected, and everything they are derived from.
+        fn alignment_checked_locals(&self) -> FxHashSet<mir::Local> {
+            let ext = self.rcx.tcx().ext();
+            let mut checked = FxHashSet::default();
+
+            for terminator in self.body.terminators() {
+                if_chain! {
+                    if let ir::TerminatorKind::StaticCall {
+                        callee_did,
+                        ref args,
+                        ..
+                    } = terminator.kind;
+                    if paths::ALIGNMENT_CHECK_LIST.contains(&ext.get_def_path(callee_did));
+                    if let Some(place) = args.first().and_then(Operand::place);
+                    then {
+                        checked.extend(self.body.borrow_chain(place.local));
+                    }
+                }
+            }
+
+            for statement in self.body.statements() {
+                if let StatementKind::Assign(box (
+                    _,
+                    Rvalue::Cast(CastKind::PointerExposeProvenance, operand, _),
+                )) = &statement.kind
+                {
+                    if let Some(place) = operand.place() {
+                        checked.extend(self.body.borrow_chain(place.local));
+                    }
+                }
+            }
+
+            checked
+        }
+
+        /// Locals derived from pointers that are aligned by construction
+        /// (`alloc::alloc`, `NonNull::dangling`, `Box::into_raw`).
+        fn aligned_source_locals(&self) -> FxHashSet<mir::Local> {
+            let ext = self.rcx.tcx().ext();
+            let mut aligned = FxHashSet::default();
+
+            loop {
+                let mut changed = false;
+                for terminator in self.body.terminators() {
+                    if let ir::TerminatorKind::StaticCall {
+                        callee_did,
+                        ref args,
+                        destination: Some((place, _)),
+                        ..
+                    } = terminator.kind
+                    {
+                        // Also follow `Result::unwrap` and `NonNull::as_ptr` on the allocation
+                        let is_source = paths::ALIGNED_SOURCE_LIST
+                            .contains(&ext.get_def_path(callee_did))
+                            || args
+                                .iter()
+                                .filter_map(Operand::place)
+                                .any(|arg| aligned.contains(&arg.local));
+                        if is_source && !aligned.contains(&place.local) {
+                            aligned.extend(self.body.derived_locals(place.local));
+                            changed = true;
+                        }
+                    }
+                }
+                if !changed {
+                    break;
+                }
+            }
+
+            aligned
+        }
+    }
+
+    /// Collects reads, writes and borrows through a dereferenced pointer (`*_1`, `&*_1`).
+    /// Taking the address of a place (`&raw const *_1`) does not access it.
+    #[derive(Default)]
+    struct AccessCollector {
+        derefs: Vec<(mir::Local, Span)>,
+        current_span: Option<Span>,
+    }
+
+    impl<'tcx> Visitor<'tcx> for AccessCollector {
+        fn visit_statement(&mut self, statement: &mir::Statement<'tcx>, location: Location) {
+            self.current_span = Some(statement.source_info.span);
+            self.super_statement(statement, location);
+        }
+
+        fn visit_terminator(&mut self, terminator: &mir::Terminator<'tcx>, location: Location) {
+            self.current_span = Some(terminator.source_info.span);
+            self.super_terminator(terminator, location);
+        }
+
+        fn visit_place(
+            &mut self,
+            place: &mir::Place<'tcx>,
+            context: PlaceContext,
+            _location: Location,
+        ) {
+            let is_access = !matches!(
+                context,
+                PlaceContext::NonMutatingUse(NonMutatingUseContext::RawBorrow)
+                    | PlaceContext::MutatingUse(MutatingUseContext::RawBorrow)
+                    | PlaceContext::NonUse(_)
+            );
+            if_chain! {
+                if is_access;
+                if place.is_indirect_first_projection();
+                if let Some(span) = self.current_span;
+                then {
+                    self.derefs.push((place.local, span));
+                }
+            }
+        }
+    }
+}
+
+bitflags! {
+    #[derive(Default)]
+    pub struct BehaviorFlag: u8 {
+        // The target type is generic, so its alignment is chosen by the user
+        const GENERIC = 0b00000001;
+        const DEREF = 0b00000010;
+        const PTR_READ = 0b00000100;
+        const FROM_RAW_PARTS = 0b00001000;
+    }
+}
+
+impl IntoReportLevel for BehaviorFlag {
+    fn report_level(&self) -> ReportLevel {
+        if self.contains(BehaviorFlag::GENERIC) {
+            ReportLevel::Warning
+        } else {
+            ReportLevel::Error
+        }
+    }
+}
@@ -0,0 +1,366 @@
+//! Detector for safe public functions that forward unchecked arguments into unsafe preconditions
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::{def_id::LocalDefId, BodyId};
+use rustc_middle::mir::{self, AssertKind, BinOp, Operand, Rvalue, StatementKind, TerminatorKind};
+use rustc_middle::ty::{Ty, TyCtxt, TyKind};
+use rustc_span::{sym, Span};
+
+use snafu::{Backtrace, Snafu};
+use termcolor::Color;
+
+use crate::prelude::*;
+use crate::{
+    analysis::{AnalysisKind, IntoReportLevel},
+    ir,
+    paths::{self, *},
+    report::{Report, ReportLevel},
+    utils,
+    visitor::ContainsUnsafe,
+};
+
+#[derive(Debug, Snafu)]
+pub enum UncheckedPreconditionError {
+    InvalidSpan { backtrace: Backtrace },
+}
+
+impl AnalysisError for UncheckedPreconditionError {
+    fn kind(&self) -> AnalysisErrorKind {
+        use UncheckedPreconditionError::*;
+        match self {
+            InvalidSpan { .. } => AnalysisErrorKind::Unreachable,
+        }
+    }
+}
+
+pub struct UncheckedPreconditionChecker<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+}
+
+impl<'tcx> UncheckedPreconditionChecker<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+        UncheckedPreconditionChecker { rcx }
+    }
+
+    pub fn analyze(self) {
+        let tcx = self.rcx.tcx();
+        let hir_map = tcx.hir();
+
+        // Iterates all (type, related function) pairs
+        for (_ty_hir_id, (body_id, related_item_span)) in self.rcx.types_with_related_items() {
+            if let Some(status) =
+                inner::UncheckedPreconditionBodyAnalyzer::analyze_body(self.rcx, body_id)
+            {
+                let behavior_flag = status.behavior_flag();
+                if !behavior_flag.is_empty()
+                    && behavior_flag.report_level() >= self.rcx.report_level()
+                {
+                    let mut color_span = unwrap_or!(
+                        utils::ColorSpan::new(tcx, related_item_span).context(InvalidSpan) => continue
+                    );
+
+                    for &span in status.param_spans() {
+                        color_span.add_sub_span(Color::Yellow, span);
+                    }
+
+                    for &span in status.unchecked_call_spans() {
+                        color_span.add_sub_span(Color::Red, span);
+                    }
+
+                    rudra_report(Report::with_color_span(
+                        tcx,
+                        behavior_flag.report_level(),
+                        AnalysisKind::UncheckedPrecondition(behavior_flag),
+                        format!(
+                            "Unchecked argument reaches an unsafe precondition in `{}`",
+                            tcx.def_path_str(hir_map.body_owner_def_id(body_id).to_def_id())
+                        ),
+                        &color_span,
+                    ))
+                }
+            }
+        }
+    }
+}
+
+mod inner {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct UncheckedPreconditionStatus {
+        params: Vec<Span>,
+        unchecked_calls: Vec<Span>,
+        behavior_flag: BehaviorFlag,
+    }
+
+    impl UncheckedPreconditionStatus {
+        pub fn behavior_flag(&self) -> BehaviorFlag {
+            self.behavior_flag
+        }
+
+        pub fn param_spans(&self) -> &Vec<Span> {
+            &self.params
+        }
+
+        pub fn unchecked_call_spans(&self) -> &Vec<Span> {
+            &self.unchecked_calls
+        }
+    }
+
+    pub struct UncheckedPreconditionBodyAnalyzer<'a, 'tcx> {
+        rcx: RudraCtxt<'tcx>,
+        body: &'a ir::Body<'tcx>,
+        status: UncheckedPreconditionStatus,
+    }
+
+    impl<'a, 'tcx> UncheckedPreconditionBodyAnalyzer<'a, 'tcx> {
+        fn new(rcx: RudraCtxt<'tcx>, body: &'a ir::Body<'tcx>) -> Self {
+            UncheckedPreconditionBodyAnalyzer {
+                rcx,
+                body,
+                status: Default::default(),
+            }
+        }
+
+        pub fn analyze_body(
+            rcx: RudraCtxt<'tcx>,
+            body_id: BodyId,
+        ) -> Option<UncheckedPreconditionStatus> {
+            let hir_map = rcx.tcx().hir();
+            let body_did: LocalDefId = hir_map.body_owner_def_id(body_id);
+
+            // Unsafe functions and private functions may rely on their callers
+            if !rcx.tcx().ext().is_exported_safe_fn(body_did) {
+                return Some(Default::default());
+            }
+
+            if ContainsUnsafe::contains_unsafe(rcx.tcx(), body_id) {
+                match rcx.translate_body(body_did.to_def_id()).as_ref() {
+                    Err(e) => {
+                        // MIR is not available for def - log it and continue
+                        e.log();
+                        None
+                    }
+                    Ok(body) => {
+                        let body_analyzer = UncheckedPreconditionBodyAnalyzer::new(rcx, body);
+                        Some(body_analyzer.analyze())
+                    }
+                }
+            } else {
+                // Unsafe functions can only be called in unsafe code
+                Some(Default::default())
+            }
+        }
+
+        fn analyze(mut self) -> UncheckedPreconditionStatus {
+            let ext = self.rcx.tcx().ext();
+            let dominators = self.body.original.basic_blocks.dominators();
+
+            for param in self.body.original.args_iter() {
+                let param_ty = self.body.local_decls[param.index()].ty;
+                let dependents = self.dependent_locals(param);
+                let check_blocks = self.check_blocks(&dependents);
+
+                let mut param_flag = BehaviorFlag::empty();
+                for (id, block) in self.body.basic_blocks.iter().enumerate() {
+                    if let ir::TerminatorKind::StaticCall {
+                        callee_did,
+                        ref args,
+                        ..
+                    } = block.terminator.kind
+                    {
+                        let (arg_index, flag) =
+                            match UNCHECKED_PRECONDITION_MAP.get(&ext.get_def_path(callee_did)) {
+                                Some(entry) => *entry,
+                                None => continue,
+                            };
+
+                        let param_applies = if flag == BehaviorFlag::FROM_UTF8_UNCHECKED {
+                            is_byte_buffer(self.rcx.tcx(), param_ty)
+                        } else {
+                            param_ty.is_integral()
+                        };
+                        if !param_applies {
+                            continue;
+                        }
+
+                        let forwarded = args
+                            .get(arg_index)
+                            .and_then(Operand::place)
+                            .is_some_and(|place| dependents.contains(&place.local));
+                        if !forwarded {
+                            continue;
+                        }
+
+                        let call_block = mir::BasicBlock::from_usize(id);
+                        let checked = !dominators.is_reachable(call_block)
+                            || check_blocks
+                                .iter()
+                                .any(|&check_block| dominators.dominates(check_block, call_block));
+                        if !checked {
+                            param_flag |= flag;
+                            self.status
+                                .unchecked_calls
+                                .push(block.terminator.original.source_info.span);
+                        }
+                    }
+                }
+
+                if !param_flag.is_empty() {
+                    self.status.behavior_flag |= param_flag;
+                    self.status
+                        .params
+                        .push(self.body.original.local_decls[param].source_info.span);
+                }
+            }
+
+            self.status
+        }
+
+        /// Returns the set of locals whose value depends on `param`.
+        /// Remainders, bit masks and `min`/`clamp` calls are treated as bounding the value.
+        fn dependent_locals(&self, param: mir::Local) -> FxHashSet<mir::Local> {
+            let ext = self.rcx.tcx().ext();
+            let mut dependents = FxHashSet::default();
+            dependents.insert(param);
+
+            loop {
+                let mut new_locals = Vec::new();
+                let depends = |operand: &Operand<'tcx>| {
+                    operand
+                        .place()
+                        .is_some_and(|place| dependents.contains(&place.local))
+                };
+
+                for block in self.body.basic_blocks.iter() {
+                    for statement in block.statements.iter() {
+                        if let StatementKind::Assign(box (place, rvalue)) = &statement.kind {
+                            let from_dependent = match rvalue {
+                                Rvalue::Ref(_, _, source)
+                                | Rvalue::RawPtr(_, source)
+                                | Rvalue::CopyForDeref(source)
+                                | Rvalue::Len(source)
+                                | Rvalue::Discriminant(source) => {
+                                    dependents.contains(&source.local)
+                                }
+                                Rvalue::BinaryOp(BinOp::Rem | BinOp::BitAnd, _) => false,
+                                Rvalue::BinaryOp(_, box (lhs, rhs)) => depends(lhs) || depends(rhs),
+                                Rvalue::Use(operand)
+                                | Rvalue::Repeat(operand, _)
+                                | Rvalue::Cast(_, operand, _)
+                                | Rvalue::UnaryOp(_, operand) => depends(operand),
+                                Rvalue::Aggregate(_, operands) => operands.iter().any(depends),
+                                _ => false,
+                            };
+                            if from_dependent {
+                                new_locals.push(place.local);
+                            }
+                        }
+                    }
+
+                    if_chain! {
+                        if let ir::TerminatorKind::StaticCall {
+                            callee_did,
+                            ref args,
+                            destination: Some((place, _)),
+                            ..
+                        } = block.terminator.kind;
+                        if args.iter().any(depends);
+                        if !paths::BOUNDS_CLAMP_LIST.contains(&ext.get_def_path(callee_did));
+                        then {
+                            new_locals.push(place.local);
+                        }
+                    }
+                }
+
+                let mut changed = false;
+                for local in new_locals {
+                    changed |= dependents.insert(local);
+                }
+                if !changed {
+                    break;
+                }
+            }
+
+            dependents
+        }
+
+        /// Returns the blocks that branch or assert on a value in `dependents`,
+        /// or that reserve capacity for it (`Vec::with_capacity(len)`).
+        /// Compiler-inserted overflow and division checks are not considered.
+        fn check_blocks(&self, dependents: &FxHashSet<mir::Local>) -> Vec<mir::BasicBlock> {
+            let ext = self.rcx.tcx().ext();
+            let depends = |operand: &Operand<'tcx>| {
+                operand
+                    .place()
+                    .is_some_and(|place| dependents.contains(&place.local))
+            };
+
+            self.body
+                .original
+                .basic_blocks
+                .iter_enumerated()
+                .filter(|(block, data)| match &data.terminator().kind {
+                    TerminatorKind::SwitchInt { discr, .. } => depends(discr),
+                    TerminatorKind::Assert { cond, msg, .. } => {
+                        depends(cond)
+                            && !matches!(
+                                **msg,
+                                AssertKind::Overflow(..)
+                                    | AssertKind::OverflowNeg(..)
+                                    | AssertKind::DivisionByZero(..)
+                                    | AssertKind::RemainderByZero(..)
+                            )
+                    }
+                    _ => matches!(
+                        self.body.basic_blocks[block.index()].terminator.kind,
+                        ir::TerminatorKind::StaticCall { callee_did, ref args, .. }
+                            if args.iter().any(depends)
+                                && paths::CAPACITY_RESERVE_LIST
+                                    .contains(&ext.get_def_path(callee_did))
+                    ),
+                })
+                .map(|(block, _)| block)
+                .collect()
+        }
+    }
+
+    /// Returns true for `[u8]`, `[u8; N]` and `Vec<u8>`, behind references or a `Box`.
+    fn is_byte_buffer<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> bool {
+        match ty.kind() {
+            TyKind::Ref(_, ty, _) => is_byte_buffer(tcx, *ty),
+            TyKind::Slice(elem_ty) | TyKind::Array(elem_ty, _) => *elem_ty == tcx.types.u8,
+            TyKind::Adt(adt_def, args) if tcx.is_diagnostic_item(sym::Vec, adt_def.did()) => {
+                args.type_at(0) == tcx.types.u8
+            }
+            _ if ty.is_box() => is_byte_buffer(tcx, ty.boxed_ty().unwrap()),
+            _ => false,
+        }
+    }
+}
+
+bitflags! {
+    #[derive(Default)]
+    pub struct BehaviorFlag: u8 {
+        // Index passed to `get_unchecked(_mut)`
+        const GET_UNCHECKED = 0b00000001;
+        // Length passed to `slice::from_raw_parts(_mut)`
+        const FROM_RAW_PARTS = 0b00000010;
+        // Count passed to pointer `add`/`offset`
+        const PTR_OFFSET = 0b00000100;
+        // Length passed to `Vec::set_len`
+        const SET_LEN = 0b00001000;
+        // Bytes passed to `from_utf8_unchecked`
+        const FROM_UTF8_UNCHECKED = 0b00010000;
+    }
+}
+
+impl IntoReportLevel for BehaviorFlag {
+    fn report_level(&self) -> ReportLevel {
+        if *self == BehaviorFlag::PTR_OFFSET {
+            // The resulting pointer might not be dereferenced
+            ReportLevel::Warning
+        } else {
+            ReportLevel::Error
+        }
+    }
+}
@@ -0,0 +1,352 @@
+//! Uninitialized memory exposure detector
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::{def_id::DefId, BodyId};
+use rustc_middle::mir::{self, Operand};
+use rustc_middle::ty::{Instance, InstanceKind, TypingEnv};
+use rustc_span::Span;
+
+use snafu::{Backtrace, Snafu};
+use termcolor::Color;
+
+use crate::graph::{GraphTaint, TaintAnalyzer};
+use crate::prelude::*;
+use crate::{
+    analysis::{unsafe_dataflow::vec_set_len_to_0, AnalysisKind, IntoReportLevel},
+    ir,
+    paths::{self, *},
+    report::{Report, ReportLevel},
+    utils,
+    visitor::ContainsUnsafe,
+};
+
+#[derive(Debug, Snafu)]
+pub enum UninitExposureError {
+    ResolveError { backtrace: Backtrace },
+    InvalidSpan { backtrace: Backtrace },
+}
+
+impl AnalysisError for UninitExposureError {
+    fn kind(&self) -> AnalysisErrorKind {
+        use UninitExposureError::*;
+        match self {
+            ResolveError { .. } => AnalysisErrorKind::OutOfScope,
+            InvalidSpan { .. } => AnalysisErrorKind::Unreachable,
+        }
+    }
+}
+
+pub struct UninitExposureChecker<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+}
+
+impl<'tcx> UninitExposureChecker<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+        UninitExposureChecker { rcx }
+    }
+
+    pub fn analyze(self) {
+        let tcx = self.rcx.tcx();
+        let hir_map = tcx.hir();
+
+        // Iterates all (type, related function) pairs
+        for (_ty_hir_id, (body_id, related_item_span)) in self.rcx.types_with_related_items() {
+            if let Some(status) = inner::UninitExposureBodyAnalyzer::analyze_body(self.rcx, body_id)
+            {
+                let behavior_flag = status.behavior_flag();
+                if !behavior_flag.is_empty()
+                    && behavior_flag.report_level() >= self.rcx.report_level()
+                {
+                    let mut color_span = unwrap_or!(
+                        utils::ColorSpan::new(tcx, related_item_span).context(InvalidSpan) => continue
+                    );
+
+                    for &span in status.uninit_source_spans() {
+                        color_span.add_sub_span(Color::Red, span);
+                    }
+
+                    for &span in status.exposure_spans() {
+                        color_span.add_sub_span(Color::Cyan, span);
+                    }
+
+                    rudra_report(Report::with_color_span(
+                        tcx,
+                        behavior_flag.report_level(),
+                        AnalysisKind::UninitExposure(behavior_flag),
+                        format!(
+                            "Potential uninitialized memory exposure in `{}`",
+                            tcx.def_path_str(hir_map.body_owner_def_id(body_id).to_def_id())
+                        ),
+                        &color_span,
+                    ))
+                }
+            }
+        }
+    }
+}
+
+mod inner {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct UninitExposureStatus {
+        uninit_sources: Vec<Span>,
+        exposures: Vec<Span>,
+        behavior_flag: BehaviorFlag,
+    }
+
+    impl UninitExposureStatus {
+        pub fn behavior_flag(&self) -> BehaviorFlag {
+            self.behavior_flag
+        }
+
+        pub fn uninit_source_spans(&self) -> &Vec<Span> {
+            &self.uninit_sources
+        }
+
+        pub fn exposure_spans(&self) -> &Vec<Span> {
+            &self.exposures
+        }
+    }
+
+    /// A buffer that becomes (partially) uninitialized at the terminator of `block`.
+    struct UninitSource {
+        block: usize,
+        root: mir::Local,
+        flag: BehaviorFlag,
+    }
+
+    pub struct UninitExposureBodyAnalyzer<'a, 'tcx> {
+        rcx: RudraCtxt<'tcx>,
+        body: &'a ir::Body<'tcx>,
+        typing_env: TypingEnv<'tcx>,
+        status: UninitExposureStatus,
+    }
+
+    impl<'a, 'tcx> UninitExposureBodyAnalyzer<'a, 'tcx> {
+        fn new(rcx: RudraCtxt<'tcx>, body: &'a ir::Body<'tcx>) -> Self {
+            UninitExposureBodyAnalyzer {
+                rcx,
+                body,
+                typing_env: body.original.typing_env(rcx.tcx()),
+                status: Default::default(),
+            }
+        }
+
+        pub fn analyze_body(rcx: RudraCtxt<'tcx>, body_id: BodyId) -> Option<UninitExposureStatus> {
+            let hir_map = rcx.tcx().hir();
+            let body_did = hir_map.body_owner_def_id(body_id).to_def_id();
+
+            if ContainsUnsafe::contains_unsafe(rcx.tcx(), body_id) {
+                match rcx.translate_body(body_did).as_ref() {
+                    Err(e) => {
+                        // MIR is not available for def - log it and continue
+                        e.log();
+                        None
+                    }
+                    Ok(body) => {
+                        let body_analyzer = UninitExposureBodyAnalyzer::new(rcx, body);
+                        Some(body_analyzer.analyze())
+                    }
+                }
+            } else {
+                // Uninitialized buffers can only be created in unsafe code
+                Some(Default::default())
+            }
+        }
+
+        fn analyze(mut self) -> UninitExposureStatus {
+            let uninit_sources = self.collect_uninit_sources();
+
+            for source in uninit_sources.iter() {
+                let aliases = self.body.derived_locals(source.root);
+                let mut taint_analyzer = TaintAnalyzer::new(self.body);
+                taint_analyzer.mark_source(source.block, &source.flag);
+
+                let mut exposures = Vec::new();
+                for (id, terminator) in self.body.terminators().enumerate() {
+                    if let ir::TerminatorKind::StaticCall {
+                        callee_did,
+                        callee_substs,
+                        ref args,
+                        ..
+                    } = terminator.kind
+                    {
+                        if !self.passes_alias_by_ref(args, &aliases) {
+                            continue;
+                        }
+
+                        let ext = self.rcx.tcx().ext();
+                        if paths::BUFFER_INIT_LIST.contains(&ext.get_def_path(callee_did)) {
+                            // The buffer is overwritten here
+                            taint_analyzer.mark_barrier(id);
+                        } else if self.is_unresolvable_call(callee_did, callee_substs) {
+                            taint_analyzer.mark_sink(id);
+                            exposures.push(terminator.original.source_info.span);
+                        }
+                    }
+                }
+
+                let flag = taint_analyzer.propagate();
+                if !flag.is_empty() {
+                    self.status.behavior_flag |= flag;
+                    self.status.uninit_sources.push(
+                        self.body.basic_blocks[source.block]
+                            .terminator
+                            .original
+                            .source_info
+                            .span,
+                    );
+                    self.status.exposures.extend(exposures);
+                }
+            }
+
+            self.status
+        }
+
+        fn collect_uninit_sources(&self) -> Vec<UninitSource> {
+            let tcx = self.rcx.tcx();
+            let ext = tcx.ext();
+
+            // `MaybeUninit`s created with `MaybeUninit::uninit()`
+            let mut uninit_values = FxHashSet::default();
+            for terminator in self.body.terminators() {
+                if_chain! {
+                    if let ir::TerminatorKind::StaticCall {
+                        callee_did,
+                        destination: Some((place, _)),
+                        ..
+                    } = terminator.kind;
+                    if ext.match_def_path(callee_did, &MAYBE_UNINIT_UNINIT);
+                    then {
+                        uninit_values.extend(self.body.derived_locals(place.local));
+                    }
+                }
+            }
+
+            let mut sources = Vec::new();
+            for (id, terminator) in self.body.terminators().enumerate() {
+                if let ir::TerminatorKind::StaticCall {
+                    callee_did,
+                    ref args,
+                    destination,
+                    ..
+                } = terminator.kind
+                {
+                    let symbol_vec = ext.get_def_path(callee_did);
+                    let flag = match UNINIT_SOURCE_MAP.get(&symbol_vec) {
+                        Some(flag) => *flag,
+                        None => continue,
+                    };
+
+                    let root = if flag == BehaviorFlag::VEC_SET_LEN {
+                        if vec_set_len_to_0(self.rcx, self.typing_env, callee_did, args) {
+                            // Leaking data is safe (`vec.set_len(0);`)
+                            continue;
+                        }
+                        match args.first().and_then(|arg| arg.place()) {
+                            Some(place) => self.body.borrowed_root(place.local),
+                            None => continue,
+                        }
+                    } else {
+                        if flag == BehaviorFlag::ASSUME_INIT
+                            && !args
+                                .first()
+                                .and_then(|arg| arg.place())
+                                .is_some_and(|place| uninit_values.contains(&place.local))
+                        {
+                            // `MaybeUninit` may have been initialized (e.g. `MaybeUninit::zeroed()`)
+                            continue;
+                        }
+                        match destination {
+                            Some((place, _)) => place.local,
+                            None => continue,
+                        }
+                    };
+
+                    sources.push(UninitSource {
+                        block: id,
+                        root,
+                        flag,
+                    });
+                }
+            }
+
+            sources
+        }
+
+        /// Returns true if any of `args` is a reference or a raw pointer derived from the buffer.
+        fn passes_alias_by_ref(
+            &self,
+            args: &Vec<Operand<'tcx>>,
+            aliases: &FxHashSet<mir::Local>,
+        ) -> bool {
+            let tcx = self.rcx.tcx();
+            args.iter().any(|arg| {
+                if let Some(place) = arg.place() {
+                    let arg_ty = place.ty(self.body, tcx).ty;
+                    aliases.contains(&place.local) && (arg_ty.is_ref() || arg_ty.is_raw_ptr())
+                } else {
+                    false
+                }
+            })
+        }
+
+        /// Returns true for calls to unresolvable generic functions and trait objects,
+        /// which are potentially user-provided code that can read the buffer.
+        fn is_unresolvable_call(
+            &self,
+            callee_did: DefId,
+            callee_substs: rustc_middle::ty::GenericArgsRef<'tcx>,
+        ) -> bool {
+            match Instance::try_resolve(self.rcx.tcx(), self.typing_env, callee_did, callee_substs)
+            {
+                Err(_e) => {
+                    log_err!(ResolveError);
+                    false
+                }
+                Ok(Some(instance)) => matches!(instance.def, InstanceKind::Virtual(..)),
+                Ok(None) => true,
+            }
+        }
+    }
+}
+
+// Uninit Exposure BehaviorFlag.
+// Used to associate each report with the way the buffer became uninitialized.
+bitflags! {
+    #[derive(Default)]
+    pub struct BehaviorFlag: u8 {
+        const VEC_SET_LEN = 0b00000001;
+        const ASSUME_INIT = 0b00000010;
+        const MEM_UNINITIALIZED = 0b00000100;
+        const ALLOC = 0b00001000;
+    }
+}
+
+impl IntoReportLevel for BehaviorFlag {
+    fn report_level(&self) -> ReportLevel {
+        use BehaviorFlag as Flag;
+
+        let high = Flag::VEC_SET_LEN | Flag::ASSUME_INIT | Flag::MEM_UNINITIALIZED;
+
+        if !(*self & high).is_empty() {
+            ReportLevel::Error
+        } else {
+            ReportLevel::Warning
+        }
+    }
+}
+
+impl GraphTaint for BehaviorFlag {
+    fn is_empty(&self) -> bool {
+        BehaviorFlag::is_empty(self)
+    }
+
+    fn contains(&self, taint: &Self) -> bool {
+        self.contains(*taint)
+    }
+
+    fn join(&mut self, taint: &Self) {
+        *self |= *taint;
+    }
+}
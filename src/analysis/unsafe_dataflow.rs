@@ -1,6 +1,8 @@
 use rustc_hir::{def_id::DefId, BodyId};
-use rustc_middle::mir::Operand;
-use rustc_middle::ty::{Instance, ParamEnv, TyKind};
+use rustc_middle::mir::{AggregateKind, Operand, Rvalue, StatementKind};
+use rustc_middle::ty::{
+    CoroutineArgs, CoroutineArgsExt, GenericArgsRef, Instance, ParamEnv, TyCtxt, TyKind, TypingEnv,
+};
 use rustc_span::Span;
 
 use snafu::{Backtrace, Snafu};
@@ -73,6 +75,10 @@ impl<'tcx> UnsafeDataflowChecker<'tcx> {
                         color_span.add_sub_span(Color::Cyan, span);
                     }
 
+                    for &span in status.suspension_point_spans() {
+                        color_span.add_sub_span(Color::Cyan, span);
+                    }
+
                     rudra_report(Report::with_color_span(
                         tcx,
                         behavior_flag.report_level(),
@@ -90,8 +96,6 @@ impl<'tcx> UnsafeDataflowChecker<'tcx> {
 }
 
 mod inner {
-    use rustc_middle::ty::TypingEnv;
-
     use super::*;
 
     #[derive(Debug, Default)]
@@ -99,6 +103,7 @@ mod inner {
         strong_bypasses: Vec<Span>,
         weak_bypasses: Vec<Span>,
         unresolvable_generic_functions: Vec<Span>,
+        suspension_points: Vec<Span>,
         behavior_flag: BehaviorFlag,
     }
 
@@ -118,6 +123,19 @@ mod inner {
         pub fn unresolvable_generic_function_spans(&self) -> &Vec<Span> {
             &self.unresolvable_generic_functions
         }
+
+        pub fn suspension_point_spans(&self) -> &Vec<Span> {
+            &self.suspension_points
+        }
+
+        fn merge(&mut self, other: UnsafeDataflowStatus) {
+            self.strong_bypasses.extend(other.strong_bypasses);
+            self.weak_bypasses.extend(other.weak_bypasses);
+            self.unresolvable_generic_functions
+                .extend(other.unresolvable_generic_functions);
+            self.suspension_points.extend(other.suspension_points);
+            self.behavior_flag |= other.behavior_flag;
+        }
     }
 
     pub struct UnsafeDataflowBodyAnalyzer<'a, 'tcx> {
@@ -151,18 +169,7 @@ mod inner {
                 trace_calls_in_body(rcx, body_did);
                 None
             } else if ContainsUnsafe::contains_unsafe(rcx.tcx(), body_id) {
-                match rcx.translate_body(body_did).as_ref() {
-                    Err(e) => {
-                        // MIR is not available for def - log it and continue
-                        e.log();
-                        None
-                    }
-                    Ok(body) => {
-                        let param_env = rcx.tcx().param_env(body_did);
-                        let body_analyzer = UnsafeDataflowBodyAnalyzer::new(rcx, param_env, body);
-                        Some(body_analyzer.analyze())
-                    }
-                }
+                Self::analyze_def(rcx, body_did)
             } else {
                 // We don't perform interprocedural analysis,
                 // thus safe functions are considered safe
@@ -170,8 +177,35 @@ mod inner {
             }
         }
 
+        /// Analyzes the body of `def_id`, together with the coroutine bodies of
+        /// `async` blocks (and `async fn`s) created in it.
+        fn analyze_def(rcx: RudraCtxt<'tcx>, def_id: DefId) -> Option<UnsafeDataflowStatus> {
+            match rcx.translate_body(def_id).as_ref() {
+                Err(e) => {
+                    // MIR is not available for def - log it and continue
+                    e.log();
+                    None
+                }
+                Ok(body) => {
+                    let param_env = rcx.tcx().param_env(def_id);
+                    let body_analyzer = UnsafeDataflowBodyAnalyzer::new(rcx, param_env, body);
+                    let mut status = body_analyzer.analyze();
+
+                    for coroutine_did in async_coroutines(rcx.tcx(), body) {
+                        if let Some(coroutine_status) = Self::analyze_def(rcx, coroutine_did) {
+                            status.merge(coroutine_status);
+                        }
+                    }
+
+                    Some(status)
+                }
+            }
+        }
+
         fn analyze(mut self) -> UnsafeDataflowStatus {
             let mut taint_analyzer = TaintAnalyzer::new(self.body);
+            // Dropping a future at a suspension point unwinds its state like a panic
+            let mut await_analyzer = TaintAnalyzer::new(self.body);
 
             for (id, terminator) in self.body.terminators().enumerate() {
                 match terminator.kind {
@@ -201,8 +235,9 @@ mod inner {
                                 continue;
                             }
 
-                            taint_analyzer
-                                .mark_source(id, STRONG_BYPASS_MAP.get(&symbol_vec).unwrap());
+                            let taint = STRONG_BYPASS_MAP.get(&symbol_vec).unwrap();
+                            taint_analyzer.mark_source(id, taint);
+                            await_analyzer.mark_source(id, taint);
                             self.status
                                 .strong_bypasses
                                 .push(terminator.original.source_info.span);
@@ -215,39 +250,22 @@ mod inner {
                                 continue;
                             }
 
-                            taint_analyzer
-                                .mark_source(id, WEAK_BYPASS_MAP.get(&symbol_vec).unwrap());
+                            let taint = WEAK_BYPASS_MAP.get(&symbol_vec).unwrap();
+                            taint_analyzer.mark_source(id, taint);
+                            await_analyzer.mark_source(id, taint);
                             self.status
                                 .weak_bypasses
                                 .push(terminator.original.source_info.span);
-                        } else if paths::GENERIC_FN_LIST.contains(&symbol_vec) {
+                        } else if calls_user_provided_code(
+                            self.rcx,
+                            self.typing_env,
+                            callee_did,
+                            callee_substs,
+                        ) {
                             taint_analyzer.mark_sink(id);
                             self.status
                                 .unresolvable_generic_functions
                                 .push(terminator.original.source_info.span);
-                        } else {
-                            // Check for unresolvable generic function calls
-                            match Instance::try_resolve(
-                                self.rcx.tcx(),
-                                self.typing_env,
-                                callee_did,
-                                callee_substs,
-                            ) {
-                                Err(_e) => log_err!(ResolveError),
-                                Ok(Some(_)) => {
-                                    // Calls were successfully resolved
-                                }
-                                Ok(None) => {
-                                    // Call contains unresolvable generic parts
-                                    // Here, we are making a two step approximation:
-                                    // 1. Unresolvable generic code is potentially user-provided
-                                    // 2. User-provided code potentially panics
-                                    taint_analyzer.mark_sink(id);
-                                    self.status
-                                        .unresolvable_generic_functions
-                                        .push(terminator.original.source_info.span);
-                                }
-                            }
                         }
                     }
                     _ => (),
@@ -255,6 +273,20 @@ mod inner {
             }
 
             self.status.behavior_flag = taint_analyzer.propagate();
+
+            let suspension_points = suspension_points(self.rcx.tcx(), self.body);
+            if !suspension_points.is_empty() {
+                for &(id, span) in suspension_points.iter() {
+                    await_analyzer.mark_sink(id);
+                    self.status.suspension_points.push(span);
+                }
+
+                let await_flag = await_analyzer.propagate();
+                if !await_flag.is_empty() {
+                    self.status.behavior_flag |= await_flag | BehaviorFlag::AWAIT_FLOW;
+                }
+            }
+
             self.status
         }
 
@@ -287,6 +319,48 @@ mod inner {
         }
     }
 
+    /// Returns `async` coroutines constructed in `body`.
+    fn async_coroutines<'tcx>(tcx: TyCtxt<'tcx>, body: &ir::Body<'tcx>) -> Vec<DefId> {
+        body.statements()
+            .filter_map(|statement| match &statement.kind {
+                StatementKind::Assign(box (
+                    _,
+                    Rvalue::Aggregate(box AggregateKind::Coroutine(coroutine_did, _), _),
+                )) if tcx.coroutine_is_async(*coroutine_did) => Some(*coroutine_did),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the blocks (and `.await` spans) where an `async` coroutine body suspends.
+    /// The MIR is already transformed into a state machine, so a suspension point is
+    /// a block that stores a suspended state into the coroutine and returns.
+    fn suspension_points<'tcx>(tcx: TyCtxt<'tcx>, body: &ir::Body<'tcx>) -> Vec<(usize, Span)> {
+        if !tcx.coroutine_is_async(body.original.source.def_id()) {
+            return Vec::new();
+        }
+
+        body.basic_blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, block)| matches!(block.terminator.kind, ir::TerminatorKind::Return))
+            .filter_map(|(id, block)| {
+                block
+                    .statements
+                    .iter()
+                    .find_map(|statement| match statement.kind {
+                        StatementKind::SetDiscriminant { variant_index, .. }
+                            if variant_index.as_usize()
+                                >= CoroutineArgs::<TyCtxt<'tcx>>::RESERVED_VARIANTS =>
+                        {
+                            Some((id, statement.source_info.span))
+                        }
+                        _ => None,
+                    })
+            })
+            .collect()
+    }
+
     fn trace_calls_in_body<'tcx>(rcx: RudraCtxt<'tcx>, body_def_id: DefId) {
         warn!("Paths discovery function has been detected");
         if let Ok(body) = rcx.translate_body(body_def_id).as_ref() {
@@ -306,31 +380,57 @@ mod inner {
             }
         }
     }
+}
+
+/// Returns true if the call contains unresolvable generic parts (or drops a generic value).
+/// Here, we are making a two step approximation:
+/// 1. Unresolvable generic code is potentially user-provided
+/// 2. User-provided code potentially panics
+pub(crate) fn calls_user_provided_code<'tcx>(
+    rcx: RudraCtxt<'tcx>,
+    typing_env: TypingEnv<'tcx>,
+    callee_did: DefId,
+    callee_substs: GenericArgsRef<'tcx>,
+) -> bool {
+    let tcx = rcx.tcx();
+    if paths::GENERIC_FN_LIST.contains(&tcx.ext().get_def_path(callee_did)) {
+        return true;
+    }
+
+    match Instance::try_resolve(tcx, typing_env, callee_did, callee_substs) {
+        Err(_e) => {
+            log_err!(ResolveError);
+            false
+        }
+        // Calls were successfully resolved
+        Ok(Some(_)) => false,
+        Ok(None) => true,
+    }
+}
 
-    // Check if the argument of `Vec::set_len()` is 0_usize.
-    fn vec_set_len_to_0<'tcx>(
-        rcx: RudraCtxt<'tcx>,
-        typing_env: TypingEnv<'tcx>,
-        _callee_did: DefId,
-        args: &Vec<Operand<'tcx>>,
-    ) -> bool {
-        let tcx = rcx.tcx();
-        for arg in args.iter() {
-            if_chain! {
-                if let Operand::Constant(c) = arg;
-                if let Some(c_val) = c.const_.try_eval_target_usize(
-                    tcx,
-                    typing_env,
-                );
-                if c_val == 0;
-                then {
-                    // Leaking(`vec.set_len(0);`) is safe.
-                    return true;
-                }
+// Check if the argument of `Vec::set_len()` is 0_usize.
+pub(crate) fn vec_set_len_to_0<'tcx>(
+    rcx: RudraCtxt<'tcx>,
+    typing_env: TypingEnv<'tcx>,
+    _callee_did: DefId,
+    args: &Vec<Operand<'tcx>>,
+) -> bool {
+    let tcx = rcx.tcx();
+    for arg in args.iter() {
+        if_chain! {
+            if let Operand::Constant(c) = arg;
+            if let Some(c_val) = c.const_.try_eval_target_usize(
+                tcx,
+                typing_env,
+            );
+            if c_val == 0;
+            then {
+                // Leaking(`vec.set_len(0);`) is safe.
+                return true;
             }
         }
-        false
     }
+    false
 }
 
 // Unsafe Dataflow BypassKind.
@@ -347,6 +447,8 @@ bitflags! {
         const SLICE_UNCHECKED = 0b01000000;
         const SLICE_FROM_RAW = 0b10000000;
         const VEC_SET_LEN = 0b100000000;
+        // The bypass reaches an `.await` point, where the future may be dropped
+        const AWAIT_FLOW = 0b1000000000;
     }
 }
 
@@ -0,0 +1,269 @@
+//! Zero-initialization validity checker based on type layouts
+use rustc_hir::BodyId;
+use rustc_middle::mir::{CastKind, Operand, Rvalue, StatementKind};
+use rustc_middle::ty::layout::ValidityRequirement;
+use rustc_middle::ty::{Ty, TyKind, TypeVisitableExt, TypingEnv};
+use rustc_span::Span;
+
+use snafu::{Backtrace, Snafu};
+use termcolor::Color;
+
+use crate::prelude::*;
+use crate::{
+    analysis::{AnalysisKind, IntoReportLevel},
+    ir,
+    paths::*,
+    report::{Report, ReportLevel},
+    utils,
+    visitor::ContainsUnsafe,
+};
+
+#[derive(Debug, Snafu)]
+pub enum ZeroedValidityError {
+    InvalidSpan { backtrace: Backtrace },
+}
+
+impl AnalysisError for ZeroedValidityError {
+    fn kind(&self) -> AnalysisErrorKind {
+        use ZeroedValidityError::*;
+        match self {
+            InvalidSpan { .. } => AnalysisErrorKind::Unreachable,
+        }
+    }
+}
+
+pub struct ZeroedValidityChecker<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+}
+
+impl<'tcx> ZeroedValidityChecker<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+        ZeroedValidityChecker { rcx }
+    }
+
+    pub fn analyze(self) {
+        let tcx = self.rcx.tcx();
+        let hir_map = tcx.hir();
+
+        // Iterates all (type, related function) pairs
+        for (_ty_hir_id, (body_id, related_item_span)) in self.rcx.types_with_related_items() {
+            if let Some(status) = inner::ZeroedValidityBodyAnalyzer::analyze_body(self.rcx, body_id)
+            {
+                let behavior_flag = status.behavior_flag();
+                if !behavior_flag.is_empty()
+                    && behavior_flag.report_level() >= self.rcx.report_level()
+                {
+                    let mut color_span = unwrap_or!(
+                        utils::ColorSpan::new(tcx, related_item_span).context(InvalidSpan) => continue
+                    );
+
+                    for &span in status.invalid_zero_spans() {
+                        color_span.add_sub_span(Color::Red, span);
+                    }
+
+                    for &span in status.generic_zero_spans() {
+                        color_span.add_sub_span(Color::Yellow, span);
+                    }
+
+                    let body_path =
+                        tcx.def_path_str(hir_map.body_owner_def_id(body_id).to_def_id());
+                    let message = if behavior_flag.contains(BehaviorFlag::INVALID_ZERO) {
+                        format!(
+                            "All-zero bit pattern is invalid for the type in `{}`",
+                            body_path
+                        )
+                    } else {
+                        format!(
+                            "All-zero bit pattern may be invalid for user types in `{}`",
+                            body_path
+                        )
+                    };
+
+                    rudra_report(Report::with_color_span(
+                        tcx,
+                        behavior_flag.report_level(),
+                        AnalysisKind::ZeroedValidity(behavior_flag),
+                        message,
+                        &color_span,
+                    ))
+                }
+            }
+        }
+    }
+}
+
+mod inner {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct ZeroedValidityStatus {
+        invalid_zeros: Vec<Span>,
+        generic_zeros: Vec<Span>,
+        behavior_flag: BehaviorFlag,
+    }
+
+    impl ZeroedValidityStatus {
+        pub fn behavior_flag(&self) -> BehaviorFlag {
+            self.behavior_flag
+        }
+
+        pub fn invalid_zero_spans(&self) -> &Vec<Span> {
+            &self.invalid_zeros
+        }
+
+        pub fn generic_zero_spans(&self) -> &Vec<Span> {
+            &self.generic_zeros
+        }
+    }
+
+    pub struct ZeroedValidityBodyAnalyzer<'a, 'tcx> {
+        rcx: RudraCtxt<'tcx>,
+        body: &'a ir::Body<'tcx>,
+        typing_env: TypingEnv<'tcx>,
+        status: ZeroedValidityStatus,
+    }
+
+    impl<'a, 'tcx> ZeroedValidityBodyAnalyzer<'a, 'tcx> {
+        fn new(rcx: RudraCtxt<'tcx>, body: &'a ir::Body<'tcx>) -> Self {
+            ZeroedValidityBodyAnalyzer {
+                rcx,
+                body,
+                typing_env: body.original.typing_env(rcx.tcx()),
+                status: Default::default(),
+            }
+        }
+
+        pub fn analyze_body(rcx: RudraCtxt<'tcx>, body_id: BodyId) -> Option<ZeroedValidityStatus> {
+            let hir_map = rcx.tcx().hir();
+            let body_did = hir_map.body_owner_def_id(body_id).to_def_id();
+
+            if ContainsUnsafe::contains_unsafe(rcx.tcx(), body_id) {
+                match rcx.translate_body(body_did).as_ref() {
+                    Err(e) => {
+                        // MIR is not available for def - log it and continue
+                        e.log();
+                        None
+                    }
+                    Ok(body) => {
+                        let body_analyzer = ZeroedValidityBodyAnalyzer::new(rcx, body);
+                        Some(body_analyzer.analyze())
+                    }
+                }
+            } else {
+                // Zeroed values can only be created in unsafe code
+                Some(Default::default())
+            }
+        }
+
+        fn analyze(mut self) -> ZeroedValidityStatus {
+            let ext = self.rcx.tcx().ext();
+
+            // `MaybeUninit`s and allocations filled with zeros
+            let mut zeroed_values = Vec::new();
+            let mut zeroed_allocations = Vec::new();
+            for terminator in self.body.terminators() {
+                if let ir::TerminatorKind::StaticCall {
+                    callee_did,
+                    destination: Some((place, _)),
+                    ..
+                } = terminator.kind
+                {
+                    if ext.match_def_path(callee_did, &MAYBE_UNINIT_ZEROED) {
+                        zeroed_values.extend(self.body.derived_locals(place.local));
+                    } else if ext.match_def_path(callee_did, &ALLOC_ALLOC_ZEROED) {
+                        zeroed_allocations.extend(self.body.derived_locals(place.local));
+                    }
+                }
+            }
+
+            // `mem::zeroed::<T>()` and `MaybeUninit::<T>::zeroed().assume_init()`
+            for terminator in self.body.terminators() {
+                if let ir::TerminatorKind::StaticCall {
+                    callee_did,
+                    callee_substs,
+                    ref args,
+                    ..
+                } = terminator.kind
+                {
+                    let source = if ext.match_def_path(callee_did, &MEM_ZEROED) {
+                        BehaviorFlag::MEM_ZEROED
+                    } else if ext.match_def_path(callee_did, &MAYBE_UNINIT_ASSUME_INIT)
+                        && args
+                            .first()
+                            .and_then(Operand::place)
+                            .is_some_and(|place| zeroed_values.contains(&place.local))
+                    {
+                        BehaviorFlag::ASSUME_INIT
+                    } else {
+                        continue;
+                    };
+
+                    if let Some(ty) = callee_substs.types().next() {
+                        self.check(ty, source, terminator.original.source_info.span);
+                    }
+                }
+            }
+
+            // `alloc_zeroed(layout) as *mut T`
+            for statement in self.body.statements() {
+                if_chain! {
+                    if let StatementKind::Assign(box (
+                        _,
+                        Rvalue::Cast(CastKind::PtrToPtr, operand, dst_ty),
+                    )) = &statement.kind;
+                    if let Some(place) = operand.place();
+                    if zeroed_allocations.contains(&place.local);
+                    if let TyKind::RawPtr(pointee_ty, _) = dst_ty.kind();
+                    then {
+                        self.check(*pointee_ty, BehaviorFlag::ALLOC_ZEROED, statement.source_info.span);
+                    }
+                }
+            }
+
+            self.status
+        }
+
+        fn check(&mut self, ty: Ty<'tcx>, source: BehaviorFlag, span: Span) {
+            let tcx = self.rcx.tcx();
+            match tcx.check_validity_requirement((
+                ValidityRequirement::Zero,
+                self.typing_env.as_query_input(ty),
+            )) {
+                Ok(true) => (),
+                Ok(false) => {
+                    self.status.behavior_flag |= source | BehaviorFlag::INVALID_ZERO;
+                    self.status.invalid_zeros.push(span);
+                }
+                Err(_) if ty.has_param() => {
+                    // The layout depends on a type parameter chosen by the user
+                    self.status.behavior_flag |= source | BehaviorFlag::GENERIC_ZERO;
+                    self.status.generic_zeros.push(span);
+                }
+                Err(_) => (),
+            }
+        }
+    }
+}
+
+bitflags! {
+    #[derive(Default)]
+    pub struct BehaviorFlag: u8 {
+        // The all-zero bit pattern is invalid for the type
+        const INVALID_ZERO = 0b00000001;
+        // The type is generic, so the all-zero bit pattern may be invalid
+        const GENERIC_ZERO = 0b00000010;
+        const MEM_ZEROED = 0b00000100;
+        const ASSUME_INIT = 0b00001000;
+        const ALLOC_ZEROED = 0b00010000;
+    }
+}
+
+impl IntoReportLevel for BehaviorFlag {
+    fn report_level(&self) -> ReportLevel {
+        if self.contains(BehaviorFlag::INVALID_ZERO) {
+            ReportLevel::Error
+        } else {
+            ReportLevel::Warning
+        }
+    }
+}
@@ -17,7 +17,10 @@ use rustc_interface::interface::Compiler;
 
 use rudra::log::Verbosity;
 use rudra::report::{default_report_logger, init_report_logger, ReportLevel};
-use rudra::{analyze, compile_time_sysroot, progress_info, RudraConfig, RUDRA_DEFAULT_ARGS};
+use rudra::{
+    analyze, compile_time_sysroot, progress_info, RudraConfig, UnsafeTraitConfig,
+    RUDRA_DEFAULT_ARGS,
+};
 use rustc_middle::ty::TyCtxt;
 use rustc_session::config::ErrorOutputType;
 use rustc_session::EarlyDiagCtxt;
@@ -46,7 +49,7 @@ impl rustc_driver::Callbacks for RudraCompilerCalls {
 
         progress_info!("Rudra started");
         debug!("Crate name: {}", tcx.crate_name(LOCAL_CRATE));
-        analyze(tcx, self.config);
+        analyze(tcx, self.config.clone());
         progress_info!("Rudra finished");
 
         compiler.sess.dcx().abort_if_errors();
@@ -88,7 +91,13 @@ fn run_compiler(
     exit_code
 }
 
-fn parse_config() -> (RudraConfig, Vec<String>) {
+/// Reports an invalid Rudra flag and exits without running the compiler
+fn invalid_flag(handler: &EarlyDiagCtxt, message: String) -> ! {
+    let _ = handler.early_err(message);
+    std::process::exit(1)
+}
+
+fn parse_config(handler: &EarlyDiagCtxt) -> (RudraConfig, Vec<String>) {
     // collect arguments
     let mut config = RudraConfig::default();
 
@@ -105,6 +114,68 @@ fn parse_config() -> (RudraConfig, Vec<String>) {
             "-Zrudra-disable-send-sync-variance" => config.send_sync_variance_enabled = false,
             "-Zrudra-enable-unsafe-dataflow" => config.unsafe_dataflow_enabled = true,
             "-Zrudra-disable-unsafe-dataflow" => config.unsafe_dataflow_enabled = false,
+            "-Zrudra-enable-uninit-exposure" => config.uninit_exposure_enabled = true,
+            "-Zrudra-disable-uninit-exposure" => config.uninit_exposure_enabled = false,
+            "-Zrudra-enable-transmute-validity" => config.transmute_validity_enabled = true,
+            "-Zrudra-disable-transmute-validity" => config.transmute_validity_enabled = false,
+            "-Zrudra-enable-lifetime-aliasing" => config.lifetime_aliasing_enabled = true,
+            "-Zrudra-disable-lifetime-aliasing" => config.lifetime_aliasing_enabled = false,
+            "-Zrudra-enable-unchecked-precondition" => config.unchecked_precondition_enabled = true,
+            "-Zrudra-disable-unchecked-precondition" => {
+                config.unchecked_precondition_enabled = false
+            }
+            "-Zrudra-enable-dangling-pointer" => config.dangling_pointer_enabled = true,
+            "-Zrudra-disable-dangling-pointer" => config.dangling_pointer_enabled = false,
+            "-Zrudra-enable-double-drop" => config.double_drop_enabled = true,
+            "-Zrudra-disable-double-drop" => config.double_drop_enabled = false,
+            "-Zrudra-enable-zeroed-validity" => config.zeroed_validity_enabled = true,
+            "-Zrudra-disable-zeroed-validity" => config.zeroed_validity_enabled = false,
+            "-Zrudra-enable-unaligned-access" => config.unaligned_access_enabled = true,
+            "-Zrudra-disable-unaligned-access" => config.unaligned_access_enabled = false,
+            "-Zrudra-enable-static-mut" => config.static_mut_enabled = true,
+            "-Zrudra-disable-static-mut" => config.static_mut_enabled = false,
+            "-Zrudra-enable-ffi-unwind" => config.ffi_unwind_enabled = true,
+            "-Zrudra-disable-ffi-unwind" => config.ffi_unwind_enabled = false,
+            "-Zrudra-enable-reentrancy" => config.reentrancy_enabled = true,
+            "-Zrudra-disable-reentrancy" => config.reentrancy_enabled = false,
+            "-Zrudra-enable-pin-soundness" => config.pin_soundness_enabled = true,
+            "-Zrudra-disable-pin-soundness" => config.pin_soundness_enabled = false,
+            "-Zrudra-enable-interior-mutability" => config.interior_mutability_enabled = true,
+            "-Zrudra-disable-interior-mutability" => config.interior_mutability_enabled = false,
+            arg if arg.starts_with("-Zrudra-unsafe-trait=") => {
+                // `-Zrudra-unsafe-trait=<trait path>[=<bound path>]`
+                let spec = &arg["-Zrudra-unsafe-trait=".len()..];
+                let (trait_path, bound_path) = spec.split_once('=').unwrap_or((spec, spec));
+                config.unsafe_traits.push(UnsafeTraitConfig {
+                    trait_path: trait_path.to_owned(),
+                    bound_path: bound_path.to_owned(),
+                });
+            }
+            arg if arg.starts_with("-Zrudra-api-search-depth=") => {
+                let depth = &arg["-Zrudra-api-search-depth=".len()..];
+                config.api_search_depth = match depth.parse() {
+                    Ok(depth) if depth > 0 => depth,
+                    _ => invalid_flag(
+                        handler,
+                        format!(
+                            "`-Zrudra-api-search-depth` takes a positive integer, got `{}`",
+                            depth
+                        ),
+                    ),
+                };
+            }
+            arg if arg.starts_with("--send-sync-mode=") => {
+                config.send_sync_mode = match arg["--send-sync-mode=".len()..].parse() {
+                    Ok(mode) => mode,
+                    Err(e) => invalid_flag(
+                        handler,
+                        format!(
+                            "{}; `--send-sync-mode` takes `strict`, `relaxed` or `both`",
+                            e
+                        ),
+                    ),
+                };
+            }
             "-v" => config.verbosity = Verbosity::Verbose,
             "-vv" => config.verbosity = Verbosity::Trace,
             "-Zsensitivity-high" => config.report_level = ReportLevel::Error,
@@ -127,7 +198,7 @@ fn main() {
     let exit_code = {
         // initialize the report logger
         // `logger_handle` must be nested because it flushes the logs when it goes out of the scope
-        let (config, mut rustc_args) = parse_config();
+        let (config, mut rustc_args) = parse_config(&handler);
         let _logger_handle = init_report_logger(default_report_logger());
 
         // init rustc logger
@@ -33,6 +33,7 @@ pub struct TaintAnalyzer<'a, G: Graph, T: GraphTaint> {
     len: usize,
     sources: Vec<T>,
     sinks: Vec<bool>,
+    barriers: Vec<bool>,
 }
 
 impl<'a, G: Graph, T: GraphTaint> TaintAnalyzer<'a, G, T> {
@@ -43,6 +44,7 @@ impl<'a, G: Graph, T: GraphTaint> TaintAnalyzer<'a, G, T> {
             len: graph_len,
             sources: vec![T::default(); graph_len],
             sinks: vec![false; graph_len],
+            barriers: vec![false; graph_len],
         }
     }
 
@@ -66,10 +68,20 @@ impl<'a, G: Graph, T: GraphTaint> TaintAnalyzer<'a, G, T> {
         self.sinks[id] = false;
     }
 
-    // Unmark all sources and sinks
+    // Taint reaches a barrier node, but does not flow out of it.
+    pub fn mark_barrier(&mut self, id: usize) {
+        self.barriers[id] = true;
+    }
+
+    pub fn unmark_barrier(&mut self, id: usize) {
+        self.barriers[id] = false;
+    }
+
+    // Unmark all sources, sinks and barriers
     pub fn clear(&mut self) {
         self.sources = vec![T::default(); self.len];
         self.sinks = vec![false; self.len];
+        self.barriers = vec![false; self.len];
     }
 
     // Checks reachability between `self.sources` & `self.sinks`.
@@ -87,6 +99,9 @@ impl<'a, G: Graph, T: GraphTaint> TaintAnalyzer<'a, G, T> {
 
         // Breadth-first propagation
         while let Some(current) = work_list.pop_front() {
+            if self.barriers[current] {
+                continue;
+            }
             for next in self.graph.next(current) {
                 let mut next_state = std::mem::take(&mut taint_state[next]);
                 let taint = &taint_state[current];
@@ -3,11 +3,12 @@
 
 use std::borrow::Cow;
 
+use rustc_data_structures::fx::FxHashSet;
 use rustc_hir::def_id::DefId;
 use rustc_index::{IndexSlice, IndexVec};
 use rustc_middle::{
     mir,
-    ty::{GenericArgsRef, Ty},
+    ty::{GenericArgKind, GenericArgsRef, Ty},
 };
 
 #[derive(Debug)]
@@ -66,4 +67,156 @@ impl<'tcx> Body<'tcx> {
     pub fn terminators(&self) -> impl Iterator<Item = &Terminator<'tcx>> {
         self.basic_blocks.iter().map(|block| &block.terminator)
     }
+
+    pub fn statements(&self) -> impl Iterator<Item = &mir::Statement<'tcx>> {
+        self.basic_blocks
+            .iter()
+            .flat_map(|block| block.statements.iter())
+    }
+
+    /// Follows `_a = &_b`, `_a = &raw _b` and `_a = copy _b` chains backwards
+    /// starting from `local`. The first element is `local` itself, and the last element
+    /// is the local that `local` ultimately points to.
+    pub fn borrow_chain(&self, local: mir::Local) -> Vec<mir::Local> {
+        let mut chain = vec![local];
+        let mut visited = FxHashSet::default();
+        let mut current = local;
+        while visited.insert(current) {
+            let source = self
+                .statements()
+                .find_map(|statement| match &statement.kind {
+                    mir::StatementKind::Assign(box (
+                        place,
+                        mir::Rvalue::Ref(_, _, source)
+                        | mir::Rvalue::RawPtr(_, source)
+                        | mir::Rvalue::CopyForDeref(source)
+                        | mir::Rvalue::Use(mir::Operand::Copy(source) | mir::Operand::Move(source))
+                        | mir::Rvalue::Cast(
+                            _,
+                            mir::Operand::Copy(source) | mir::Operand::Move(source),
+                            _,
+                        ),
+                    )) if place.as_local() == Some(current) => Some(source.local),
+                    _ => None,
+                });
+            match source {
+                Some(source) if !visited.contains(&source) => {
+                    chain.push(source);
+                    current = source;
+                }
+                _ => break,
+            }
+        }
+        chain
+    }
+
+    /// Returns the local that `local` ultimately points to (or `local` itself).
+    /// See [`Body::borrow_chain`].
+    pub fn borrowed_root(&self, local: mir::Local) -> mir::Local {
+        *self.borrow_chain(local).last().unwrap()
+    }
+
+    /// Blocks reachable from the terminator of `start` on normal (non-cleanup) paths.
+    /// Blocks in `barriers` are included, but the search does not continue past them.
+    pub fn reachable_blocks(&self, start: usize, barriers: &FxHashSet<usize>) -> FxHashSet<usize> {
+        let mut reachable = FxHashSet::default();
+        let mut worklist = self.successors(start);
+
+        while let Some(block) = worklist.pop() {
+            if self.basic_blocks[block].is_cleanup || !reachable.insert(block) {
+                continue;
+            }
+            if !barriers.contains(&block) {
+                worklist.extend(self.successors(block));
+            }
+        }
+
+        reachable
+    }
+
+    fn successors(&self, block: usize) -> Vec<usize> {
+        self.basic_blocks[block]
+            .terminator
+            .original
+            .successors()
+            .map(|successor| successor.index())
+            .collect()
+    }
+
+    /// Returns the location of the terminator of `block`.
+    pub fn terminator_location(&self, block: usize) -> mir::Location {
+        mir::Location {
+            block: block.into(),
+            statement_index: self.basic_blocks[block].statements.len(),
+        }
+    }
+
+    /// Returns the set of locals that may hold a pointer into (or a copy of) `root`.
+    /// This is a flow-insensitive approximation: a local is derived from `root` if it is
+    /// assigned from a derived local through a borrow, a copy, a cast, or an aggregate,
+    /// or if it is the pointer-typed return value of a call that takes a derived local.
+    pub fn derived_locals(&self, root: mir::Local) -> FxHashSet<mir::Local> {
+        let mut derived = FxHashSet::default();
+        derived.insert(root);
+
+        loop {
+            let mut changed = false;
+            let is_derived = |operand: &mir::Operand<'tcx>| {
+                operand
+                    .place()
+                    .is_some_and(|place| derived.contains(&place.local))
+            };
+
+            let mut new_locals = Vec::new();
+            for block in self.basic_blocks.iter() {
+                for statement in block.statements.iter() {
+                    if let mir::StatementKind::Assign(box (place, rvalue)) = &statement.kind {
+                        let from_derived = match rvalue {
+                            mir::Rvalue::Ref(_, _, source)
+                            | mir::Rvalue::RawPtr(_, source)
+                            | mir::Rvalue::CopyForDeref(source) => derived.contains(&source.local),
+                            mir::Rvalue::Use(operand)
+                            | mir::Rvalue::Repeat(operand, _)
+                            | mir::Rvalue::Cast(_, operand, _)
+                            | mir::Rvalue::BinaryOp(mir::BinOp::Offset, box (operand, _)) => {
+                                is_derived(operand)
+                            }
+                            mir::Rvalue::Aggregate(_, operands) => operands.iter().any(is_derived),
+                            _ => false,
+                        };
+                        if from_derived {
+                            new_locals.push(place.local);
+                        }
+                    }
+                }
+
+                if let TerminatorKind::StaticCall {
+                    ref args,
+                    destination: Some((place, _)),
+                    ..
+                } = block.terminator.kind
+                {
+                    if args.iter().any(is_derived)
+                        && self.local_decls[place.local.index()].ty.walk().any(|arg| {
+                            matches!(
+                                arg.unpack(),
+                                GenericArgKind::Type(ty) if ty.is_ref() || ty.is_raw_ptr()
+                            )
+                        })
+                    {
+                        new_locals.push(place.local);
+                    }
+                }
+            }
+
+            for local in new_locals {
+                changed |= derived.insert(local);
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        derived
+    }
 }
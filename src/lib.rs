@@ -3,6 +3,7 @@
 #![feature(try_blocks)]
 #![feature(never_type)]
 
+extern crate rustc_abi;
 extern crate rustc_data_structures;
 extern crate rustc_driver;
 extern crate rustc_errors;
@@ -13,6 +14,7 @@ extern crate rustc_infer;
 extern crate rustc_interface;
 extern crate rustc_middle;
 extern crate rustc_span;
+extern crate rustc_trait_selection;
 
 #[macro_use]
 extern crate bitflags;
@@ -39,7 +41,13 @@ pub mod visitor;
 
 use rustc_middle::ty::TyCtxt;
 
-use crate::analysis::{SendSyncVarianceChecker, UnsafeDataflowChecker, UnsafeDestructorChecker};
+use crate::analysis::{
+    DanglingPointerChecker, DoubleDropChecker, FfiUnwindChecker, InteriorMutabilityChecker,
+    LifetimeAliasingChecker, PinSoundnessChecker, ReentrancyChecker, SendSyncVarianceChecker,
+    StaticMutChecker, TransmuteValidityChecker, UnalignedAccessChecker,
+    UncheckedPreconditionChecker, UninitExposureChecker, UnsafeDataflowChecker,
+    UnsafeDestructorChecker, ZeroedValidityChecker,
+};
 use crate::context::RudraCtxtOwner;
 use crate::log::Verbosity;
 use crate::report::ReportLevel;
@@ -49,13 +57,63 @@ use crate::report::ReportLevel;
 pub static RUDRA_DEFAULT_ARGS: &[&str] =
     &["-Zalways-encode-mir", "-Zmir-opt-level=0", "--cfg=rudra"];
 
-#[derive(Debug, Clone, Copy)]
+/// A user-declared `unsafe trait` that SendSyncVariance checks like `Send`.
+#[derive(Debug, Clone)]
+pub struct UnsafeTraitConfig {
+    /// Def path of the trait, e.g. `bytemuck::Pod`
+    pub trait_path: String,
+    /// Def path of the trait that each generic parameter must implement
+    pub bound_path: String,
+}
+
+/// Rules that SendSyncVariance applies to `Send`/`Sync` impls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendSyncMode {
+    /// Check the bound of each generic parameter against its usage in the ADT
+    Strict,
+    /// Report only impls that bound none of their generic parameters
+    Relaxed,
+    /// Run both modes and merge their reports per ADT
+    Both,
+}
+
+impl std::str::FromStr for SendSyncMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(SendSyncMode::Strict),
+            "relaxed" => Ok(SendSyncMode::Relaxed),
+            "both" => Ok(SendSyncMode::Both),
+            _ => Err(format!("unknown Send/Sync mode `{}`", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct RudraConfig {
     pub verbosity: Verbosity,
     pub report_level: ReportLevel,
     pub unsafe_destructor_enabled: bool,
     pub send_sync_variance_enabled: bool,
     pub unsafe_dataflow_enabled: bool,
+    pub uninit_exposure_enabled: bool,
+    pub transmute_validity_enabled: bool,
+    pub lifetime_aliasing_enabled: bool,
+    pub unchecked_precondition_enabled: bool,
+    pub dangling_pointer_enabled: bool,
+    pub double_drop_enabled: bool,
+    pub zeroed_validity_enabled: bool,
+    pub unaligned_access_enabled: bool,
+    pub static_mut_enabled: bool,
+    pub ffi_unwind_enabled: bool,
+    pub reentrancy_enabled: bool,
+    pub pin_soundness_enabled: bool,
+    pub interior_mutability_enabled: bool,
+    pub unsafe_traits: Vec<UnsafeTraitConfig>,
+    /// Maximum length of the method chain SendSyncVariance follows to find exposed `&T`
+    pub api_search_depth: usize,
+    pub send_sync_mode: SendSyncMode,
 }
 
 impl Default for RudraConfig {
@@ -66,6 +124,22 @@ impl Default for RudraConfig {
             unsafe_destructor_enabled: false,
             send_sync_variance_enabled: true,
             unsafe_dataflow_enabled: true,
+            uninit_exposure_enabled: true,
+            transmute_validity_enabled: true,
+            lifetime_aliasing_enabled: true,
+            unchecked_precondition_enabled: true,
+            dangling_pointer_enabled: true,
+            double_drop_enabled: true,
+            zeroed_validity_enabled: true,
+            unaligned_access_enabled: true,
+            static_mut_enabled: true,
+            ffi_unwind_enabled: true,
+            reentrancy_enabled: true,
+            pin_soundness_enabled: true,
+            interior_mutability_enabled: true,
+            unsafe_traits: Vec::new(),
+            api_search_depth: 3,
+            send_sync_mode: SendSyncMode::Strict,
         }
     }
 }
@@ -124,7 +198,12 @@ pub fn analyze<'tcx>(tcx: TyCtxt<'tcx>, config: RudraConfig) {
     // Send/Sync variance analysis
     if config.send_sync_variance_enabled {
         run_analysis("SendSyncVariance", || {
-            let checker = SendSyncVarianceChecker::new(rcx);
+            let checker = SendSyncVarianceChecker::new(
+                rcx,
+                &config.unsafe_traits,
+                config.api_search_depth,
+                config.send_sync_mode,
+            );
             checker.analyze();
         })
     }
@@ -136,4 +215,108 @@ pub fn analyze<'tcx>(tcx: TyCtxt<'tcx>, config: RudraConfig) {
             checker.analyze();
         })
     }
+
+    // Uninitialized memory exposure analysis
+    if config.uninit_exposure_enabled {
+        run_analysis("UninitExposure", || {
+            let checker = UninitExposureChecker::new(rcx);
+            checker.analyze();
+        })
+    }
+
+    // Transmute validity analysis
+    if config.transmute_validity_enabled {
+        run_analysis("TransmuteValidity", || {
+            let checker = TransmuteValidityChecker::new(rcx);
+            checker.analyze();
+        })
+    }
+
+    // Unbounded lifetime and aliasing analysis
+    if config.lifetime_aliasing_enabled {
+        run_analysis("LifetimeAliasing", || {
+            let checker = LifetimeAliasingChecker::new(rcx);
+            checker.analyze();
+        })
+    }
+
+    // Unchecked precondition analysis
+    if config.unchecked_precondition_enabled {
+        run_analysis("UncheckedPrecondition", || {
+            let checker = UncheckedPreconditionChecker::new(rcx);
+            checker.analyze();
+        })
+    }
+
+    // Dangling pointer analysis
+    if config.dangling_pointer_enabled {
+        run_analysis("DanglingPointer", || {
+            let checker = DanglingPointerChecker::new(rcx);
+            checker.analyze();
+        })
+    }
+
+    // Double drop analysis
+    if config.double_drop_enabled {
+        run_analysis("DoubleDrop", || {
+            let checker = DoubleDropChecker::new(rcx);
+            checker.analyze();
+        })
+    }
+
+    // Zero-initialization validity analysis
+    if config.zeroed_validity_enabled {
+        run_analysis("ZeroedValidity", || {
+            let checker = ZeroedValidityChecker::new(rcx);
+            checker.analyze();
+        })
+    }
+
+    // Unaligned access analysis
+    if config.unaligned_access_enabled {
+        run_analysis("UnalignedAccess", || {
+            let checker = UnalignedAccessChecker::new(rcx);
+            checker.analyze();
+        })
+    }
+
+    // `static mut` data race analysis
+    if config.static_mut_enabled {
+        run_analysis("StaticMut", || {
+            let checker = StaticMutChecker::new(rcx);
+            checker.analyze();
+        })
+    }
+
+    // FFI unwinding analysis
+    if config.ffi_unwind_enabled {
+        run_analysis("FfiUnwind", || {
+            let checker = FfiUnwindChecker::new(rcx);
+            checker.analyze();
+        })
+    }
+
+    // Reentrancy analysis
+    if config.reentrancy_enabled {
+        run_analysis("Reentrancy", || {
+            let checker = ReentrancyChecker::new(rcx);
+            checker.analyze();
+        })
+    }
+
+    // Pin soundness analysis
+    if config.pin_soundness_enabled {
+        run_analysis("PinSoundness", || {
+            let checker = PinSoundnessChecker::new(rcx);
+            checker.analyze();
+        })
+    }
+
+    // Interior mutability analysis
+    if config.interior_mutability_enabled {
+        run_analysis("InteriorMutability", || {
+            let checker = InteriorMutabilityChecker::new(rcx);
+            checker.analyze();
+        })
+    }
 }
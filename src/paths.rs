@@ -5,7 +5,10 @@ use rustc_span::Symbol;
 use maplit::hashmap;
 use once_cell::sync::Lazy;
 
-use crate::analysis::UnsafeDataflowBehaviorFlag;
+use crate::analysis::{
+    DoubleDropBehaviorFlag, UnalignedAccessBehaviorFlag, UncheckedPreconditionBehaviorFlag,
+    UninitExposureBehaviorFlag, UnsafeDataflowBehaviorFlag,
+};
 
 /*
 How to find a path for unknown item:
@@ -29,28 +32,155 @@ pub const VEC_FROM_RAW_PARTS: [&str; 4] = ["alloc", "vec", "Vec", "from_raw_part
 
 // Weak bypasses
 pub const TRANSMUTE: [&str; 4] = ["core", "intrinsics", "", "transmute"];
+pub const MEM_TRANSMUTE_COPY: [&str; 3] = ["core", "mem", "transmute_copy"];
+// `mem::transmute` as resolved in HIR
+pub const INTRINSICS_TRANSMUTE: [&str; 3] = ["core", "intrinsics", "transmute"];
 
 pub const PTR_WRITE: [&str; 3] = ["core", "ptr", "write"];
 pub const PTR_DIRECT_WRITE: [&str; 5] = ["core", "ptr", "mut_ptr", "<impl *mut T>", "write"];
 
 pub const PTR_AS_REF: [&str; 5] = ["core", "ptr", "const_ptr", "<impl *const T>", "as_ref"];
 pub const PTR_AS_MUT: [&str; 5] = ["core", "ptr", "mut_ptr", "<impl *mut T>", "as_mut"];
-pub const NON_NULL_AS_REF: [&str; 5] = ["core", "ptr", "non_nul", "NonNull", "as_ref"];
-pub const NON_NULL_AS_MUT: [&str; 5] = ["core", "ptr", "non_nul", "NonNull", "as_mut"];
+pub const NON_NULL_AS_REF: [&str; 5] = ["core", "ptr", "non_null", "NonNull", "as_ref"];
+pub const NON_NULL_AS_MUT: [&str; 5] = ["core", "ptr", "non_null", "NonNull", "as_mut"];
 
 pub const SLICE_GET_UNCHECKED: [&str; 4] = ["core", "slice", "<impl [T]>", "get_unchecked"];
 pub const SLICE_GET_UNCHECKED_MUT: [&str; 4] = ["core", "slice", "<impl [T]>", "get_unchecked_mut"];
 
 pub const PTR_SLICE_FROM_RAW_PARTS: [&str; 3] = ["core", "ptr", "slice_from_raw_parts"];
 pub const PTR_SLICE_FROM_RAW_PARTS_MUT: [&str; 3] = ["core", "ptr", "slice_from_raw_parts_mut"];
-pub const SLICE_FROM_RAW_PARTS: [&str; 3] = ["core", "slice", "from_raw_parts"];
-pub const SLICE_FROM_RAW_PARTS_MUT: [&str; 3] = ["core", "slice", "from_raw_parts_mut"];
+pub const SLICE_FROM_RAW_PARTS: [&str; 4] = ["core", "slice", "raw", "from_raw_parts"];
+pub const SLICE_FROM_RAW_PARTS_MUT: [&str; 4] = ["core", "slice", "raw", "from_raw_parts_mut"];
 
 // Generic function call
 pub const PTR_DROP_IN_PLACE: [&str; 3] = ["core", "ptr", "drop_in_place"];
 pub const PTR_DIRECT_DROP_IN_PLACE: [&str; 5] =
     ["core", "ptr", "mut_ptr", "<impl *mut T>", "drop_in_place"];
 
+// Uninitialized memory sources
+pub const MAYBE_UNINIT_UNINIT: [&str; 5] = ["core", "mem", "maybe_uninit", "MaybeUninit", "uninit"];
+pub const MAYBE_UNINIT_ASSUME_INIT: [&str; 5] =
+    ["core", "mem", "maybe_uninit", "MaybeUninit", "assume_init"];
+pub const MEM_UNINITIALIZED: [&str; 3] = ["core", "mem", "uninitialized"];
+pub const ALLOC_ALLOC: [&str; 3] = ["alloc", "alloc", "alloc"];
+
+// Zero-initialized memory sources
+pub const MEM_ZEROED: [&str; 3] = ["core", "mem", "zeroed"];
+pub const MAYBE_UNINIT_ZEROED: [&str; 5] = ["core", "mem", "maybe_uninit", "MaybeUninit", "zeroed"];
+pub const ALLOC_ALLOC_ZEROED: [&str; 3] = ["alloc", "alloc", "alloc_zeroed"];
+
+// Buffer initializers
+pub const INTRINSICS_WRITE_BYTES: [&str; 3] = ["core", "intrinsics", "write_bytes"];
+pub const SLICE_FILL: [&str; 4] = ["core", "slice", "<impl [T]>", "fill"];
+pub const SLICE_COPY_FROM_SLICE: [&str; 4] = ["core", "slice", "<impl [T]>", "copy_from_slice"];
+
+// Ownership
+pub const MEM_DROP: [&str; 3] = ["core", "mem", "drop"];
+pub const PTR_MUT_READ: [&str; 5] = ["core", "ptr", "mut_ptr", "<impl *mut T>", "read"];
+pub const BOX_FROM_RAW: [&str; 4] = ["alloc", "boxed", "Box", "from_raw"];
+
+// Functions with preconditions on their arguments
+pub const PTR_ADD: [&str; 5] = ["core", "ptr", "const_ptr", "<impl *const T>", "add"];
+pub const PTR_OFFSET: [&str; 5] = ["core", "ptr", "const_ptr", "<impl *const T>", "offset"];
+pub const PTR_MUT_ADD: [&str; 5] = ["core", "ptr", "mut_ptr", "<impl *mut T>", "add"];
+pub const PTR_MUT_OFFSET: [&str; 5] = ["core", "ptr", "mut_ptr", "<impl *mut T>", "offset"];
+pub const STR_FROM_UTF8_UNCHECKED: [&str; 4] = ["core", "str", "converts", "from_utf8_unchecked"];
+pub const STR_FROM_UTF8_UNCHECKED_MUT: [&str; 4] =
+    ["core", "str", "converts", "from_utf8_unchecked_mut"];
+pub const STRING_FROM_UTF8_UNCHECKED: [&str; 4] =
+    ["alloc", "string", "String", "from_utf8_unchecked"];
+
+// Alignment checks
+pub const PTR_IS_ALIGNED: [&str; 5] = ["core", "ptr", "const_ptr", "<impl *const T>", "is_aligned"];
+pub const PTR_ALIGN_OFFSET: [&str; 5] = [
+    "core",
+    "ptr",
+    "const_ptr",
+    "<impl *const T>",
+    "align_offset",
+];
+pub const PTR_ADDR: [&str; 5] = ["core", "ptr", "const_ptr", "<impl *const T>", "addr"];
+pub const PTR_MUT_IS_ALIGNED: [&str; 5] = ["core", "ptr", "mut_ptr", "<impl *mut T>", "is_aligned"];
+pub const PTR_MUT_ALIGN_OFFSET: [&str; 5] =
+    ["core", "ptr", "mut_ptr", "<impl *mut T>", "align_offset"];
+pub const PTR_MUT_ADDR: [&str; 5] = ["core", "ptr", "mut_ptr", "<impl *mut T>", "addr"];
+
+// Pointers that are aligned by construction
+pub const ALLOC_REALLOC: [&str; 3] = ["alloc", "alloc", "realloc"];
+pub const GLOBAL_ALLOC_ALLOC: [&str; 5] = ["core", "alloc", "global", "GlobalAlloc", "alloc"];
+pub const GLOBAL_ALLOC_ALLOC_ZEROED: [&str; 5] =
+    ["core", "alloc", "global", "GlobalAlloc", "alloc_zeroed"];
+pub const GLOBAL_ALLOC_REALLOC: [&str; 5] = ["core", "alloc", "global", "GlobalAlloc", "realloc"];
+pub const ALLOCATOR_ALLOCATE: [&str; 4] = ["core", "alloc", "Allocator", "allocate"];
+pub const ALLOCATOR_ALLOCATE_ZEROED: [&str; 4] = ["core", "alloc", "Allocator", "allocate_zeroed"];
+pub const NON_NULL_DANGLING: [&str; 5] = ["core", "ptr", "non_null", "NonNull", "dangling"];
+pub const BOX_INTO_RAW: [&str; 4] = ["alloc", "boxed", "Box", "into_raw"];
+
+// Opaque pointee type of FFI pointers
+pub const FFI_C_VOID: [&str; 3] = ["core", "ffi", "c_void"];
+
+// Functions that bound an integer
+pub const CMP_MIN: [&str; 3] = ["core", "cmp", "min"];
+pub const CMP_ORD_MIN: [&str; 4] = ["core", "cmp", "Ord", "min"];
+pub const CMP_ORD_CLAMP: [&str; 4] = ["core", "cmp", "Ord", "clamp"];
+
+// Functions that make room for a given length
+pub const VEC_WITH_CAPACITY: [&str; 4] = ["alloc", "vec", "Vec", "with_capacity"];
+pub const VEC_RESERVE: [&str; 4] = ["alloc", "vec", "Vec", "reserve"];
+pub const VEC_RESERVE_EXACT: [&str; 4] = ["alloc", "vec", "Vec", "reserve_exact"];
+pub const VEC_RESIZE: [&str; 4] = ["alloc", "vec", "Vec", "resize"];
+
+// Pinning without `Unpin` guarantees
+pub const PIN_NEW_UNCHECKED: [&str; 4] = ["core", "pin", "Pin", "new_unchecked"];
+pub const PIN_GET_UNCHECKED_MUT: [&str; 4] = ["core", "pin", "Pin", "get_unchecked_mut"];
+pub const PIN_MAP_UNCHECKED: [&str; 4] = ["core", "pin", "Pin", "map_unchecked"];
+pub const PIN_MAP_UNCHECKED_MUT: [&str; 4] = ["core", "pin", "Pin", "map_unchecked_mut"];
+
+// Functions that move a value out of a reference
+pub const MEM_REPLACE: [&str; 3] = ["core", "mem", "replace"];
+pub const MEM_SWAP: [&str; 3] = ["core", "mem", "swap"];
+pub const MEM_TAKE: [&str; 3] = ["core", "mem", "take"];
+pub const OPTION_TAKE: [&str; 4] = ["core", "option", "Option", "take"];
+pub const OPTION_REPLACE: [&str; 4] = ["core", "option", "Option", "replace"];
+
+// Panic boundaries
+pub const PANIC_CATCH_UNWIND: [&str; 3] = ["std", "panic", "catch_unwind"];
+
+// Functions that synchronize accesses to shared data
+pub const MUTEX_LOCK: [&str; 6] = ["std", "sync", "poison", "mutex", "Mutex", "lock"];
+pub const MUTEX_TRY_LOCK: [&str; 6] = ["std", "sync", "poison", "mutex", "Mutex", "try_lock"];
+pub const RWLOCK_READ: [&str; 6] = ["std", "sync", "poison", "rwlock", "RwLock", "read"];
+pub const RWLOCK_WRITE: [&str; 6] = ["std", "sync", "poison", "rwlock", "RwLock", "write"];
+pub const ONCE_CALL_ONCE: [&str; 6] = ["std", "sync", "poison", "once", "Once", "call_once"];
+pub const ONCE_CALL_ONCE_FORCE: [&str; 6] =
+    ["std", "sync", "poison", "once", "Once", "call_once_force"];
+pub const ATOMIC_BOOL_COMPARE_EXCHANGE: [&str; 5] =
+    ["core", "sync", "atomic", "AtomicBool", "compare_exchange"];
+pub const ATOMIC_BOOL_COMPARE_EXCHANGE_WEAK: [&str; 5] = [
+    "core",
+    "sync",
+    "atomic",
+    "AtomicBool",
+    "compare_exchange_weak",
+];
+pub const ATOMIC_BOOL_SWAP: [&str; 5] = ["core", "sync", "atomic", "AtomicBool", "swap"];
+pub const ATOMIC_MODULE: [&str; 3] = ["core", "sync", "atomic"];
+pub const ATOMIC_ORDERING: [&str; 4] = ["core", "sync", "atomic", "Ordering"];
+pub const MUTEX: [&str; 5] = ["std", "sync", "poison", "mutex", "Mutex"];
+pub const RWLOCK: [&str; 5] = ["std", "sync", "poison", "rwlock", "RwLock"];
+
+// Interior mutability
+pub const CELL: [&str; 3] = ["core", "cell", "Cell"];
+pub const CELL_SET: [&str; 4] = ["core", "cell", "Cell", "set"];
+pub const CELL_REPLACE: [&str; 4] = ["core", "cell", "Cell", "replace"];
+pub const CELL_SWAP: [&str; 4] = ["core", "cell", "Cell", "swap"];
+pub const CELL_TAKE: [&str; 4] = ["core", "cell", "Cell", "take"];
+pub const UNSAFE_CELL_GET: [&str; 4] = ["core", "cell", "UnsafeCell", "get"];
+pub const UNSAFE_CELL_RAW_GET: [&str; 4] = ["core", "cell", "UnsafeCell", "raw_get"];
+pub const NON_NULL: [&str; 4] = ["core", "ptr", "non_null", "NonNull"];
+pub const PTR_REPLACE: [&str; 3] = ["core", "ptr", "replace"];
+pub const PTR_DIRECT_REPLACE: [&str; 5] = ["core", "ptr", "mut_ptr", "<impl *mut T>", "replace"];
+
 pub struct PathSet {
     set: HashSet<Vec<Symbol>>,
 }
@@ -154,3 +284,171 @@ pub static WEAK_BYPASS_MAP: Lazy<PathMap> = Lazy::new(move || {
         SLICE_FROM_RAW_PARTS_MUT.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::SLICE_FROM_RAW,
     }
 });
+
+pub static BUFFER_INIT_LIST: Lazy<PathSet> = Lazy::new(move || {
+    PathSet::new(&[
+        &INTRINSICS_WRITE_BYTES,
+        &INTRINSICS_COPY,
+        &INTRINSICS_COPY_NONOVERLAPPING,
+        &SLICE_FILL,
+        &SLICE_COPY_FROM_SLICE,
+    ])
+});
+
+type UninitPathMap = HashMap<Vec<Symbol>, UninitExposureBehaviorFlag>;
+
+pub static UNINIT_SOURCE_MAP: Lazy<UninitPathMap> = Lazy::new(move || {
+    use UninitExposureBehaviorFlag as BehaviorFlag;
+
+    hashmap! {
+        VEC_SET_LEN.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::VEC_SET_LEN,
+        MAYBE_UNINIT_ASSUME_INIT.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::ASSUME_INIT,
+        MEM_UNINITIALIZED.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::MEM_UNINITIALIZED,
+        ALLOC_ALLOC.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::ALLOC,
+    }
+});
+
+pub static BOUNDS_CLAMP_LIST: Lazy<PathSet> =
+    Lazy::new(move || PathSet::new(&[&CMP_MIN, &CMP_ORD_MIN, &CMP_ORD_CLAMP]));
+
+pub static CAPACITY_RESERVE_LIST: Lazy<PathSet> = Lazy::new(move || {
+    PathSet::new(&[
+        &VEC_WITH_CAPACITY,
+        &VEC_RESERVE,
+        &VEC_RESERVE_EXACT,
+        &VEC_RESIZE,
+    ])
+});
+
+/// Maps a function to the index of the argument that carries its precondition.
+type PreconditionPathMap = HashMap<Vec<Symbol>, (usize, UncheckedPreconditionBehaviorFlag)>;
+
+pub static UNCHECKED_PRECONDITION_MAP: Lazy<PreconditionPathMap> = Lazy::new(move || {
+    use UncheckedPreconditionBehaviorFlag as BehaviorFlag;
+
+    hashmap! {
+        SLICE_GET_UNCHECKED.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => (1, BehaviorFlag::GET_UNCHECKED),
+        SLICE_GET_UNCHECKED_MUT.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => (1, BehaviorFlag::GET_UNCHECKED),
+        //
+        SLICE_FROM_RAW_PARTS.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => (1, BehaviorFlag::FROM_RAW_PARTS),
+        SLICE_FROM_RAW_PARTS_MUT.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => (1, BehaviorFlag::FROM_RAW_PARTS),
+        PTR_SLICE_FROM_RAW_PARTS.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => (1, BehaviorFlag::FROM_RAW_PARTS),
+        PTR_SLICE_FROM_RAW_PARTS_MUT.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => (1, BehaviorFlag::FROM_RAW_PARTS),
+        //
+        PTR_ADD.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => (1, BehaviorFlag::PTR_OFFSET),
+        PTR_OFFSET.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => (1, BehaviorFlag::PTR_OFFSET),
+        PTR_MUT_ADD.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => (1, BehaviorFlag::PTR_OFFSET),
+        PTR_MUT_OFFSET.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => (1, BehaviorFlag::PTR_OFFSET),
+        //
+        VEC_SET_LEN.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => (1, BehaviorFlag::SET_LEN),
+        //
+        STR_FROM_UTF8_UNCHECKED.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => (0, BehaviorFlag::FROM_UTF8_UNCHECKED),
+        STR_FROM_UTF8_UNCHECKED_MUT.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => (0, BehaviorFlag::FROM_UTF8_UNCHECKED),
+        STRING_FROM_UTF8_UNCHECKED.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => (0, BehaviorFlag::FROM_UTF8_UNCHECKED),
+    }
+});
+
+pub static REINITIALIZE_LIST: Lazy<PathSet> = Lazy::new(move || {
+    PathSet::new(&[
+        &PTR_WRITE,
+        &PTR_DIRECT_WRITE,
+        &INTRINSICS_COPY,
+        &INTRINSICS_COPY_NONOVERLAPPING,
+        &VEC_SET_LEN,
+    ])
+});
+
+type DuplicationPathMap = HashMap<Vec<Symbol>, DoubleDropBehaviorFlag>;
+
+pub static DUPLICATION_MAP: Lazy<DuplicationPathMap> = Lazy::new(move || {
+    use DoubleDropBehaviorFlag as BehaviorFlag;
+
+    hashmap! {
+        PTR_READ.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::PTR_READ,
+        PTR_DIRECT_READ.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::PTR_READ,
+        PTR_MUT_READ.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::PTR_READ,
+        BOX_FROM_RAW.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::BOX_FROM_RAW,
+        VEC_FROM_RAW_PARTS.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::VEC_FROM_RAW_PARTS,
+        MEM_TRANSMUTE_COPY.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::TRANSMUTE_COPY,
+    }
+});
+
+pub static ALIGNMENT_CHECK_LIST: Lazy<PathSet> = Lazy::new(move || {
+    PathSet::new(&[
+        &PTR_IS_ALIGNED,
+        &PTR_ALIGN_OFFSET,
+        &PTR_ADDR,
+        &PTR_MUT_IS_ALIGNED,
+        &PTR_MUT_ALIGN_OFFSET,
+        &PTR_MUT_ADDR,
+    ])
+});
+
+pub static ALIGNED_SOURCE_LIST: Lazy<PathSet> = Lazy::new(move || {
+    PathSet::new(&[
+        &ALLOC_ALLOC,
+        &ALLOC_ALLOC_ZEROED,
+        &ALLOC_REALLOC,
+        &GLOBAL_ALLOC_ALLOC,
+        &GLOBAL_ALLOC_ALLOC_ZEROED,
+        &GLOBAL_ALLOC_REALLOC,
+        &ALLOCATOR_ALLOCATE,
+        &ALLOCATOR_ALLOCATE_ZEROED,
+        &NON_NULL_DANGLING,
+        &BOX_INTO_RAW,
+    ])
+});
+
+type AlignedAccessPathMap = HashMap<Vec<Symbol>, UnalignedAccessBehaviorFlag>;
+
+pub static ALIGNED_ACCESS_MAP: Lazy<AlignedAccessPathMap> = Lazy::new(move || {
+    use UnalignedAccessBehaviorFlag as BehaviorFlag;
+
+    hashmap! {
+        PTR_READ.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::PTR_READ,
+        PTR_DIRECT_READ.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::PTR_READ,
+        PTR_MUT_READ.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::PTR_READ,
+        SLICE_FROM_RAW_PARTS.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::FROM_RAW_PARTS,
+        SLICE_FROM_RAW_PARTS_MUT.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::FROM_RAW_PARTS,
+    }
+});
+
+pub static SYNCHRONIZATION_LIST: Lazy<PathSet> = Lazy::new(move || {
+    PathSet::new(&[
+        &MUTEX_LOCK,
+        &MUTEX_TRY_LOCK,
+        &RWLOCK_READ,
+        &RWLOCK_WRITE,
+        &ONCE_CALL_ONCE,
+        &ONCE_CALL_ONCE_FORCE,
+        &ATOMIC_BOOL_COMPARE_EXCHANGE,
+        &ATOMIC_BOOL_COMPARE_EXCHANGE_WEAK,
+        &ATOMIC_BOOL_SWAP,
+    ])
+});
+
+pub static CELL_WRITE_LIST: Lazy<PathSet> =
+    Lazy::new(move || PathSet::new(&[&CELL_SET, &CELL_REPLACE, &CELL_SWAP, &CELL_TAKE]));
+
+pub static UNSAFE_CELL_GET_LIST: Lazy<PathSet> =
+    Lazy::new(move || PathSet::new(&[&UNSAFE_CELL_GET, &UNSAFE_CELL_RAW_GET]));
+
+pub static PTR_WRITE_LIST: Lazy<PathSet> = Lazy::new(move || {
+    PathSet::new(&[
+        &PTR_WRITE,
+        &PTR_DIRECT_WRITE,
+        &PTR_REPLACE,
+        &PTR_DIRECT_REPLACE,
+    ])
+});
+
+pub static MOVE_OUT_LIST: Lazy<PathSet> = Lazy::new(move || {
+    PathSet::new(&[
+        &MEM_REPLACE,
+        &MEM_SWAP,
+        &MEM_TAKE,
+        &PTR_READ,
+        &OPTION_TAKE,
+        &OPTION_REPLACE,
+    ])
+});
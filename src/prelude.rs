@@ -1,7 +1,7 @@
 use rustc_hir::definitions::{DefPathData, DisambiguatedDefPathData};
 use rustc_hir::{
-    def::Res,
-    def_id::{CrateNum, DefId},
+    def::{DefKind, Res},
+    def_id::{CrateNum, DefId, LocalDefId},
     Expr, ExprKind, Safety,
 };
 use rustc_middle::ty::print::{with_no_trimmed_paths, PrintError, PrintTraitRefExt as _, Printer};
@@ -65,6 +65,13 @@ impl<'tcx> TyCtxtExtension<'tcx> {
         }
     }
 
+    /// Returns true if `def_id` is a safe function or method that is reachable from other crates.
+    pub fn is_exported_safe_fn(self, def_id: LocalDefId) -> bool {
+        matches!(self.tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+            && self.tcx.fn_sig(def_id).skip_binder().safety() == Safety::Safe
+            && self.tcx.effective_visibilities(()).is_exported(def_id)
+    }
+
     // `clippy_lints::utils::match_def_path` + rustc's `LateContext::match_def_path`
     /// Checks if the given def_id matches the path string.
     /// Prefer [`crate::paths::PathSet`] when comparing a single definition against multiple paths.
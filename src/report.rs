@@ -1,5 +1,6 @@
 use rustc_hir::def_id::LocalDefId;
 use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
 
 use std::borrow::Cow;
 use std::env;
@@ -73,6 +74,16 @@ pub struct Report {
     description: Cow<'static, str>,
     location: String,
     source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suggestion: Option<Suggestion>,
+}
+
+/// A machine-applicable replacement that resolves the report
+#[derive(Serialize)]
+pub struct Suggestion {
+    message: Cow<'static, str>,
+    location: String,
+    replacement: String,
 }
 
 impl Report {
@@ -111,6 +122,7 @@ impl Report {
             description: description.into(),
             location,
             source,
+            suggestion: None,
         }
     }
 
@@ -134,8 +146,29 @@ impl Report {
             description: description.into(),
             location,
             source: color_span.to_colored_string(),
+            suggestion: None,
         }
     }
+
+    /// Suggest replacing the source at `span` with `replacement`
+    pub fn with_suggestion<T>(
+        mut self,
+        tcx: TyCtxt<'_>,
+        message: T,
+        span: Span,
+        replacement: String,
+    ) -> Report
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        let source_map = tcx.sess.source_map();
+        self.suggestion = Some(Suggestion {
+            message: message.into(),
+            location: source_map.span_to_diagnostic_string(span),
+            replacement,
+        });
+        self
+    }
 }
 
 pub trait ReportLogger: Sync + Send {
@@ -176,6 +209,23 @@ impl ReportLogger for StderrLogger {
                 &report.source
             )
             .expect("stderr closed");
+            if let Some(suggestion) = &report.suggestion {
+                let replacement = suggestion.replacement.trim_start();
+                if replacement.is_empty() {
+                    writeln!(
+                        &mut handle,
+                        "help: {}\n-> {}",
+                        &suggestion.message, &suggestion.location
+                    )
+                } else {
+                    writeln!(
+                        &mut handle,
+                        "help: {}: `{}`\n-> {}",
+                        &suggestion.message, replacement, &suggestion.location
+                    )
+                }
+                .expect("stderr closed");
+            }
         }
     }
 }
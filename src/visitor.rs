@@ -134,6 +134,44 @@ impl<'tcx> intravisit::Visitor<'tcx> for ContainsUnsafe<'tcx> {
     }
 }
 
+/// Collects the spans of `unsafe` blocks in a function body.
+pub struct UnsafeBlockSpans<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    spans: Vec<Span>,
+}
+
+impl<'tcx> UnsafeBlockSpans<'tcx> {
+    pub fn collect(tcx: TyCtxt<'tcx>, body_id: BodyId) -> Vec<Span> {
+        use intravisit::Visitor;
+
+        let mut visitor = UnsafeBlockSpans {
+            tcx,
+            spans: Vec::new(),
+        };
+
+        let body = visitor.tcx.hir().body(body_id);
+        visitor.visit_body(body);
+
+        visitor.spans
+    }
+}
+
+impl<'tcx> intravisit::Visitor<'tcx> for UnsafeBlockSpans<'tcx> {
+    type NestedFilter = OnlyBodies;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    fn visit_block(&mut self, block: &'tcx Block<'tcx>) {
+        use rustc_hir::BlockCheckMode;
+        if let BlockCheckMode::UnsafeBlock(_unsafe_source) = block.rules {
+            self.spans.push(block.span);
+        }
+        intravisit::walk_block(self, block);
+    }
+}
+
 /// (`DefId` of ADT) => Vec<(HirId of relevant impl block, impl_self_ty)>
 /// We use this map to quickly access associated impl blocks per ADT.
 /// `impl_self_ty` in the return value may differ from `tcx.type_of(ADT.DefID)`,
@@ -80,14 +80,19 @@ def run_test(test_case):
         with tempfile.NamedTemporaryFile(prefix="rudra") as report_file:
             env_dict = dict(os.environ)
             env_dict["RUDRA_REPORT_PATH"] = report_file.name
+            command = [
+                "rudra",
+                "-Zrudra-enable-unsafe-destructor",
+                "--crate-type",
+                "lib",
+                test_case.path
+            ]
+            if "edition" in metadata:
+                command += ["--edition", str(metadata["edition"])]
+            if "rudra_args" in metadata:
+                command += [str(arg) for arg in metadata["rudra_args"]]
             output = subprocess.run(
-                [
-                    "rudra",
-                    "-Zrudra-enable-unsafe-destructor",
-                    "--crate-type",
-                    "lib",
-                    test_case.path
-                ],
+                command,
                 stdout=subprocess.PIPE,
                 stderr=subprocess.STDOUT,
                 env=env_dict,
@@ -109,6 +114,20 @@ def run_test(test_case):
             )
             assert expected_analyzers == reported_analyzers, analyzer_mismatch_msg
 
+            # Each expected message has to appear in the description of some report
+            descriptions = [str(report["description"]) for report in reports.get("reports", [])]
+            for expected_message in metadata.get("expected_messages", []):
+                message_mismatch_msg = "Message not found; expected {!r} in {}".format(
+                    str(expected_message), descriptions
+                )
+                assert any(str(expected_message) in description for description in descriptions), message_mismatch_msg
+
+            # Each expected log line has to appear in the output of Rudra
+            output_str = output.stdout.decode()
+            for expected_log in metadata.get("expected_logs", []):
+                log_mismatch_msg = "Log not found; expected {!r}".format(str(expected_log))
+                assert str(expected_log) in output_str, log_mismatch_msg
+
             return TestResult(test_case, test_type)
     except (AssertionError,) as e:
         return TestResult(test_case, test_type, e)
@@ -0,0 +1,30 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+edition = "2021"
+```
+!*/
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+pub struct Tick;
+
+impl Future for Tick {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        Poll::Ready(())
+    }
+}
+
+// If the future is dropped at `.await`, the first element is dropped twice
+pub async fn rotate_first(v: &mut Vec<String>) {
+    unsafe {
+        let first = std::ptr::read(v.as_ptr());
+        Tick.await;
+        std::ptr::write(v.as_mut_ptr(), first);
+    }
+}
@@ -0,0 +1,34 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+edition = "2021"
+```
+!*/
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+pub struct Tick;
+
+impl Future for Tick {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        Poll::Ready(())
+    }
+}
+
+// Exposes an uninitialized element if the future is dropped at `.await`
+pub fn grow<'a>(v: &'a mut Vec<u8>) -> impl Future<Output = ()> + 'a {
+    async move {
+        v.reserve(1);
+        unsafe {
+            let len = v.len();
+            v.set_len(len + 1);
+            Tick.await;
+            *v.as_mut_ptr().add(len) = 0;
+        }
+    }
+}
@@ -0,0 +1,20 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["DanglingPointer"]
+```
+!*/
+
+use std::ffi::CString;
+use std::os::raw::c_char;
+
+extern "C" {
+    fn puts(s: *const c_char) -> i32;
+}
+
+pub fn print(s: &str) {
+    let ptr = CString::new(s).unwrap().as_ptr();
+    unsafe {
+        puts(ptr);
+    }
+}
@@ -0,0 +1,51 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+use std::ffi::CString;
+use std::os::raw::c_char;
+
+extern "C" {
+    fn puts(s: *const c_char) -> i32;
+}
+
+pub fn print(s: &str) {
+    let c_string = CString::new(s).unwrap();
+    unsafe {
+        puts(c_string.as_ptr());
+    }
+}
+
+pub fn into_raw(v: Vec<u8>) -> *const u8 {
+    let v = std::mem::ManuallyDrop::new(v);
+    v.as_ptr()
+}
+
+pub fn leak(v: Vec<u8>) -> *const u8 {
+    let ptr = v.as_ptr();
+    std::mem::forget(v);
+    ptr
+}
+
+pub fn read_local() -> u32 {
+    let value = 42u32;
+    let ptr = &value as *const u32;
+    unsafe { *ptr }
+}
+
+pub fn read_param(value: &u32) -> *const u32 {
+    let ptr = value as *const u32;
+    unsafe { std::ptr::read_volatile(ptr) };
+    ptr
+}
+
+pub fn in_loop(values: &mut [u32]) {
+    for value in values.iter_mut() {
+        let copy = *value;
+        let ptr = &copy as *const u32;
+        *value = unsafe { *ptr } + 1;
+    }
+}
@@ -0,0 +1,35 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["DanglingPointer"]
+```
+!*/
+
+pub struct Holder {
+    ptr: *const u32,
+}
+
+pub fn local_ptr() -> *const u32 {
+    let value = 42u32;
+    let ptr = &value as *const u32;
+    unsafe { std::ptr::read_volatile(ptr) };
+    ptr
+}
+
+impl Holder {
+    pub fn remember(&mut self) {
+        let value = 42u32;
+        let ptr = &value as *const u32;
+        unsafe { std::ptr::read_volatile(ptr) };
+        self.ptr = ptr;
+    }
+}
+
+pub fn use_after_scope() -> u32 {
+    let ptr;
+    {
+        let value = 42u32;
+        ptr = &value as *const u32;
+    }
+    unsafe { *ptr }
+}
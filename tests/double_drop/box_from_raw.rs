@@ -0,0 +1,16 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["DoubleDrop"]
+```
+!*/
+
+pub fn free_twice(value: Box<u32>) {
+    let raw = Box::into_raw(value);
+    unsafe {
+        let first = Box::from_raw(raw);
+        let second = Box::from_raw(raw);
+        drop(first);
+        drop(second);
+    }
+}
@@ -0,0 +1,48 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+use std::mem::{self, ManuallyDrop};
+
+pub fn forget_original(value: String) -> String {
+    let copy = unsafe { std::ptr::read(&value) };
+    mem::forget(value);
+    copy
+}
+
+pub fn manually_drop_original(value: String) -> usize {
+    let value = ManuallyDrop::new(value);
+    let copy = unsafe { std::ptr::read(&*value) };
+    copy.len()
+}
+
+pub fn replace(slot: &mut String, new_value: String) -> String {
+    unsafe {
+        let old = std::ptr::read(slot);
+        std::ptr::write(slot, new_value);
+        old
+    }
+}
+
+pub fn take_all(mut values: Vec<String>) -> Vec<String> {
+    let mut taken = Vec::new();
+    unsafe {
+        for i in 0..values.len() {
+            taken.push(std::ptr::read(values.as_ptr().add(i)));
+        }
+        values.set_len(0);
+    }
+    taken
+}
+
+pub fn round_trip(value: Box<u32>) -> Box<u32> {
+    let raw = Box::into_raw(value);
+    unsafe { Box::from_raw(raw) }
+}
+
+pub fn copy_integer(value: &u64) -> u64 {
+    unsafe { std::ptr::read(value) }
+}
@@ -0,0 +1,37 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["DoubleDrop"]
+expected_messages = [
+    "Potential double drop in `duplicate_and_return`",
+    "Potential double drop in `read_into`",
+]
+```
+!*/
+
+pub fn duplicate(value: String) -> usize {
+    let copy = unsafe { std::ptr::read(&value) };
+    copy.len()
+}
+
+// The caller drops the returned copy
+pub fn duplicate_and_return(value: String) -> String {
+    let copy = unsafe { std::ptr::read(&value) };
+    drop(value);
+    copy
+}
+
+pub fn read_into(value: String, out: *mut String) {
+    unsafe {
+        let copy = std::ptr::read(&value);
+        std::ptr::write(out, copy);
+    }
+}
+
+pub fn read_and_drop_in_place(ptr: *mut String) {
+    unsafe {
+        let copy = ptr.read();
+        drop(copy);
+        std::ptr::drop_in_place(ptr);
+    }
+}
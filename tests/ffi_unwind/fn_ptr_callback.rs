@@ -0,0 +1,16 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["FfiUnwind"]
+```
+!*/
+
+pub struct Handler {
+    on_event: fn(u32),
+}
+
+pub extern "C" fn dispatch(handler: *const Handler, event: u32) {
+    let handler = unsafe { &*handler };
+    let run = || (handler.on_event)(event);
+    run();
+}
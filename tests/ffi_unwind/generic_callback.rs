@@ -0,0 +1,19 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["FfiUnwind"]
+```
+!*/
+
+use std::os::raw::c_void;
+
+// Trampoline handed to a C library together with a pointer to the closure
+pub extern "C" fn trampoline<F: FnMut(i32)>(data: *mut c_void, value: i32) {
+    let callback = unsafe { &mut *(data as *mut F) };
+    callback(value);
+}
+
+pub extern "C" fn boxed_trampoline(data: *mut c_void, value: i32) {
+    let callback = unsafe { &mut *(data as *mut Box<dyn FnMut(i32)>) };
+    callback(value);
+}
@@ -0,0 +1,34 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+use std::os::raw::c_void;
+use std::panic::{self, AssertUnwindSafe};
+
+// Panics are caught before they reach the C caller
+pub extern "C" fn trampoline<F: FnMut(i32)>(data: *mut c_void, value: i32) -> i32 {
+    let callback = unsafe { &mut *(data as *mut F) };
+    match panic::catch_unwind(AssertUnwindSafe(|| callback(value))) {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+
+// Panics are allowed to unwind through `C-unwind`
+pub extern "C-unwind" fn unwinding_trampoline<F: FnMut(i32)>(data: *mut c_void, value: i32) {
+    let callback = unsafe { &mut *(data as *mut F) };
+    callback(value);
+}
+
+// Only calls resolvable code
+pub extern "C" fn sum(values: &[u32; 4]) -> u32 {
+    values.iter().copied().fold(0, u32::wrapping_add)
+}
+
+// Rust functions are not affected
+pub fn call<F: FnOnce()>(f: F) {
+    f()
+}
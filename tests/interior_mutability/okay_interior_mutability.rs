@@ -0,0 +1,72 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+// Writes happen only after acquiring the spin lock
+pub struct SpinCell {
+    locked: AtomicBool,
+    value: UnsafeCell<u64>,
+}
+
+unsafe impl Sync for SpinCell {}
+
+impl SpinCell {
+    pub fn set(&self, value: u64) {
+        while self
+            .locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {}
+        unsafe {
+            *self.value.get() = value;
+        }
+        self.locked.store(false, Ordering::Release);
+    }
+
+    // Only reads through `&self`
+    pub fn peek(&self) -> u64 {
+        unsafe { *self.value.get() }
+    }
+
+    // `&mut self` guarantees exclusive access
+    pub fn reset(&mut self) {
+        *self.value.get_mut() = 0;
+    }
+}
+
+// Writes are guarded by a `Mutex` field
+pub struct Guarded {
+    lock: Mutex<()>,
+    value: UnsafeCell<u64>,
+}
+
+unsafe impl Sync for Guarded {}
+
+impl Guarded {
+    pub fn set(&self, value: u64) {
+        let _guard = self.lock.lock().unwrap();
+        unsafe {
+            *self.value.get() = value;
+        }
+    }
+}
+
+// Unsafe methods leave synchronization to their callers
+pub struct Unchecked {
+    value: *mut u64,
+}
+
+unsafe impl Sync for Unchecked {}
+
+impl Unchecked {
+    pub unsafe fn store(&self, value: u64) {
+        *self.value = value;
+    }
+}
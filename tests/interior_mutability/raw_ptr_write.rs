@@ -0,0 +1,34 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["InteriorMutability"]
+```
+!*/
+
+use std::ptr::{self, NonNull};
+
+pub struct Slot {
+    value: *mut u64,
+}
+
+unsafe impl Sync for Slot {}
+
+impl Slot {
+    pub fn store(&self, value: u64) {
+        unsafe {
+            *self.value = value;
+        }
+    }
+}
+
+pub struct Buffer {
+    data: NonNull<u8>,
+}
+
+unsafe impl Sync for Buffer {}
+
+impl Buffer {
+    pub fn clear(&self) {
+        unsafe { ptr::write(self.data.as_ptr(), 0) }
+    }
+}
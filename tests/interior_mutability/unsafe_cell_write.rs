@@ -0,0 +1,44 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["InteriorMutability"]
+```
+!*/
+
+use std::cell::{Cell, UnsafeCell};
+
+pub struct Counter {
+    count: UnsafeCell<usize>,
+    hits: Cell<usize>,
+}
+
+unsafe impl Sync for Counter {}
+
+impl Counter {
+    pub fn increment(&self) {
+        unsafe {
+            *self.count.get() += 1;
+        }
+    }
+
+    pub fn hit(&self) {
+        self.hits.set(self.hits.get() + 1);
+    }
+
+    pub fn get(&self) -> usize {
+        unsafe { *self.count.get() }
+    }
+}
+
+pub struct Log {
+    entries: UnsafeCell<Vec<String>>,
+}
+
+unsafe impl Sync for Log {}
+
+impl Log {
+    pub fn push(&self, entry: String) {
+        let entries = unsafe { &mut *self.entries.get() };
+        entries.push(entry);
+    }
+}
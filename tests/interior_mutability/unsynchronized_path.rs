@@ -0,0 +1,55 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["InteriorMutability"]
+expected_messages = [
+    "`Stats::set` writes to `Sync` type `Stats`",
+    "`MaybeGuarded::set` writes to `Sync` type `MaybeGuarded`",
+]
+```
+!*/
+
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+// A `Relaxed` counter does not synchronize the write that follows it
+pub struct Stats {
+    updates: AtomicUsize,
+    value: UnsafeCell<u64>,
+}
+
+unsafe impl Sync for Stats {}
+
+impl Stats {
+    pub fn set(&self, value: u64) {
+        self.updates.fetch_add(1, Ordering::Relaxed);
+        unsafe {
+            *self.value.get() = value;
+        }
+    }
+}
+
+// The lock is only taken on one of the paths to a write
+pub struct MaybeGuarded {
+    shared: bool,
+    lock: Mutex<()>,
+    value: UnsafeCell<u64>,
+}
+
+unsafe impl Sync for MaybeGuarded {}
+
+impl MaybeGuarded {
+    pub fn set(&self, value: u64) {
+        if self.shared {
+            let _guard = self.lock.lock().unwrap();
+            unsafe {
+                *self.value.get() = value;
+            }
+        } else {
+            unsafe {
+                *self.value.get() = value;
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+use std::cell::UnsafeCell;
+
+pub struct RawBuffer {
+    ptr: *const u8,
+    len: usize,
+}
+
+impl RawBuffer {
+    // Bounded by `self`
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    // Unsafe functions may return unbounded lifetimes
+    pub unsafe fn first<'a>(&self) -> &'a u8 {
+        &*self.ptr
+    }
+}
+
+pub fn set(cell: &UnsafeCell<u32>, new_value: u32) {
+    let ptr = cell as *const UnsafeCell<u32> as *mut UnsafeCell<u32>;
+    unsafe {
+        *(*ptr).get_mut() = new_value;
+    }
+}
+
+pub fn set_via_get(cell: &UnsafeCell<u32>, new_value: u32) {
+    unsafe {
+        *cell.get() = new_value;
+    }
+}
@@ -0,0 +1,30 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["LifetimeAliasing"]
+```
+!*/
+
+#![allow(invalid_reference_casting)]
+
+pub fn overwrite(value: &u32, new_value: u32) {
+    let ptr = value as *const u32 as *mut u32;
+    unsafe {
+        *ptr = new_value;
+    }
+}
+
+pub fn overwrite_with(value: &u64, new_value: u64) {
+    let ptr = value as *const u64 as *mut u64;
+    unsafe {
+        std::ptr::write(ptr, new_value);
+    }
+}
+
+// The old value is dropped through `*ptr` by a `Drop` terminator
+pub fn reset(value: &String) {
+    let ptr = value as *const String as *mut String;
+    unsafe {
+        *ptr = String::new();
+    }
+}
@@ -0,0 +1,22 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["LifetimeAliasing"]
+```
+!*/
+
+pub struct RawBuffer {
+    ptr: *const u8,
+    len: usize,
+}
+
+impl RawBuffer {
+    // The returned slice can outlive `self`
+    pub fn as_slice<'a>(&self) -> &'a [u8] {
+        unsafe { std::slice::from_raw_parts(&*self.ptr, self.len) }
+    }
+}
+
+pub fn first<'a>(ptr: *const u32) -> &'a u32 {
+    unsafe { &*ptr }
+}
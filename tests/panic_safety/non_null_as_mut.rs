@@ -0,0 +1,19 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+```
+!*/
+
+use std::ptr::NonNull;
+
+// `NonNull::as_mut` creates a reference of an unbounded lifetime that reaches user code
+pub fn inspect<T, F: FnMut(&mut T)>(mut ptr: NonNull<T>, mut f: F) {
+    let value = unsafe { ptr.as_mut() };
+    f(value);
+}
+
+pub fn peek<T, F: FnMut(&T)>(ptr: NonNull<T>, mut f: F) {
+    let value = unsafe { ptr.as_ref() };
+    f(value);
+}
@@ -0,0 +1,13 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+```
+!*/
+
+// A slice made by `slice::from_raw_parts` reaches user code
+pub fn with_prefix<T, F: FnMut(&[T])>(values: &Vec<T>, n: usize, mut f: F) {
+    let len = n.min(values.len());
+    let prefix = unsafe { std::slice::from_raw_parts(values.as_ptr(), len) };
+    f(prefix);
+}
@@ -0,0 +1,27 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["PinSoundness"]
+```
+!*/
+
+use std::mem;
+use std::pin::Pin;
+
+pub struct Slot<T: Default> {
+    value: T,
+}
+
+impl<T: Default> Slot<T> {
+    pub fn value(self: Pin<&mut Self>) -> Pin<&mut T> {
+        unsafe { self.map_unchecked_mut(|slot| &mut slot.value) }
+    }
+}
+
+// Moves the pinned `value` before it is dropped in place
+impl<T: Default> Drop for Slot<T> {
+    fn drop(&mut self) {
+        let value = mem::take(&mut self.value);
+        drop(value);
+    }
+}
@@ -0,0 +1,29 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["PinSoundness"]
+```
+!*/
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// The future is moved after being polled in place
+pub fn poll_once<F: Future>(mut fut: F, cx: &mut Context<'_>) -> Result<F::Output, F> {
+    match unsafe { Pin::new_unchecked(&mut fut) }.poll(cx) {
+        Poll::Ready(output) => Ok(output),
+        Poll::Pending => Err(fut),
+    }
+}
+
+pub struct Restart<F> {
+    fut: Option<F>,
+}
+
+impl<F: Future> Restart<F> {
+    pub fn restart(self: Pin<&mut Self>, fut: F) -> Option<F> {
+        let this = unsafe { self.get_unchecked_mut() };
+        this.fut.replace(fut)
+    }
+}
@@ -0,0 +1,43 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+pub struct Counted<F> {
+    fut: F,
+    polls: u32,
+    output: Option<u32>,
+}
+
+impl<F: Future> Future for Counted<F> {
+    type Output = Option<u32>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+        this.polls += 1;
+        match unsafe { Pin::new_unchecked(&mut this.fut) }.poll(cx) {
+            // Moving an `Unpin` field out is fine
+            Poll::Ready(_) => Poll::Ready(this.output.take()),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<F: Unpin> Unpin for Counted<F> {}
+
+impl<F> Drop for Counted<F> {
+    fn drop(&mut self) {
+        self.output.take();
+    }
+}
+
+pub fn poll_unpin<F: Future + Unpin>(mut fut: F, cx: &mut Context<'_>) -> F {
+    let _ = unsafe { Pin::new_unchecked(&mut fut) }.poll(cx);
+    fut
+}
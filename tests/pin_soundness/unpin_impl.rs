@@ -0,0 +1,34 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["PinSoundness"]
+```
+!*/
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+pub struct Timeout<F> {
+    fut: F,
+    remaining: u32,
+}
+
+impl<F: Future> Future for Timeout<F> {
+    type Output = Option<F::Output>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+        if this.remaining == 0 {
+            return Poll::Ready(None);
+        }
+        this.remaining -= 1;
+        match unsafe { Pin::new_unchecked(&mut this.fut) }.poll(cx) {
+            Poll::Ready(output) => Poll::Ready(Some(output)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+// `fut` is structurally pinned, so this allows moving a pinned `F`
+impl<F> Unpin for Timeout<F> {}
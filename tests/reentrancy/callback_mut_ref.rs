@@ -0,0 +1,24 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["Reentrancy"]
+```
+!*/
+
+pub struct Observable<F> {
+    values: Vec<u32>,
+    on_push: F,
+}
+
+impl<F: Fn(&Observable<F>)> Observable<F> {
+    pub fn push(&mut self, value: u32) {
+        let values = &mut self.values as *mut Vec<u32>;
+        unsafe {
+            let values = &mut *values;
+            values.push(value);
+            // The callback can read `self.values` while `values` is live
+            (self.on_push)(self);
+            values.push(value);
+        }
+    }
+}
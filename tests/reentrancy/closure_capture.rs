@@ -0,0 +1,45 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["Reentrancy"]
+```
+!*/
+
+use std::cell::UnsafeCell;
+use std::rc::Rc;
+
+pub struct Registry<F> {
+    entries: Vec<String>,
+    on_change: F,
+}
+
+impl<F: Fn(&dyn Fn() -> usize)> Registry<F> {
+    pub fn insert(&mut self, entry: String) {
+        let entries = &mut self.entries as *mut Vec<String>;
+        unsafe {
+            let entries = &mut *entries;
+            let this = &*self;
+            // The closure lets the callback read `self.entries`
+            (this.on_change)(&move || this.len());
+            entries.push(entry);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+pub struct SharedRegistry {
+    entries: UnsafeCell<Vec<String>>,
+}
+
+impl SharedRegistry {
+    pub fn notify<H: Fn(Rc<Self>)>(self: &Rc<Self>, handler: H) {
+        unsafe {
+            let entries = &mut *self.entries.get();
+            handler(Rc::clone(self));
+            entries.clear();
+        }
+    }
+}
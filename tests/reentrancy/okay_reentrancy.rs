@@ -0,0 +1,33 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+pub struct Observable<F> {
+    values: Vec<u32>,
+    on_push: F,
+}
+
+impl<F: Fn(&Observable<F>)> Observable<F> {
+    // The alias is dead before the callback runs
+    pub fn push(&mut self, value: u32) {
+        let values = &mut self.values as *mut Vec<u32>;
+        unsafe {
+            let values = &mut *values;
+            values.push(value);
+        }
+        (self.on_push)(self);
+    }
+
+    // The callback can not reach `self`
+    pub fn push_with<G: Fn(u32) -> u32>(&mut self, value: u32, map: G) {
+        let values = &mut self.values as *mut Vec<u32>;
+        unsafe {
+            let values = &mut *values;
+            values.push(map(value));
+            values.push(value);
+        }
+    }
+}
@@ -0,0 +1,12 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+rudra_args = ["--send-sync-mode=both"]
+```
+!*/
+
+// Flagged by both modes, reported once with the bounds of the strict mode
+pub struct Wrapper<T, U>(T, U);
+
+unsafe impl<T, U> Send for Wrapper<T, U> {}
@@ -0,0 +1,28 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+expected_messages = ["`&T` is exposed through `Scoped::<T>::with`"]
+```
+!*/
+
+use std::cell::UnsafeCell;
+
+// `&T` is handed to a generic callback, so `T: Sync` is needed for `impl Sync`
+pub struct Scoped<T> {
+    value: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Sync for Scoped<T> {}
+
+impl<T> Scoped<T> {
+    pub fn new(value: T) -> Self {
+        Scoped {
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    pub fn with<F: Fn(&T)>(&self, f: F) {
+        f(unsafe { &*self.value.get() })
+    }
+}
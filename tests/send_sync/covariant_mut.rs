@@ -0,0 +1,50 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+expected_messages = [
+    "`MyCell::<T>::set` writes `T` through `&self`",
+    "`Buffer::<T>::replace` writes `T` through `&self`",
+]
+```
+!*/
+
+use std::ptr::NonNull;
+
+// Covariant in `T`, so `&MyCell<&'static str>` coerces to `&MyCell<&'a str>`
+// and `set` can store a short-lived reference in a long-lived cell
+pub struct MyCell<T> {
+    ptr: NonNull<T>,
+}
+
+impl<T> MyCell<T> {
+    pub fn set(&self, value: T) {
+        unsafe {
+            *self.ptr.as_ptr() = value;
+        }
+    }
+
+    pub fn get(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+pub struct Slot<T> {
+    value: *const T,
+}
+
+impl<T> Slot<T> {
+    pub fn get_mut(&self) -> &mut T {
+        unsafe { &mut *(self.value as *mut T) }
+    }
+}
+
+pub struct Buffer<T> {
+    value: NonNull<T>,
+}
+
+impl<T> Buffer<T> {
+    pub fn replace(&self, value: T) {
+        unsafe { std::ptr::write(self.value.as_ptr(), value) }
+    }
+}
@@ -0,0 +1,55 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+expected_messages = [
+    "field `inner: Rc<T>` is never `Send`, field `value: U` requires `U: Send`, without this impl, `Shared<T, U>` would never be `Send`",
+    "field `cell: Cell<T>` is never `Sync`, without this impl, `Slot<T>` would never be `Sync`",
+    "field `first: X` requires `X: Send`, field `second: Arc<Y>` requires `Y: Send + Sync`, without this impl, `Pair<X, Y>` would be `Send` iff `X: Send` and `Y: Send + Sync`, `X` lacks `Send`",
+]
+```
+!*/
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+// Reported with "field `inner: Rc<T>` is never `Send`, field `value: U` requires `U: Send`"
+pub struct Shared<T, U> {
+    inner: Rc<T>,
+    value: U,
+}
+
+unsafe impl<T, U> Send for Shared<T, U> {}
+
+impl<T, U> Shared<T, U> {
+    pub fn value(&self) -> &U {
+        &self.value
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+// Reported with "field `cell: Cell<T>` is never `Sync`"
+pub struct Slot<T> {
+    cell: Cell<T>,
+}
+
+unsafe impl<T: Send> Sync for Slot<T> {}
+
+impl<T: Copy> Slot<T> {
+    pub fn get(&self) -> T {
+        self.cell.get()
+    }
+}
+
+// Reported with the impl's parameter names, and with the bounds the compiler would have derived:
+// "without this impl, `Pair<X, Y>` would be `Send` iff `X: Send` and `Y: Send + Sync`"
+pub struct Pair<A, B> {
+    first: A,
+    second: Arc<B>,
+}
+
+unsafe impl<X, Y> Send for Pair<X, Y> {}
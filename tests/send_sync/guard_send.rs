@@ -0,0 +1,45 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+```
+!*/
+
+// Reduced from lock_api's `MappedMutexGuard`
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+pub unsafe trait RawMutex {
+    fn lock(&self);
+    fn unlock(&self);
+}
+
+pub struct MappedMutexGuard<'a, R: RawMutex, T: ?Sized> {
+    raw: &'a R,
+    data: *mut T,
+    marker: PhantomData<&'a mut T>,
+}
+
+unsafe impl<'a, R: RawMutex + Sync + 'a, T: ?Sized + Sync + 'a> Sync
+    for MappedMutexGuard<'a, R, T>
+{
+}
+// The guard must be dropped on the thread that acquired the lock
+unsafe impl<'a, R: RawMutex + Sync + 'a, T: ?Sized + Send + 'a> Send
+    for MappedMutexGuard<'a, R, T>
+{
+}
+
+impl<'a, R: RawMutex, T: ?Sized> Deref for MappedMutexGuard<'a, R, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.data }
+    }
+}
+
+impl<'a, R: RawMutex, T: ?Sized> Drop for MappedMutexGuard<'a, R, T> {
+    fn drop(&mut self) {
+        self.raw.unlock()
+    }
+}
@@ -0,0 +1,59 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+// `PhantomData<*mut T>` makes `T` invariant
+pub struct MyCell<T> {
+    ptr: NonNull<T>,
+    _marker: PhantomData<*mut T>,
+}
+
+impl<T> MyCell<T> {
+    pub fn set(&self, value: T) {
+        unsafe {
+            *self.ptr.as_ptr() = value;
+        }
+    }
+}
+
+// Mutation requires `&mut self`, which is invariant in `T`
+pub struct Shared<T> {
+    ptr: *const T,
+}
+
+impl<T> Shared<T> {
+    pub fn get(&self) -> &T {
+        unsafe { &*self.ptr }
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        unsafe { &mut *(self.ptr as *mut T) }
+    }
+}
+
+// The store through a raw pointer writes a counter, not the `T` argument
+pub struct Counted<T> {
+    ptr: *const T,
+    hits: *mut usize,
+}
+
+impl<T: PartialEq> Counted<T> {
+    pub fn contains(&self, value: T) -> bool {
+        unsafe {
+            *self.hits += 1;
+            *self.ptr == value
+        }
+    }
+
+    pub fn reset(&self, _value: T) {
+        unsafe {
+            std::ptr::write(self.hits, 0);
+        }
+    }
+}
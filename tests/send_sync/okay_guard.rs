@@ -0,0 +1,35 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+use std::ops::Deref;
+
+pub unsafe trait RawMutex {
+    fn lock(&self);
+    fn unlock(&self);
+}
+
+pub struct MutexGuard<'a, R: RawMutex, T> {
+    raw: &'a R,
+    data: *mut T,
+}
+
+// Sharing the guard shares `&T`, so `T: Sync` is required
+unsafe impl<'a, R: RawMutex + Sync, T: Sync> Sync for MutexGuard<'a, R, T> {}
+
+impl<'a, R: RawMutex, T> Deref for MutexGuard<'a, R, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.data }
+    }
+}
+
+impl<'a, R: RawMutex, T> Drop for MutexGuard<'a, R, T> {
+    fn drop(&mut self) {
+        self.raw.unlock()
+    }
+}
@@ -0,0 +1,53 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub struct Pool;
+
+impl Pool {
+    pub fn release_buffer(&self, _ptr: *mut u8) {}
+}
+
+// `release_buffer` is not a release-like method
+pub struct Buffer<'a, T> {
+    pool: &'a Pool,
+    data: *mut T,
+}
+
+unsafe impl<'a, T: Send> Send for Buffer<'a, T> {}
+
+impl<'a, T> Drop for Buffer<'a, T> {
+    fn drop(&mut self) {
+        self.pool.release_buffer(self.data as *mut u8)
+    }
+}
+
+pub struct SpinLock {
+    locked: AtomicBool,
+}
+
+impl SpinLock {
+    pub fn unlock(&self) {
+        self.locked.store(false, Ordering::Release);
+    }
+}
+
+// The unlocked lock is owned, not borrowed, by the ticket
+pub struct Ticket<'a, T> {
+    label: &'a str,
+    lock: SpinLock,
+    value: *mut T,
+}
+
+unsafe impl<'a, T: Send> Send for Ticket<'a, T> {}
+
+impl<'a, T> Drop for Ticket<'a, T> {
+    fn drop(&mut self) {
+        self.lock.unlock()
+    }
+}
@@ -0,0 +1,20 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+pub struct Peeked<I: Iterator> {
+    item: I::Item,
+}
+
+unsafe impl<I: Iterator> Send for Peeked<I> where I::Item: Send {}
+unsafe impl<I: Iterator> Sync for Peeked<I> where I::Item: Sync {}
+
+pub struct Peekable<I: Iterator> {
+    iter: I,
+    peeked: Option<I::Item>,
+}
+
+unsafe impl<I: Iterator + Send> Send for Peekable<I> where I::Item: Send {}
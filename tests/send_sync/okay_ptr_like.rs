@@ -5,11 +5,59 @@ expected_analyzers = []
 ```
 !*/
 
+use std::ptr::NonNull;
+
 // impl `Send` for `PtrLike<Sync>` is okay
-// Note that we don't check pointer-likeness yet
+// Note that `Atom1` and `Atom2` don't expose `&P`, so they aren't pointer-like
 
 struct Atom1<P>(P);
 unsafe impl<P: Sync> Send for Atom1<P> {}
 
 struct Atom2<P>(P);
 unsafe impl<P> Send for Atom2<P> where P: Sync {}
+
+// Shared pointer with both bounds
+pub struct Shared<T> {
+    ptr: NonNull<T>,
+}
+
+unsafe impl<T: Send + Sync> Send for Shared<T> {}
+
+impl<T> Shared<T> {
+    pub fn get(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Shared { ptr: self.ptr }
+    }
+}
+
+// Unique pointer: no `Clone` impl, so `T` is never shared
+pub struct Unique<T> {
+    ptr: NonNull<T>,
+}
+
+unsafe impl<T: Send> Send for Unique<T> {}
+
+impl<T> Unique<T> {
+    pub fn get(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+// Cloning the wrapper clones `T`
+#[derive(Clone)]
+pub struct Owned<T> {
+    value: Box<T>,
+}
+
+unsafe impl<T: Send> Send for Owned<T> {}
+
+impl<T> Owned<T> {
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+}
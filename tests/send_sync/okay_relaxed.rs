@@ -0,0 +1,27 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+rudra_args = ["--send-sync-mode=relaxed"]
+```
+!*/
+
+use std::marker::PhantomData;
+
+// The relaxed mode accepts a bound on any of the generic parameters
+pub struct Wrapper<T, U>(T, U);
+
+unsafe impl<T: Send, U> Send for Wrapper<T, U> {}
+unsafe impl<T, U> Sync for Wrapper<T, U> where T: Sync {}
+
+// Supertraits of `Send` count as well
+pub trait Task: Send {}
+
+pub struct Queue<T>(PhantomData<T>);
+
+unsafe impl<T: Task> Send for Queue<T> {}
+
+// Impls without generic parameters are not checked
+pub struct Handle(*mut u8);
+
+unsafe impl Send for Handle {}
@@ -0,0 +1,33 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+rudra_args = ["-Zrudra-unsafe-trait=okay_unsafe_trait::Pod=okay_unsafe_trait::Zeroable"]
+```
+!*/
+
+/// Types that are valid when zero-initialized
+pub unsafe trait Zeroable {}
+
+/// Types that can be created from any bit pattern
+pub unsafe trait Pod: Zeroable + Copy {}
+
+unsafe impl Zeroable for u32 {}
+unsafe impl Pod for u32 {}
+
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct Wrapper<T>(T);
+
+unsafe impl<T: Zeroable> Zeroable for Wrapper<T> {}
+unsafe impl<T: Pod> Pod for Wrapper<T> {}
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct Tagged<T: Zeroable + Copy> {
+    value: T,
+}
+
+// Satisfying the configured bound is enough
+unsafe impl<T: Zeroable + Copy> Pod for Tagged<T> {}
+unsafe impl<T: Zeroable + Copy> Zeroable for Tagged<T> {}
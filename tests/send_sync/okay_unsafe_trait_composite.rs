@@ -0,0 +1,19 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+rudra_args = ["-Zrudra-unsafe-trait=okay_unsafe_trait_composite::Pod"]
+```
+!*/
+
+/// Types that can be created from any bit pattern
+pub unsafe trait Pod: Copy {}
+
+unsafe impl Pod for u32 {}
+
+unsafe impl<T: Pod> Pod for (T, T) {}
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+// Raw pointers are out of scope, and only logged
+unsafe impl<T> Pod for *const T {}
@@ -0,0 +1,21 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+```
+!*/
+
+// `I::Item: Send` is needed, not `I: Send`
+pub struct Peeked<I: Iterator> {
+    item: I::Item,
+}
+
+unsafe impl<I: Iterator> Send for Peeked<I> {}
+
+// `I: Send` alone doesn't cover the stored `I::Item`
+pub struct Peekable<I: Iterator> {
+    iter: I,
+    peeked: Option<I::Item>,
+}
+
+unsafe impl<I: Iterator + Send> Send for Peekable<I> {}
@@ -0,0 +1,27 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+```
+!*/
+
+use std::ptr::NonNull;
+
+// Clones share `T` across threads, so `T: Sync` is needed as well
+pub struct Shared<T> {
+    ptr: NonNull<T>,
+}
+
+unsafe impl<T: Send> Send for Shared<T> {}
+
+impl<T> Shared<T> {
+    pub fn get(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Shared { ptr: self.ptr }
+    }
+}
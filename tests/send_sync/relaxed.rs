@@ -0,0 +1,13 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+rudra_args = ["--send-sync-mode=relaxed"]
+```
+!*/
+
+// None of the generic parameters is bounded
+pub struct Wrapper<T, U>(T, U);
+
+unsafe impl<T, U> Send for Wrapper<T, U> {}
+unsafe impl<T, U> Sync for Wrapper<T, U> {}
@@ -0,0 +1,50 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+expected_messages = [
+    "field `inner: Rc<U>` is never `Send`, without this impl, `Wrapper<U>` would never be `Send`, `U` lacks `Send`",
+    "`T` lacks `Send + Sync`",
+    "field `item: Cell<<I as Iterator>::Item>` requires `I::Item: Sync`, without this impl, `Items<I>` would never be `Sync`, `I::Item` lacks `Sync`",
+]
+```
+!*/
+
+use std::cell::Cell;
+use std::ptr::NonNull;
+use std::rc::Rc;
+
+// Reported with "`U` lacks `Send`" and suggests `where U: Send`,
+// using the name of the impl's parameter
+pub struct Wrapper<T> {
+    inner: Rc<T>,
+}
+
+unsafe impl<U> Send for Wrapper<U> {}
+
+// Reported with "`T` lacks `Send + Sync`" and suggests `, T: Send + Sync`
+// after the existing where clause
+pub struct Shared<T> {
+    ptr: NonNull<T>,
+}
+
+unsafe impl<T> Send for Shared<T> where T: Clone {}
+
+impl<T> Shared<T> {
+    pub fn get(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Shared { ptr: self.ptr }
+    }
+}
+
+// Reported with "`I::Item` lacks `Sync`" and suggests `where I::Item: Sync`
+pub struct Items<I: Iterator> {
+    item: Cell<I::Item>,
+}
+
+unsafe impl<I: Iterator> Sync for Items<I> {}
@@ -0,0 +1,19 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+rudra_args = ["-Zrudra-unsafe-trait=unsafe_trait::Pod"]
+```
+!*/
+
+/// Types that can be created from any bit pattern
+pub unsafe trait Pod: Copy {}
+
+unsafe impl Pod for u32 {}
+
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct Wrapper<T>(T);
+
+// `Wrapper<bool>` would be `Pod`
+unsafe impl<T: Copy> Pod for Wrapper<T> {}
@@ -0,0 +1,29 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+rudra_args = ["-v", "-Zrudra-unsafe-trait=unsafe_trait_alias::Zeroable"]
+expected_logs = [
+    "[OutOfScope] Impl of `Zeroable` for non-ADT type `(dyn Shape + 'static)` is not analyzed",
+]
+```
+!*/
+
+#![feature(lazy_type_alias)]
+#![allow(incomplete_features)]
+
+/// Types that can be created from all-zero bytes
+pub unsafe trait Zeroable {}
+
+pub struct Wrapper<T>(T);
+
+// A lazy type alias stays an alias in the impl's self type
+pub type Zeroed<T> = Wrapper<T>;
+
+// `Zeroed<&u8>` would be `Zeroable`
+unsafe impl<T> Zeroable for Zeroed<T> {}
+
+pub trait Shape {}
+
+// Logged as out of scope
+unsafe impl Zeroable for dyn Shape {}
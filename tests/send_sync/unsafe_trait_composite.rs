@@ -0,0 +1,17 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+rudra_args = ["-Zrudra-unsafe-trait=unsafe_trait_composite::Pod"]
+```
+!*/
+
+/// Types that can be created from any bit pattern
+pub unsafe trait Pod: Copy {}
+
+pub type Pair<T> = (T, T);
+
+// `(bool, bool)` would be `Pod`
+unsafe impl<T: Copy> Pod for Pair<T> {}
+
+unsafe impl<T: Copy, const N: usize> Pod for [T; N] {}
@@ -0,0 +1,20 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["StaticMut"]
+```
+!*/
+
+use std::ptr::addr_of_mut;
+
+static mut LOG: Vec<String> = Vec::new();
+
+pub fn log_buffer() -> &'static mut Vec<String> {
+    unsafe { &mut *addr_of_mut!(LOG) }
+}
+
+pub fn store_log(out: &mut Option<&'static Vec<String>>) {
+    unsafe {
+        *out = Some(&*addr_of_mut!(LOG));
+    }
+}
@@ -0,0 +1,55 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+use std::ptr::addr_of_mut;
+use std::sync::{Mutex, Once};
+
+static mut CONFIG: Option<String> = None;
+static CONFIG_INIT: Once = Once::new();
+
+// Written only once, under `Once`
+pub fn config() -> Option<&'static String> {
+    CONFIG_INIT.call_once(|| unsafe {
+        CONFIG = Some(String::from("default"));
+    });
+    unsafe { (*addr_of_mut!(CONFIG)).as_ref() }
+}
+
+static mut TOTAL: u64 = 0;
+static TOTAL_LOCK: Mutex<()> = Mutex::new(());
+
+// Accessed while holding a lock
+pub fn add_total(amount: u64) -> u64 {
+    let _guard = TOTAL_LOCK.lock().unwrap();
+    unsafe {
+        TOTAL += amount;
+        TOTAL
+    }
+}
+
+static mut LIMIT: usize = 16;
+
+// Never written, so reads can not race
+pub fn limit() -> usize {
+    unsafe { LIMIT }
+}
+
+static mut SCRATCH: [u8; 64] = [0; 64];
+
+// Only reachable from `main`-like code inside the crate
+#[allow(dead_code)]
+fn reset_scratch() {
+    unsafe {
+        SCRATCH = [0; 64];
+    }
+}
+
+/// # Safety
+/// The caller must make sure that no other reference to the buffer is alive.
+pub unsafe fn scratch() -> &'static mut [u8; 64] {
+    &mut *addr_of_mut!(SCRATCH)
+}
@@ -0,0 +1,22 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["StaticMut"]
+```
+!*/
+
+static mut COUNTER: usize = 0;
+
+fn bump(amount: usize) {
+    unsafe {
+        COUNTER += amount;
+    }
+}
+
+pub fn increment() {
+    bump(1);
+}
+
+pub fn current() -> usize {
+    unsafe { COUNTER }
+}
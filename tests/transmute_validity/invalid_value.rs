@@ -0,0 +1,14 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["TransmuteValidity"]
+```
+!*/
+
+pub fn byte_to_bool(byte: u8) -> bool {
+    unsafe { std::mem::transmute::<u8, bool>(byte) }
+}
+
+pub fn code_to_char(code: u32) -> char {
+    unsafe { std::mem::transmute::<u32, char>(code) }
+}
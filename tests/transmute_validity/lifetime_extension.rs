@@ -0,0 +1,21 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["TransmuteValidity"]
+```
+!*/
+
+pub fn extend<'a>(s: &'a str) -> &'static str {
+    unsafe { std::mem::transmute::<&'a str, &'static str>(s) }
+}
+
+// Lifetimes from the signature when the turbofish is omitted
+pub fn extend_param(s: &str) -> &'static str {
+    unsafe { std::mem::transmute(s) }
+}
+
+pub fn unbounded<'a, 'b>(bytes: &'a [u8]) -> &'b [u8] {
+    unsafe {
+        return std::mem::transmute(bytes);
+    }
+}
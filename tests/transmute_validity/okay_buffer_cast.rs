@@ -0,0 +1,24 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+// The slice may hold more than one `u64`
+pub fn read_pair(words: &[u64]) -> [u64; 2] {
+    assert!(words.len() >= 2);
+    let ptr = words.as_ptr() as *const [u64; 2];
+    unsafe { *ptr }
+}
+
+// The caller provides a pointer to at least two `u64`s
+pub unsafe fn read_pair_raw(words: *const u64) -> [u64; 2] {
+    *(words as *const [u64; 2])
+}
+
+// The array is large enough for the destination
+pub fn read_from_array(words: &[u64; 2]) -> [u64; 2] {
+    let ptr = words.as_ptr() as *const [u64; 2];
+    unsafe { *ptr }
+}
@@ -0,0 +1,18 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+use std::os::raw::c_void;
+
+// User data handed to a C library is cast back to its original type
+pub extern "C" fn user_data(data: *mut c_void) -> u64 {
+    unsafe { *(data as *mut u64) }
+}
+
+pub extern "C" fn user_data_ref(data: *const c_void) -> u32 {
+    let words = unsafe { std::mem::transmute::<*const c_void, &[u32; 4]>(data) };
+    words[0]
+}
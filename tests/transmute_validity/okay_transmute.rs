@@ -0,0 +1,35 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+pub fn bits(value: f32) -> u32 {
+    unsafe { std::mem::transmute::<f32, u32>(value) }
+}
+
+pub fn bool_to_byte(value: bool) -> u8 {
+    unsafe { std::mem::transmute::<bool, u8>(value) }
+}
+
+pub fn first_byte(value: &u32) -> u8 {
+    let ptr = value as *const u32 as *const u8;
+    unsafe { *ptr }
+}
+
+pub fn from_bytes(bytes: [u8; 4]) -> u32 {
+    unsafe { std::mem::transmute::<[u8; 4], u32>(bytes) }
+}
+
+pub fn shorten<'a>(s: &'static str) -> &'a str {
+    unsafe { std::mem::transmute::<&'static str, &'a str>(s) }
+}
+
+pub fn same_lifetime<'a>(bytes: &'a [u8]) -> &'a [u8] {
+    unsafe { std::mem::transmute::<&'a [u8], &'a [u8]>(bytes) }
+}
+
+pub fn outlived<'a, 'b: 'a>(bytes: &'b [u8]) -> &'a [u8] {
+    unsafe { std::mem::transmute(bytes) }
+}
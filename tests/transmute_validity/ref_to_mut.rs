@@ -0,0 +1,12 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["TransmuteValidity"]
+```
+!*/
+
+#![allow(mutable_transmutes)]
+
+pub fn make_mut<T>(value: &T) -> &mut T {
+    unsafe { std::mem::transmute::<&T, &mut T>(value) }
+}
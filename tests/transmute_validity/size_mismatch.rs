@@ -0,0 +1,16 @@
+/*!
+```rudra-test
+test_type = "normal"
+rudra_args = ["-Zrudra-disable-unaligned-access"]
+expected_analyzers = ["TransmuteValidity"]
+```
+!*/
+
+pub fn read_u64(bytes: &[u8; 4]) -> u64 {
+    let ptr = bytes.as_ptr() as *const u64;
+    unsafe { *ptr }
+}
+
+pub fn copy_u64(value: &u32) -> u64 {
+    unsafe { std::mem::transmute_copy::<u32, u64>(value) }
+}
@@ -0,0 +1,21 @@
+/*!
+```rudra-test
+test_type = "normal"
+rudra_args = ["-Zrudra-disable-transmute-validity"]
+expected_analyzers = ["UnalignedAccess"]
+```
+!*/
+
+pub fn read_u32(bytes: &[u8]) -> u32 {
+    assert!(bytes.len() >= 4);
+    unsafe { *(bytes.as_ptr() as *const u32) }
+}
+
+pub fn read_header(bytes: &[u8]) -> u64 {
+    assert!(bytes.len() >= 8);
+    unsafe { std::ptr::read(bytes.as_ptr() as *const u64) }
+}
+
+pub fn as_words(bytes: &[u8]) -> &[u16] {
+    unsafe { std::slice::from_raw_parts(bytes.as_ptr() as *const u16, bytes.len() / 2) }
+}
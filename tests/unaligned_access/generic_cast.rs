@@ -0,0 +1,11 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnalignedAccess"]
+```
+!*/
+
+pub fn read_value<T: Copy>(bytes: &[u8]) -> T {
+    assert!(bytes.len() >= std::mem::size_of::<T>());
+    unsafe { *(bytes.as_ptr() as *const T) }
+}
@@ -0,0 +1,39 @@
+/*!
+```rudra-test
+test_type = "normal"
+rudra_args = ["-Zrudra-disable-transmute-validity"]
+expected_analyzers = []
+```
+!*/
+
+pub fn read_u32(bytes: &[u8]) -> u32 {
+    assert!(bytes.len() >= 4);
+    unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const u32) }
+}
+
+pub fn read_checked(bytes: &[u8]) -> Option<u32> {
+    let ptr = bytes.as_ptr() as *const u32;
+    if bytes.len() >= 4 && ptr.is_aligned() {
+        Some(unsafe { *ptr })
+    } else {
+        None
+    }
+}
+
+pub fn read_masked(bytes: &[u8]) -> Option<u64> {
+    let ptr = bytes.as_ptr();
+    if bytes.len() >= 8 && ptr as usize % 8 == 0 {
+        Some(unsafe { *(ptr as *const u64) })
+    } else {
+        None
+    }
+}
+
+pub fn narrow(words: &[u32]) -> u8 {
+    unsafe { *(words.as_ptr() as *const u8) }
+}
+
+pub fn field_address(bytes: &mut [u8]) -> *const u32 {
+    let ptr = bytes.as_mut_ptr() as *mut u32;
+    unsafe { std::ptr::addr_of!(*ptr) }
+}
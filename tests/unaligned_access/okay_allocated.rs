@@ -0,0 +1,72 @@
+/*!
+```rudra-test
+test_type = "normal"
+rudra_args = ["-Zrudra-disable-transmute-validity"]
+expected_analyzers = []
+```
+!*/
+
+#![feature(allocator_api, slice_ptr_get)]
+
+use std::alloc::{alloc, alloc_zeroed, realloc, Allocator, GlobalAlloc, Layout, System};
+use std::ptr::NonNull;
+
+// Allocators return pointers aligned to the requested layout
+pub fn allocate_counter() -> *mut u64 {
+    unsafe {
+        let ptr = alloc(Layout::new::<u64>()) as *mut u64;
+        *ptr = 1;
+        ptr
+    }
+}
+
+pub fn allocate_zeroed() -> *mut u64 {
+    unsafe {
+        let ptr = alloc_zeroed(Layout::new::<u64>()) as *mut u64;
+        *ptr += 1;
+        ptr
+    }
+}
+
+pub fn allocate_value<T>(value: T) -> *mut T {
+    unsafe {
+        let ptr = alloc(Layout::new::<T>()) as *mut T;
+        *ptr = value;
+        ptr
+    }
+}
+
+pub fn grow(ptr: *mut u64, len: usize) -> *mut u64 {
+    unsafe {
+        let layout = Layout::array::<u64>(len).unwrap();
+        let ptr = realloc(ptr as *mut u8, layout, layout.size() * 2) as *mut u64;
+        *ptr = 0;
+        ptr
+    }
+}
+
+pub fn allocate_with<A: GlobalAlloc>(allocator: &A) -> *mut u32 {
+    unsafe {
+        let ptr = allocator.alloc(Layout::new::<u32>()) as *mut u32;
+        *ptr = 0;
+        ptr
+    }
+}
+
+pub fn allocate_in(layout: Layout) -> u64 {
+    let ptr = System.allocate(layout).unwrap();
+    unsafe { *(ptr.as_mut_ptr() as *mut u64) }
+}
+
+// `NonNull::dangling` and `Box::into_raw` are aligned for their type,
+// even after erasing it to a byte pointer
+pub fn dangling_slice<'a>() -> &'a [u64] {
+    let erased = NonNull::<u64>::dangling().as_ptr() as *const u8;
+    unsafe { std::slice::from_raw_parts(erased as *const u64, 0) }
+}
+
+pub fn erase(value: Box<u64>) -> *mut u8 {
+    let erased = Box::into_raw(value) as *mut u8;
+    unsafe { *(erased as *mut u64) += 1 };
+    erased
+}
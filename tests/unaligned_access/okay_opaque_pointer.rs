@@ -0,0 +1,21 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+use std::os::raw::c_void;
+
+// `*mut c_void` hides the original type of user data handed to C, which keeps its alignment
+pub extern "C" fn increment(data: *mut c_void) {
+    let counter = unsafe { &mut *(data as *mut u64) };
+    *counter += 1;
+}
+
+pub fn user_data<T>(data: *mut c_void) -> T
+where
+    T: Copy,
+{
+    unsafe { *(data as *mut T) }
+}
@@ -0,0 +1,24 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UncheckedPrecondition"]
+```
+!*/
+
+pub struct RawBuffer {
+    ptr: *const u8,
+}
+
+impl RawBuffer {
+    pub fn prefix(&self, len: usize) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr, len) }
+    }
+
+    pub fn byte_at(&self, offset: usize) -> u8 {
+        unsafe { *self.ptr.add(offset) }
+    }
+}
+
+pub fn to_str(bytes: &[u8]) -> &str {
+    unsafe { std::str::from_utf8_unchecked(bytes) }
+}
@@ -0,0 +1,20 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UncheckedPrecondition"]
+```
+!*/
+
+pub struct Buffer<T> {
+    buf: Vec<T>,
+}
+
+impl<T> Buffer<T> {
+    pub fn get(&self, index: usize) -> &T {
+        unsafe { self.buf.get_unchecked(index) }
+    }
+
+    pub fn set_len(&mut self, len: usize) {
+        unsafe { self.buf.set_len(len) }
+    }
+}
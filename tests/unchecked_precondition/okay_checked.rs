@@ -0,0 +1,66 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+pub struct Buffer<T> {
+    buf: Vec<T>,
+}
+
+impl<T> Buffer<T> {
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.buf.len() {
+            Some(unsafe { self.buf.get_unchecked(index) })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_asserted(&self, index: usize) -> &T {
+        assert!(index < self.buf.len());
+        unsafe { self.buf.get_unchecked(index) }
+    }
+
+    pub fn get_wrapping(&self, index: usize) -> &T {
+        unsafe { self.buf.get_unchecked(index % self.buf.len()) }
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        unsafe { self.buf.set_len(len.min(self.buf.len())) }
+    }
+
+    // Callers are responsible for the precondition
+    pub unsafe fn get_unchecked(&self, index: usize) -> &T {
+        self.buf.get_unchecked(index)
+    }
+
+    fn get_private(&self, index: usize) -> &T {
+        unsafe { self.buf.get_unchecked(index) }
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        if self.buf.is_empty() {
+            None
+        } else {
+            Some(self.get_private(0))
+        }
+    }
+}
+
+pub fn to_str(bytes: &[u8]) -> Option<&str> {
+    match std::str::from_utf8(bytes) {
+        Ok(_) => Some(unsafe { std::str::from_utf8_unchecked(bytes) }),
+        Err(_) => None,
+    }
+}
+
+pub fn zeroed_buffer(len: usize) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(len);
+    unsafe {
+        std::ptr::write_bytes(buf.as_mut_ptr(), 0, len);
+        buf.set_len(len);
+    }
+    buf
+}
@@ -0,0 +1,15 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UninitExposure"]
+```
+!*/
+
+use std::io::Read;
+use std::mem::MaybeUninit;
+
+pub fn read_block(reader: &mut dyn Read) -> [u8; 64] {
+    let mut buf: [u8; 64] = unsafe { MaybeUninit::uninit().assume_init() };
+    reader.read(&mut buf).unwrap();
+    buf
+}
@@ -0,0 +1,33 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+use std::io::Read;
+use std::mem::MaybeUninit;
+
+pub fn read_zeroed(reader: &mut dyn Read) -> [u8; 64] {
+    let mut buf: [u8; 64] = unsafe { MaybeUninit::zeroed().assume_init() };
+    reader.read(&mut buf).unwrap();
+    buf
+}
+
+pub fn read_filled(reader: &mut dyn Read, len: usize) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(len);
+    unsafe {
+        buf.set_len(len);
+    }
+    buf.fill(0);
+    reader.read(&mut buf).unwrap();
+    buf
+}
+
+pub fn read_cleared<R: Read>(reader: &mut R, mut buf: Vec<u8>) -> Vec<u8> {
+    unsafe {
+        buf.set_len(0);
+    }
+    reader.read_to_end(&mut buf).unwrap();
+    buf
+}
@@ -0,0 +1,17 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UninitExposure", "UnsafeDataflow"]
+```
+!*/
+
+use std::io::Read;
+
+pub fn read_to_vec<R: Read>(reader: &mut R, len: usize) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(len);
+    unsafe {
+        buf.set_len(len);
+    }
+    reader.read(&mut buf).unwrap();
+    buf
+}
@@ -0,0 +1,10 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["ZeroedValidity"]
+```
+!*/
+
+pub fn zeroed<T>() -> T {
+    unsafe { std::mem::zeroed() }
+}
@@ -0,0 +1,32 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["ZeroedValidity"]
+```
+!*/
+
+use std::alloc::{alloc_zeroed, Layout};
+use std::mem::{self, MaybeUninit};
+use std::num::NonZeroU32;
+use std::ptr::NonNull;
+
+pub enum Direction {
+    North = 1,
+    South = 2,
+}
+
+pub fn zeroed_ref() -> &'static u32 {
+    unsafe { mem::zeroed() }
+}
+
+pub fn zeroed_non_null() -> NonNull<u8> {
+    unsafe { MaybeUninit::zeroed().assume_init() }
+}
+
+pub fn zeroed_fields() -> (u32, NonZeroU32, Box<u8>, fn()) {
+    unsafe { mem::zeroed() }
+}
+
+pub fn zeroed_direction() -> Box<Direction> {
+    unsafe { Box::from_raw(alloc_zeroed(Layout::new::<Direction>()) as *mut Direction) }
+}
@@ -0,0 +1,47 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+use std::mem::{self, MaybeUninit};
+use std::ptr;
+
+#[repr(C)]
+pub struct Header {
+    magic: u32,
+    flags: u16,
+    valid: bool,
+    next: *const Header,
+    callback: Option<fn()>,
+}
+
+pub enum State {
+    Idle = 0,
+    Busy = 1,
+}
+
+pub fn zeroed_header() -> Header {
+    unsafe { mem::zeroed() }
+}
+
+pub fn zeroed_state() -> State {
+    unsafe { MaybeUninit::zeroed().assume_init() }
+}
+
+pub fn zeroed_optional_ref() -> Option<&'static u32> {
+    unsafe { mem::zeroed() }
+}
+
+pub fn zeroed_array() -> [u64; 8] {
+    unsafe { mem::zeroed() }
+}
+
+pub fn null() -> *const u8 {
+    ptr::null()
+}
+
+pub fn zeroed_uninit<T>() -> MaybeUninit<T> {
+    MaybeUninit::zeroed()
+}